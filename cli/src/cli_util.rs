@@ -41,7 +41,9 @@ use jj_lib::git_backend::GitBackend;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::hex_util::to_reverse_hex;
 use jj_lib::id_prefix::IdPrefixContext;
-use jj_lib::matchers::{EverythingMatcher, Matcher, PrefixMatcher, Visit};
+use jj_lib::matchers::{
+    EverythingMatcher, FileGlob, FileGlobsMatcher, Matcher, PrefixMatcher, UnionMatcher, Visit,
+};
 use jj_lib::merged_tree::{MergedTree, MergedTreeBuilder};
 use jj_lib::op_heads_store::{self, OpHeadResolutionError, OpHeadsStore};
 use jj_lib::op_store::{OpStore, OpStoreError, OperationId, WorkspaceId};
@@ -76,6 +78,7 @@ use tracing::instrument;
 use tracing_chrome::ChromeLayerBuilder;
 use tracing_subscriber::prelude::*;
 
+use crate::commit_templater::{self, BranchListItem};
 use crate::config::{
     new_config_path, AnnotatedValue, CommandNameAndArgs, ConfigSource, LayeredConfigs,
 };
@@ -83,8 +86,8 @@ use crate::formatter::{FormatRecorder, Formatter, PlainTextFormatter};
 use crate::merge_tools::{ConflictResolveError, DiffEditError, DiffGenerateError};
 use crate::template_parser::{TemplateAliasesMap, TemplateParseError};
 use crate::templater::Template;
+use crate::text_util;
 use crate::ui::{ColorChoice, Ui};
-use crate::{commit_templater, text_util};
 
 #[derive(Clone, Debug)]
 pub enum CommandError {
@@ -99,6 +102,10 @@ pub enum CommandError {
     ClapCliError(Arc<clap::Error>),
     BrokenPipe,
     InternalError(String),
+    /// Check requested by the user failed. Reported with a distinct exit code
+    /// so scripts can tell it from other errors. An empty message isn't
+    /// printed.
+    CheckFailed(String),
 }
 
 pub fn user_error(message: impl Into<String>) -> CommandError {
@@ -913,6 +920,84 @@ impl WorkspaceCommandHelper {
         RepoPath::parse_fs_path(&self.cwd, self.workspace_root(), input)
     }
 
+    /// Parses `input` as a glob pattern relative to the current directory.
+    ///
+    /// The leading path components without glob metacharacters form the base
+    /// directory of the pattern, which is always matched literally.
+    pub fn parse_file_glob(&self, input: &str) -> Result<FileGlob, CommandError> {
+        let components = input.split(std::path::is_separator).collect_vec();
+        let base_len = components
+            .iter()
+            .position(|component| has_glob_metacharacters(component))
+            .unwrap_or(components.len())
+            .min(components.len() - 1);
+        // Byte offset of the first pattern component, including the separator
+        let base_end: usize = components[..base_len].iter().map(|c| c.len() + 1).sum();
+        let base = match &input[..base_end] {
+            "" => self.parse_file_path(".")?,
+            base => self.parse_file_path(base)?,
+        };
+        let pattern = glob::Pattern::new(&components[base_len..].join("/"))
+            .map_err(|err| user_error(format!("Invalid glob pattern `{input}`: {err}")))?;
+        // File systems are usually case-insensitive on Windows and macOS.
+        let case_sensitive = self
+            .settings
+            .config()
+            .get_bool("ui.path-glob-case-sensitive")
+            .optional()?
+            .unwrap_or(!cfg!(any(windows, target_os = "macos")));
+        Ok(FileGlob {
+            base,
+            pattern,
+            case_sensitive,
+        })
+    }
+
+    /// Parses path arguments into literal path prefixes and glob patterns.
+    pub fn parse_file_patterns(
+        &self,
+        values: &[String],
+        glob_args: &PathGlobArgs,
+    ) -> Result<(Vec<RepoPath>, Vec<FileGlob>), CommandError> {
+        let mut paths = vec![];
+        let mut globs = vec![];
+        for value in values {
+            let is_glob = if glob_args.glob {
+                true
+            } else if glob_args.no_glob {
+                false
+            } else {
+                has_glob_metacharacters(value)
+            };
+            if is_glob {
+                globs.push(self.parse_file_glob(value)?);
+            } else {
+                paths.push(self.parse_file_path(value)?);
+            }
+        }
+        Ok((paths, globs))
+    }
+
+    /// Like `matcher_from_values()`, but path arguments may also be glob
+    /// patterns.
+    pub fn matcher_from_path_args(
+        &self,
+        values: &[String],
+        glob_args: &PathGlobArgs,
+    ) -> Result<Box<dyn Matcher>, CommandError> {
+        let (paths, globs) = self.parse_file_patterns(values, glob_args)?;
+        if globs.is_empty() {
+            self.matcher_from_values(values)
+        } else if paths.is_empty() {
+            Ok(Box::new(FileGlobsMatcher::new(&globs)))
+        } else {
+            Ok(Box::new(UnionMatcher::new(
+                PrefixMatcher::new(&paths),
+                FileGlobsMatcher::new(&globs),
+            )))
+        }
+    }
+
     pub fn matcher_from_values(&self, values: &[String]) -> Result<Box<dyn Matcher>, CommandError> {
         if values.is_empty() {
             Ok(Box::new(EverythingMatcher))
@@ -1210,6 +1295,21 @@ Set which revision the branch points to with `jj branch set {branch_name} -r <RE
         Ok(template)
     }
 
+    pub fn parse_branch_list_template(
+        &self,
+        template_text: &str,
+    ) -> Result<Box<dyn Template<BranchListItem> + '_>, CommandError> {
+        let id_prefix_context = self.id_prefix_context()?;
+        let template = commit_templater::parse_branch_list(
+            self.repo().as_ref(),
+            self.workspace_id(),
+            id_prefix_context,
+            template_text,
+            &self.template_aliases_map,
+        )?;
+        Ok(template)
+    }
+
     /// Returns one-line summary of the given `commit`.
     pub fn format_commit_summary(&self, commit: &Commit) -> String {
         let mut output = Vec::new();
@@ -1240,16 +1340,9 @@ Set which revision the branch points to with `jj branch set {branch_name} -r <RE
         Ok(())
     }
 
-    pub fn check_rewritable<'a>(
-        &self,
-        commits: impl IntoIterator<Item = &'a Commit>,
-    ) -> Result<(), CommandError> {
-        let to_rewrite_revset = RevsetExpression::commits(
-            commits
-                .into_iter()
-                .map(|commit| commit.id().clone())
-                .collect(),
-        );
+    /// Parses the set of immutable commits, which are the ancestors of
+    /// `immutable_heads()` and the root commit.
+    pub fn immutable_revset_expression(&self) -> Result<Rc<RevsetExpression>, CommandError> {
         let (params, immutable_heads_str) = self
             .revset_aliases_map
             .get_function("immutable_heads")
@@ -1260,11 +1353,24 @@ Set which revision the branch points to with `jj branch set {branch_name} -r <RE
             ));
         }
         let immutable_heads_revset = self.parse_revset(immutable_heads_str, None)?;
-        let immutable_revset = immutable_heads_revset
+        Ok(immutable_heads_revset
             .ancestors()
             .union(&RevsetExpression::commit(
                 self.repo().store().root_commit_id().clone(),
-            ));
+            )))
+    }
+
+    pub fn check_rewritable<'a>(
+        &self,
+        commits: impl IntoIterator<Item = &'a Commit>,
+    ) -> Result<(), CommandError> {
+        let to_rewrite_revset = RevsetExpression::commits(
+            commits
+                .into_iter()
+                .map(|commit| commit.id().clone())
+                .collect(),
+        );
+        let immutable_revset = self.immutable_revset_expression()?;
         let revset = self.evaluate_revset(to_rewrite_revset.intersection(&immutable_revset))?;
         if let Some(commit) = revset.iter().commits(self.repo().store()).next() {
             let commit = commit?;
@@ -1427,10 +1533,16 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
         WorkspaceCommandTransaction { helper: self, tx }
     }
 
-    fn finish_transaction(&mut self, ui: &mut Ui, mut tx: Transaction) -> Result<(), CommandError> {
+    /// Commits the transaction, returning the id of the new operation, or
+    /// `None` if there was nothing to commit.
+    fn finish_transaction(
+        &mut self,
+        ui: &mut Ui,
+        mut tx: Transaction,
+    ) -> Result<Option<OperationId>, CommandError> {
         if !tx.mut_repo().has_changes() {
             writeln!(ui.stderr(), "Nothing changed.")?;
-            return Ok(());
+            return Ok(None);
         }
         let num_rebased = tx.mut_repo().rebase_descendants(&self.settings)?;
         if num_rebased > 0 {
@@ -1458,6 +1570,8 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
             print_failed_git_export(ui, &failed_branches)?;
         }
         self.user_repo = ReadonlyUserRepo::new(tx.commit());
+        let op_id = self.repo().op_id().clone();
+        tracing::info!(op_id = op_id.hex(), "created operation");
         if self.may_update_working_copy {
             if let Some(new_commit) = &maybe_new_wc_commit {
                 self.update_working_copy(ui, maybe_old_wc_commit.as_ref(), new_commit)?;
@@ -1475,7 +1589,7 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
   jj config set --user user.email "someone@example.com""#
             )?;
         }
-        Ok(())
+        Ok(Some(op_id))
     }
 }
 
@@ -1601,6 +1715,13 @@ impl WorkspaceCommandTransaction<'_> {
     }
 
     pub fn finish(self, ui: &mut Ui) -> Result<(), CommandError> {
+        self.helper.finish_transaction(ui, self.tx)?;
+        Ok(())
+    }
+
+    /// Like `finish()`, but also returns the id of the operation created by
+    /// the transaction, or `None` if nothing changed.
+    pub fn finish_with_operation(self, ui: &mut Ui) -> Result<Option<OperationId>, CommandError> {
         self.helper.finish_transaction(ui, self.tx)
     }
 
@@ -2405,6 +2526,25 @@ pub fn join_message_paragraphs(paragraphs: &[String]) -> String {
         .join("\n")
 }
 
+/// Options controlling whether path arguments are parsed as glob patterns
+#[derive(clap::Args, Clone, Debug, Default)]
+pub struct PathGlobArgs {
+    /// Parse all path arguments as glob patterns
+    ///
+    /// By default, only the path arguments containing any of the glob
+    /// metacharacters `*?[` are parsed as glob patterns.
+    #[arg(long, conflicts_with = "no_glob")]
+    pub glob: bool,
+    /// Parse all path arguments as literal paths, even if they contain glob
+    /// metacharacters
+    #[arg(long)]
+    pub no_glob: bool,
+}
+
+fn has_glob_metacharacters(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
 #[derive(Clone, Debug)]
 pub struct RevisionArg(String);
 
@@ -2618,6 +2758,7 @@ pub fn parse_args(
 }
 
 const BROKEN_PIPE_EXIT_CODE: u8 = 3;
+const CHECK_FAILED_EXIT_CODE: u8 = 4;
 
 pub fn handle_command_result(
     ui: &mut Ui,
@@ -2679,6 +2820,12 @@ pub fn handle_command_result(
             writeln!(ui.error(), "Internal error: {message}")?;
             Ok(ExitCode::from(255))
         }
+        Err(CommandError::CheckFailed(message)) => {
+            if !message.is_empty() {
+                writeln!(ui.error(), "Error: {message}")?;
+            }
+            Ok(ExitCode::from(CHECK_FAILED_EXIT_CODE))
+        }
     }
 }
 
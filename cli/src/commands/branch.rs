@@ -1,24 +1,36 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
-use std::io::Write as _;
+use std::io::{self, IsTerminal as _, Write as _};
+use std::iter;
+use std::ops::Deref;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use clap::builder::NonEmptyStringValueParser;
 use itertools::Itertools;
-use jj_lib::backend::{CommitId, ObjectId};
+use jj_lib::backend::{BackendError, CommitId, ObjectId, Signature};
+use jj_lib::commit::Commit;
 use jj_lib::git;
-use jj_lib::op_store::{RefTarget, RemoteRef};
-use jj_lib::repo::Repo;
-use jj_lib::revset::{self, RevsetExpression};
+use jj_lib::op_store::{BranchTarget, RefTarget, RemoteRef};
+use jj_lib::operation::{self, Operation};
+use jj_lib::repo::{MutableRepo, Repo};
+use jj_lib::revset::{self, RevsetExpression, RevsetIteratorExt as _};
+use jj_lib::settings::ConfigResultExt as _;
+use jj_lib::store::Store;
 use jj_lib::str_util::StringPattern;
 use jj_lib::view::View;
+use rayon::prelude::*;
 
 use crate::cli_util::{
-    parse_string_pattern, user_error, user_error_with_hint, CommandError, CommandHelper,
-    RevisionArg,
+    parse_string_pattern, short_commit_hash, short_operation_hash, user_error,
+    user_error_with_hint, CommandError, CommandHelper, RevisionArg, WorkspaceCommandHelper,
+    WorkspaceCommandTransaction,
 };
-use crate::commands::make_branch_term;
+use crate::commands::{edit_description, make_branch_term};
+use crate::commit_templater::BranchListItem;
 use crate::formatter::Formatter;
+use crate::text_util;
+use crate::time_util::{format_absolute_timestamp, parse_duration};
 use crate::ui::Ui;
 
 /// Manage branches.
@@ -31,10 +43,15 @@ pub enum BranchSubcommand {
     Create(BranchCreateArgs),
     #[command(visible_alias("d"))]
     Delete(BranchDeleteArgs),
+    Describe(BranchDescribeArgs),
+    Diff(BranchDiffArgs),
+    Exists(BranchExistsArgs),
     #[command(visible_alias("f"))]
     Forget(BranchForgetArgs),
     #[command(visible_alias("l"))]
     List(BranchListArgs),
+    #[command(visible_alias("m"))]
+    Move(BranchMoveArgs),
     #[command(visible_alias("s"))]
     Set(BranchSetArgs),
     Track(BranchTrackArgs),
@@ -45,12 +62,47 @@ pub enum BranchSubcommand {
 #[derive(clap::Args, Clone, Debug)]
 pub struct BranchCreateArgs {
     /// The branch's target revision.
+    ///
+    /// If given once for each branch, the branches are created at the
+    /// revisions in the same order, e.g.
+    /// `jj branch create rel-1 rel-2 -r @ -r @-`.
+    #[arg(long, short)]
+    revision: Vec<RevisionArg>,
+
+    /// Resolve the target revision as of the given operation
+    ///
+    /// This can be used to create a branch pointing to a commit that existed at
+    /// a past operation. Unlike the global `--at-operation` option, the branch
+    /// is created on top of the current operation.
+    #[arg(long, value_name = "OPERATION")]
+    resolve_at_op: Option<String>,
+
+    /// Allow branch names that can't be exported to Git
+    #[arg(long, visible_alias = "allow-invalid-name")]
+    allow_nonstandard: bool,
+
+    /// Don't ask for confirmation when many branches would be created
+    #[arg(long, short)]
+    yes: bool,
+
+    /// Print the branches that would be created without creating them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Don't print the number of created branches
     #[arg(long, short)]
-    revision: Option<RevisionArg>,
+    quiet: bool,
+
+    /// Skip the branches that already exist instead of failing
+    #[arg(long)]
+    if_not_exists: bool,
 
     /// The branches to create.
     #[arg(required = true, value_parser=NonEmptyStringValueParser::new())]
     names: Vec<String>,
+
+    #[command(flatten)]
+    operation: BranchOperationArgs,
 }
 
 /// Delete an existing branch and propagate the deletion to remotes on the
@@ -59,22 +111,54 @@ pub struct BranchCreateArgs {
 pub struct BranchDeleteArgs {
     /// The branches to delete
     ///
-    /// By default, the specified name matches exactly. Use `glob:` prefix to
-    /// select branches by wildcard pattern. For details, see
+    /// By default, the specified name matches exactly. Use `glob:` or `regex:`
+    /// prefix to select branches by wildcard pattern or regular expression.
+    /// For details, see
     /// https://github.com/martinvonz/jj/blob/main/docs/revsets.md#string-patterns.
+    ///
+    /// A tracking remote branch can be given as `NAME@REMOTE` to delete it
+    /// only on that remote on the next push. The local branch is kept.
     #[arg(required_unless_present_any(&["glob"]), value_parser = parse_string_pattern)]
     pub names: Vec<StringPattern>,
 
     /// Deprecated. Please prefix the pattern with `glob:` instead.
     #[arg(long, hide = true, value_parser = StringPattern::glob)]
     pub glob: Vec<StringPattern>,
+
+    /// Delete only the local branches, and leave the remote branches intact
+    ///
+    /// The remote branches are untracked so that the next `jj git push` won't
+    /// delete them on the remotes. Unlike `jj branch forget`, which also
+    /// forgets the remote branches so they will be recreated on the next
+    /// fetch, the remote branches stay visible as `name@remote`.
+    #[arg(long)]
+    pub retain_remote: bool,
+
+    /// Don't ask for confirmation when many branches would be deleted
+    #[arg(long, short)]
+    pub yes: bool,
+
+    /// Print the branches that would be deleted without deleting them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Don't print the number of deleted branches
+    #[arg(long, short)]
+    pub quiet: bool,
+
+    #[command(flatten)]
+    operation: BranchOperationArgs,
 }
 
 /// List branches and their targets
 ///
-/// By default, a tracking remote branch will be included only if its target is
-/// different from the local target. A non-tracking remote branch won't be
-/// listed. For a conflicted branch (both local and remote), old target
+/// A branch marked with `*` would be created, moved, or deleted on a remote by
+/// the next `jj git push`.
+///
+/// By default, every tracking remote branch is included, and the ones whose
+/// targets are the same as the local target are marked as `(in sync)`. The
+/// underlying Git repo is only listed if it's out of sync. A non-tracking
+/// remote branch won't be listed. For a conflicted branch (both local and remote), old target
 /// revisions are preceded by a "-" and new target revisions are preceded by a
 /// "+".
 ///
@@ -82,17 +166,214 @@ pub struct BranchDeleteArgs {
 /// https://github.com/martinvonz/jj/blob/main/docs/branches.md.
 #[derive(clap::Args, Clone, Debug)]
 pub struct BranchListArgs {
-    /// Show all tracking and non-tracking remote branches including the ones
-    /// whose targets are synchronized with the local branches.
+    /// Show all tracking and non-tracking remote branches including the
+    /// underlying Git repo even if it's synchronized with the local branches.
     #[arg(long, short, conflicts_with = "revisions")]
     all: bool,
 
+    /// Show only the local branches, omitting all remote branches
+    #[arg(long, conflicts_with_all = ["all", "remotes"])]
+    local_only: bool,
+
+    /// Show branches whose names match the given patterns
+    ///
+    /// By default, the specified name matches exactly. Use `glob:` or `regex:`
+    /// prefix to select branches by wildcard pattern or regular expression.
+    /// For details, see
+    /// https://github.com/martinvonz/jj/blob/main/docs/revsets.md#string-patterns.
+    ///
+    /// Branches selected by name or by `--revisions` are both listed.
+    #[arg(value_parser = parse_string_pattern)]
+    names: Vec<StringPattern>,
+
     /// Show branches whose local targets are in the given revisions.
     ///
     /// Note that `-r deleted_branch` will not work since `deleted_branch`
     /// wouldn't have a local target.
     #[arg(long, short)]
     revisions: Vec<RevisionArg>,
+
+    /// Show only branches that need attention
+    ///
+    /// These are branches with conflicted local or remote targets, and
+    /// branches whose tracking remote targets have diverged from the local
+    /// targets.
+    #[arg(long)]
+    conflicted: bool,
+
+    /// Exit with status 4 if any of the listed branches is conflicted
+    ///
+    /// The branches are listed as usual.
+    #[arg(long)]
+    check: bool,
+
+    /// Show only branches that exist on the given remotes
+    ///
+    /// Remote branches of the other remotes are omitted from the output, and
+    /// the remote branches of the given remotes are shown even if they are in
+    /// sync with the local branches.
+    #[arg(long = "remote", value_name = "REMOTE")]
+    remotes: Vec<String>,
+
+    /// Show only branches whose local targets contain the given revision
+    ///
+    /// A branch contains the revision if its target is the revision or a
+    /// descendant of it. A conflicted branch is shown if any of its targets
+    /// contains the revision.
+    #[arg(long, value_name = "REVISION")]
+    contains: Option<RevisionArg>,
+
+    /// Show only branches whose local targets are ancestors of the given
+    /// revision
+    ///
+    /// This is useful to find feature branches that have been merged into the
+    /// trunk. A conflicted branch is shown only if all of its targets have
+    /// been merged.
+    #[arg(long, value_name = "REVISION")]
+    merged: Option<RevisionArg>,
+
+    /// Show only branches whose local targets aren't ancestors of the given
+    /// revision
+    #[arg(long, value_name = "REVISION")]
+    no_merged: Option<RevisionArg>,
+
+    /// Show only branches that exist on some remote
+    #[arg(long, conflicts_with = "untracked")]
+    tracked: bool,
+
+    /// Show only branches that don't exist on any remote
+    ///
+    /// The local Git repository isn't considered a remote.
+    #[arg(long)]
+    untracked: bool,
+
+    /// Don't show deleted branches
+    ///
+    /// A branch is omitted if it has no local target and all of its remote
+    /// branches are tracked, i.e. it would only be listed to report the
+    /// pending deletion. Branches that still have untracked remote branches
+    /// are shown as usual.
+    #[arg(long)]
+    hide_deleted: bool,
+
+    /// Output format
+    ///
+    /// `json` prints an array of objects with `name`, `local_target`,
+    /// `conflicted`, and `remotes` fields. `local_target` is the commit id,
+    /// null if the branch is deleted, or an object with `adds` and `removes`
+    /// commit id lists if the branch is conflicted. `remotes` maps each
+    /// remote name to its `target` (in the same form), `tracking` state, and
+    /// the number of commits it is `ahead` or `behind` the local target.
+    #[arg(long, value_enum, default_value_t = BranchListFormat::Text)]
+    format: BranchListFormat,
+
+    /// Render each listed local and remote branch using the given template
+    ///
+    /// The following keywords are available: `name`, `remote` (empty for
+    /// local branches), `target` (the commit the branch points to),
+    /// `remotes` (the remotes the branch exists on), `conflicted`, and
+    /// `deleted`. For details, see
+    /// https://github.com/martinvonz/jj/blob/main/docs/templates.md.
+    #[arg(long, short = 'T', conflicts_with = "format")]
+    template: Option<String>,
+
+    /// Print only the names of the listed branches, one per line
+    #[arg(long, conflicts_with_all = ["format", "template"])]
+    names_only: bool,
+
+    /// Show the last operation that changed each local branch
+    ///
+    /// Only the most recent 1000 operations are searched.
+    #[arg(long, conflicts_with_all = ["format", "template", "names_only"])]
+    last_op: bool,
+
+    /// Show how far each local branch is ahead of and behind the given
+    /// revisions
+    ///
+    /// If no revisions are given, the `revsets.trunk` config is used, which
+    /// defaults to `trunk()`.
+    #[arg(
+        long,
+        value_name = "REVSETS",
+        num_args = 0..=1,
+        require_equals = true,
+        conflicts_with_all = ["format", "template", "names_only"],
+    )]
+    base: Option<Option<RevisionArg>>,
+
+    /// Sort branches by the given key
+    ///
+    /// Possible keys are `name`, `committer-date`, and `author-date`. Prefix
+    /// the key with `-` to sort in descending order, e.g. `-committer-date` to
+    /// list the most recently updated branches first. Dates are taken from the
+    /// local target commits. Conflicted branches are sorted by the newest of
+    /// their target commits, and deleted branches are listed last. Branches
+    /// with the same date are sorted by name.
+    #[arg(long, value_parser = parse_branch_list_sort_key, allow_hyphen_values = true)]
+    sort: Option<BranchListSortKey>,
+
+    /// List branches in reverse order
+    ///
+    /// The whole ordering is reversed after `--sort` is applied, including
+    /// the name order of branches with the same date and the position of
+    /// deleted branches. Without `--sort`, branches are listed by name in
+    /// descending order.
+    #[arg(long)]
+    reverse: bool,
+
+    /// Show only branches whose local targets haven't been committed to for
+    /// the given duration
+    ///
+    /// The duration is a number followed by a unit: `m` (minutes), `h`
+    /// (hours), `d` (days), or `w` (weeks), e.g. `30d` or `2w`. A branch is
+    /// stale if the committer dates of all of its local target commits are
+    /// older than that. Deleted branches are never stale.
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    stale: Option<std::time::Duration>,
+
+    /// Print only the number of listed branches that have a local target
+    ///
+    /// With `--all`, the number of remote branches of the listed branches is
+    /// printed on a second line. The local Git repository isn't counted as a
+    /// remote.
+    #[arg(
+        long,
+        conflicts_with_all = ["format", "template", "names_only", "last_op", "base"],
+    )]
+    count: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum BranchListFormat {
+    /// Human-readable output
+    Text,
+    /// Machine-readable JSON output
+    Json,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum BranchListSort {
+    /// Sort by branch name
+    Name,
+    /// Sort by committer date of the target commit
+    CommitterDate,
+    /// Sort by author date of the target commit
+    AuthorDate,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct BranchListSortKey {
+    kind: BranchListSort,
+    descending: bool,
+}
+
+fn parse_branch_list_sort_key(src: &str) -> Result<BranchListSortKey, String> {
+    let (descending, kind) = match src.strip_prefix('-') {
+        Some(kind) => (true, kind),
+        None => (false, src),
+    };
+    let kind = <BranchListSort as clap::ValueEnum>::from_str(kind, false)?;
+    Ok(BranchListSortKey { kind, descending })
 }
 
 /// Forget everything about a branch, including its local and remote
@@ -104,15 +385,91 @@ pub struct BranchListArgs {
 pub struct BranchForgetArgs {
     /// The branches to forget
     ///
-    /// By default, the specified name matches exactly. Use `glob:` prefix to
-    /// select branches by wildcard pattern. For details, see
+    /// By default, the specified name matches exactly. Use `glob:` or `regex:`
+    /// prefix to select branches by wildcard pattern or regular expression.
+    /// For details, see
     /// https://github.com/martinvonz/jj/blob/main/docs/revsets.md#string-patterns.
-    #[arg(required_unless_present_any(&["glob"]), value_parser = parse_string_pattern)]
+    ///
+    /// A glob pattern starting with `!`, e.g. `glob:!wip/keep-me`, excludes
+    /// the matching branches from the ones selected by the other patterns. A
+    /// branch whose name starts with `!` can be selected by its exact name.
+    #[arg(required_unless_present_any(&["glob", "at"]), value_parser = parse_string_pattern)]
     pub names: Vec<StringPattern>,
 
     /// Deprecated. Please prefix the pattern with `glob:` instead.
+    ///
+    /// A leading `!` excludes the matching branches.
     #[arg(long, hide = true, value_parser = StringPattern::glob)]
     pub glob: Vec<StringPattern>,
+
+    /// Also forget the branches pointing to this revision
+    ///
+    /// A branch is selected if its local target or any of its remote targets
+    /// points to the revision, including conflicted targets.
+    #[arg(long)]
+    pub at: Option<RevisionArg>,
+
+    /// Print the branches that would be forgotten without forgetting them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Don't print the number of forgotten branches
+    #[arg(long, short)]
+    pub quiet: bool,
+
+    /// Forget only the local branches, and keep the remote branches
+    ///
+    /// The remote branches are untracked so that the next `jj git push` won't
+    /// delete them on the remotes.
+    #[arg(long)]
+    pub keep_remotes: bool,
+
+    /// Don't ask for confirmation when many branches would be forgotten
+    #[arg(long, short)]
+    pub yes: bool,
+
+    /// Also match the patterns against remote branches as `NAME@REMOTE`
+    ///
+    /// A matching remote branch is forgotten without forgetting the local
+    /// branch, e.g. `'glob:*@origin'` forgets all branches of `origin`. The
+    /// Git-tracking branches (`NAME@git`) aren't matched.
+    #[arg(long)]
+    pub include_remotes: bool,
+
+    #[command(flatten)]
+    operation: BranchOperationArgs,
+}
+
+/// Move existing branches forward to a descendant revision
+///
+/// Without `--to`, each branch is moved to the tip of its chain of descendants.
+/// This fails if the descendants of the branch have diverged.
+///
+/// If no branch names are given, the branches pointing to the `--from`
+/// revision are moved.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BranchMoveArgs {
+    /// Move the branches pointing to this revision [default: @-]
+    #[arg(long, conflicts_with = "names")]
+    pub from: Option<RevisionArg>,
+
+    /// Move the branches to this revision instead of the tip of descendants.
+    #[arg(long)]
+    pub to: Option<RevisionArg>,
+
+    /// Allow moving the branch backwards or sideways.
+    #[arg(long, short = 'B')]
+    pub allow_backwards: bool,
+
+    /// Also move conflicted branches.
+    #[arg(long)]
+    pub include_conflicted: bool,
+
+    /// The branches to move.
+    pub names: Vec<String>,
+
+    #[command(flatten)]
+    operation: BranchOperationArgs,
 }
 
 /// Update a given branch to point to a certain commit.
@@ -122,13 +479,101 @@ pub struct BranchSetArgs {
     #[arg(long, short)]
     pub revision: Option<RevisionArg>,
 
+    /// Reset each branch to the target of the same branch on the given remote
+    #[arg(long, value_name = "REMOTE", conflicts_with = "revision")]
+    pub to_remote: Option<String>,
+
+    /// Move each branch to the parent of its current target
+    ///
+    /// This is an error if the current target is a merge commit.
+    #[arg(long, conflicts_with_all = ["revision", "to_remote"])]
+    pub to_parent: bool,
+
     /// Allow moving the branch backwards or sideways.
     #[arg(long, short = 'B')]
     pub allow_backwards: bool,
 
+    /// Create branches that don't exist yet.
+    #[arg(long)]
+    pub allow_new: bool,
+
+    /// Allow creating branches with names that can't be exported to Git
+    #[arg(long, requires = "allow_new", visible_alias = "allow-invalid-name")]
+    pub allow_nonstandard: bool,
+
+    /// Don't ask for confirmation when many branches would be updated
+    #[arg(long, short)]
+    pub yes: bool,
+
+    /// Print the branches that would be updated without updating them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Don't print the number of updated branches
+    #[arg(long, short)]
+    pub quiet: bool,
+
     /// The branches to update.
+    ///
+    /// A branch can be given its own target as `NAME=REVISION`. Otherwise, it
+    /// is updated to point to `--revision` or `--to-remote`.
     #[arg(required = true)]
     pub names: Vec<String>,
+
+    #[command(flatten)]
+    operation: BranchOperationArgs,
+}
+
+/// Set the description of a branch
+///
+/// The description is a note about the purpose of the branch, which is shown
+/// by `jj branch list`. Starts an editor to edit the description unless
+/// `--message` is given. An empty description removes it.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BranchDescribeArgs {
+    /// The branch to describe
+    #[arg(value_parser = NonEmptyStringValueParser::new())]
+    name: String,
+
+    /// The description to use (don't open an editor)
+    #[arg(long, short)]
+    message: Option<String>,
+
+    #[command(flatten)]
+    operation: BranchOperationArgs,
+}
+
+/// Show the commits that are in one branch but not in another
+///
+/// This lists the commits reachable from the local target of branch `A` but
+/// not from the one of branch `B`, like `jj log -r 'B..A'`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BranchDiffArgs {
+    /// The branch whose commits to show
+    #[arg(value_name = "A")]
+    a: String,
+
+    /// The branch to compare against
+    #[arg(value_name = "B")]
+    b: String,
+
+    /// Also show the commits that are in `B` but not in `A`
+    #[arg(long)]
+    both: bool,
+}
+
+/// Check whether a branch exists
+///
+/// Nothing is printed. The exit status is 0 if the local branch exists, and 4
+/// if it doesn't, e.g. because it was deleted.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BranchExistsArgs {
+    /// The branch to look up
+    name: String,
+
+    /// Check the remote branch of the given remote instead
+    #[arg(long, value_name = "REMOTE")]
+    remote: Option<String>,
 }
 
 /// Start tracking given remote branches
@@ -140,11 +585,15 @@ pub struct BranchSetArgs {
 pub struct BranchTrackArgs {
     /// Remote branches to track
     ///
-    /// By default, the specified name matches exactly. Use `glob:` prefix to
-    /// select branches by wildcard pattern. For details, see
+    /// By default, the specified name matches exactly. Use `glob:` or `regex:`
+    /// prefix to select branches by wildcard pattern or regular expression.
+    /// For details, see
     /// https://github.com/martinvonz/jj/blob/main/docs/revsets.md#string-patterns.
     #[arg(required = true)]
     pub names: Vec<RemoteBranchNamePattern>,
+
+    #[command(flatten)]
+    operation: BranchOperationArgs,
 }
 
 /// Stop tracking given remote branches
@@ -155,11 +604,27 @@ pub struct BranchTrackArgs {
 pub struct BranchUntrackArgs {
     /// Remote branches to untrack
     ///
-    /// By default, the specified name matches exactly. Use `glob:` prefix to
-    /// select branches by wildcard pattern. For details, see
+    /// By default, the specified name matches exactly. Use `glob:` or `regex:`
+    /// prefix to select branches by wildcard pattern or regular expression.
+    /// For details, see
     /// https://github.com/martinvonz/jj/blob/main/docs/revsets.md#string-patterns.
     #[arg(required = true)]
     pub names: Vec<RemoteBranchNamePattern>,
+
+    #[command(flatten)]
+    operation: BranchOperationArgs,
+}
+
+/// Options shared by the subcommands that modify branches
+#[derive(clap::Args, Clone, Debug)]
+pub struct BranchOperationArgs {
+    /// Print the id of the operation created by the command
+    ///
+    /// The full operation id is printed to stdout, so scripts can capture it
+    /// and later pass it to `jj op restore` or `jj op undo`. Nothing is printed
+    /// if nothing changed.
+    #[arg(long)]
+    print_op_id: bool,
 }
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -231,7 +696,11 @@ pub fn cmd_branch(
     match subcommand {
         BranchSubcommand::Create(sub_args) => cmd_branch_create(ui, command, sub_args),
         BranchSubcommand::Set(sub_args) => cmd_branch_set(ui, command, sub_args),
+        BranchSubcommand::Move(sub_args) => cmd_branch_move(ui, command, sub_args),
         BranchSubcommand::Delete(sub_args) => cmd_branch_delete(ui, command, sub_args),
+        BranchSubcommand::Describe(sub_args) => cmd_branch_describe(ui, command, sub_args),
+        BranchSubcommand::Diff(sub_args) => cmd_branch_diff(ui, command, sub_args),
+        BranchSubcommand::Exists(sub_args) => cmd_branch_exists(ui, command, sub_args),
         BranchSubcommand::Forget(sub_args) => cmd_branch_forget(ui, command, sub_args),
         BranchSubcommand::Track(sub_args) => cmd_branch_track(ui, command, sub_args),
         BranchSubcommand::Untrack(sub_args) => cmd_branch_untrack(ui, command, sub_args),
@@ -239,6 +708,179 @@ pub fn cmd_branch(
     }
 }
 
+/// Finishes a transaction that modified branches, and prints the id of the
+/// created operation if `--print-op-id` was given.
+fn finish_branch_transaction(
+    ui: &mut Ui,
+    tx: WorkspaceCommandTransaction,
+    args: &BranchOperationArgs,
+) -> Result<(), CommandError> {
+    let op_id = tx.finish_with_operation(ui)?;
+    if let Some(op_id) = op_id.filter(|_| args.print_op_id) {
+        writeln!(ui.stdout(), "{}", op_id.hex())?;
+    }
+    Ok(())
+}
+
+/// Asks the user to confirm the change if it would affect more than
+/// `branches.confirm-threshold` branches. Returns false if the user declined.
+///
+/// If the terminal isn't interactive, non-`destructive` changes are applied
+/// without confirmation, whereas destructive changes require `--yes`.
+fn confirm_branch_changes(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    action: &str,
+    branch_names: &[impl AsRef<str>],
+    yes: bool,
+    destructive: bool,
+) -> Result<bool, CommandError> {
+    let threshold = command
+        .settings()
+        .config()
+        .get::<usize>("branches.confirm-threshold")?;
+    if yes || branch_names.len() <= threshold {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        if destructive {
+            return Err(user_error_with_hint(
+                format!(
+                    "Refusing to {} {} branches without confirmation",
+                    action.to_lowercase(),
+                    branch_names.len()
+                ),
+                "Use --yes to confirm the change non-interactively.",
+            ));
+        }
+        return Ok(true);
+    }
+    writeln!(
+        ui.stderr(),
+        "{action} the following {} branches:",
+        branch_names.len()
+    )?;
+    for name in branch_names {
+        writeln!(ui.stderr(), "  {}", name.as_ref())?;
+    }
+    if ui.prompt_yes_no("Continue?", Some(false))? {
+        Ok(true)
+    } else {
+        writeln!(ui.stderr(), "Nothing changed.")?;
+        Ok(false)
+    }
+}
+
+/// Prints the planned change of each local branch as `name: old -> new`
+/// without modifying the repo. Used by the `--dry-run` mode of the mutating
+/// subcommands.
+fn print_branch_changes(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    changes: &[(&str, RefTarget)],
+) -> Result<(), CommandError> {
+    let view = workspace_command.repo().view();
+    let changes = changes
+        .iter()
+        .map(|(branch_name, new_target)| {
+            let old_target = view.get_local_branch(branch_name);
+            (branch_name.to_string(), old_target, new_target.clone())
+        })
+        .collect_vec();
+    print_ref_changes(ui, workspace_command, &changes)
+}
+
+/// Like `print_branch_changes()`, but the old target of each change is given
+/// so that remote branches can be printed as well.
+fn print_ref_changes(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    changes: &[(String, &RefTarget, RefTarget)],
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo();
+    let mut formatter = ui.stdout_formatter();
+    let formatter = formatter.as_mut();
+    let write_target = |formatter: &mut dyn Formatter, target: &RefTarget| {
+        if let Some(id) = target.as_normal() {
+            let commit = repo.store().get_commit(id)?;
+            workspace_command.write_commit_summary(formatter, &commit)?;
+        } else if target.has_conflict() {
+            write!(formatter.labeled("conflict"), "(conflicted)")?;
+        } else {
+            write!(formatter, "(absent)")?;
+        }
+        Ok::<_, CommandError>(())
+    };
+    for (branch_name, old_target, new_target) in changes {
+        write!(formatter.labeled("branch"), "{branch_name}")?;
+        write!(formatter, ": ")?;
+        write_target(formatter, old_target)?;
+        write!(formatter, " -> ")?;
+        write_target(formatter, new_target)?;
+        writeln!(formatter)?;
+    }
+    Ok(())
+}
+
+/// Warns if more than one branch is about to be changed, listing their names so
+/// an unintended name stands out.
+fn warn_multiple_branches(ui: &Ui, action: &str, branch_names: &[&str]) -> io::Result<()> {
+    if branch_names.len() > 1 {
+        writeln!(
+            ui.warning(),
+            "warning: {action} multiple branches ({}): {}",
+            branch_names.len(),
+            branch_names.join(", ")
+        )?;
+    }
+    Ok(())
+}
+
+/// Prints the number of changed branches, e.g. `Deleted 3 branches.`, unless
+/// `quiet` is set.
+fn print_branch_count(ui: &Ui, action: &str, count: usize, quiet: bool) -> io::Result<()> {
+    if !quiet {
+        let noun = if count == 1 { "branch" } else { "branches" };
+        writeln!(ui.stderr(), "{action} {count} {noun}.")?;
+    }
+    Ok(())
+}
+
+/// Checks that the new branch name can be exported to Git and pushed.
+fn validate_branch_name(branch_name: &str) -> Result<(), CommandError> {
+    let problem = if branch_name.contains("..") {
+        "must not contain `..`"
+    } else if branch_name.contains("@{") {
+        "must not contain `@{`"
+    } else if branch_name.starts_with('/') || branch_name.ends_with('/') {
+        "must not start or end with `/`"
+    } else if branch_name.contains(' ') {
+        "must not contain spaces"
+    } else if branch_name.contains(|c: char| c.is_ascii_control()) {
+        "must not contain control characters"
+    } else if branch_name.contains(['~', '^', ':', '?', '*', '[', '\\']) {
+        "must not contain any of `~^:?*[\\`"
+    } else if branch_name.contains("//") {
+        "must not contain `//`"
+    } else if branch_name.ends_with('.') {
+        "must not end with `.`"
+    } else if branch_name
+        .split('/')
+        .any(|component| component.starts_with('.') || component.ends_with(".lock"))
+    {
+        "must not have a component starting with `.` or ending with `.lock`"
+    } else if branch_name == "@" {
+        "must not be `@`"
+    } else {
+        return Ok(());
+    };
+    Err(user_error_with_hint(
+        format!("Invalid branch name {branch_name:?}: branch names {problem}"),
+        "Branch names must be valid Git ref names to be exported and pushed. Use \
+         --allow-nonstandard to create it anyway.",
+    ))
+}
+
 fn cmd_branch_create(
     ui: &mut Ui,
     command: &CommandHelper,
@@ -246,41 +888,122 @@ fn cmd_branch_create(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     let view = workspace_command.repo().view();
-    let branch_names: Vec<&str> = args
-        .names
-        .iter()
-        .map(|branch_name| {
-            if view.get_local_branch(branch_name).is_present() {
-                Err(user_error_with_hint(
-                    format!("Branch already exists: {branch_name}"),
-                    "Use `jj branch set` to update it.",
-                ))
-            } else {
-                Ok(branch_name.as_str())
+    let revisions: Vec<&str> = match &args.revision[..] {
+        [] => vec!["@"; args.names.len()],
+        [revision] => vec![revision.deref(); args.names.len()],
+        revisions if revisions.len() == args.names.len() => {
+            revisions.iter().map(|revision| revision.deref()).collect()
+        }
+        revisions => {
+            return Err(user_error_with_hint(
+                format!(
+                    "Got {} revisions for {} branches",
+                    revisions.len(),
+                    args.names.len()
+                ),
+                "Give a single --revision for all branches, or one for each branch in the same \
+                 order.",
+            ));
+        }
+    };
+    // Pairs of the names of the branches to create and their revisions
+    let mut branch_args: Vec<(&str, &str)> = vec![];
+    for (branch_name, revision) in iter::zip(&args.names, revisions) {
+        if view.get_local_branch(branch_name).is_present() {
+            if args.if_not_exists {
+                continue;
             }
+            return Err(user_error_with_hint(
+                format!("Branch already exists: {branch_name}"),
+                "Use `jj branch set` to update it.",
+            ));
+        }
+        branch_args.push((branch_name, revision));
+    }
+    if branch_args.is_empty() {
+        writeln!(ui.stderr(), "Nothing changed.")?;
+        return Ok(());
+    }
+    let branch_names = branch_args.iter().map(|(name, _)| *name).collect_vec();
+    if !args.allow_nonstandard {
+        for branch_name in &branch_names {
+            validate_branch_name(branch_name)?;
+        }
+    }
+
+    warn_multiple_branches(ui, "Creating", &branch_names)?;
+    let old_workspace_command = if let Some(op_str) = &args.resolve_at_op {
+        let op = workspace_command.resolve_single_op(op_str)?;
+        let workspace = command.load_workspace()?;
+        let repo = workspace.repo_loader().load_at(&op)?;
+        Some(command.for_loaded_repo(ui, workspace, repo)?)
+    } else {
+        None
+    };
+    let branch_targets: Vec<(&str, Commit)> = branch_args
+        .iter()
+        .map(|(branch_name, revision)| {
+            let target_commit = old_workspace_command
+                .as_ref()
+                .unwrap_or(&workspace_command)
+                .resolve_single_rev(revision, ui)?;
+            Ok::<_, CommandError>((*branch_name, target_commit))
         })
         .try_collect()?;
-
-    if branch_names.len() > 1 {
+    let target_commits = branch_targets
+        .iter()
+        .map(|(_, commit)| commit)
+        .unique_by(|commit| commit.id())
+        .collect_vec();
+    for target_commit in &target_commits {
+        let existing_branch_names = view
+            .local_branches()
+            .filter(|(_, target)| target.as_normal() == Some(target_commit.id()))
+            .map(|(name, _)| name)
+            .collect_vec();
+        if existing_branch_names.is_empty() {
+            continue;
+        }
+        let commit_term = if target_commits.len() > 1 {
+            format!("Target commit {}", short_commit_hash(target_commit.id()))
+        } else {
+            "Target commit".to_owned()
+        };
         writeln!(
             ui.warning(),
-            "warning: Creating multiple branches ({}).",
-            branch_names.len()
+            "warning: {commit_term} already has {}: {}",
+            if existing_branch_names.len() > 1 {
+                "branches"
+            } else {
+                "branch"
+            },
+            existing_branch_names.join(", ")
         )?;
     }
-
-    let target_commit =
-        workspace_command.resolve_single_rev(args.revision.as_deref().unwrap_or("@"), ui)?;
-    let mut tx = workspace_command.start_transaction(&format!(
-        "create {} pointing to commit {}",
-        make_branch_term(&branch_names),
-        target_commit.id().hex()
-    ));
-    for branch_name in branch_names {
+    if args.dry_run {
+        let changes = branch_targets
+            .iter()
+            .map(|(name, commit)| (*name, RefTarget::normal(commit.id().clone())))
+            .collect_vec();
+        return print_branch_changes(ui, &workspace_command, &changes);
+    }
+    if !confirm_branch_changes(ui, command, "Create", &branch_names, args.yes, false)? {
+        return Ok(());
+    }
+    let description = describe_branch_targets(&branch_targets, "pointing to");
+    let mut tx = workspace_command.start_transaction(&format!("create {description}"));
+    if args.resolve_at_op.is_some() {
+        // The target commits may have been abandoned since.
+        for target_commit in &target_commits {
+            tx.mut_repo().add_head(target_commit);
+        }
+    }
+    for (branch_name, target_commit) in &branch_targets {
         tx.mut_repo()
             .set_local_branch_target(branch_name, RefTarget::normal(target_commit.id().clone()));
     }
-    tx.finish(ui)?;
+    finish_branch_transaction(ui, tx, &args.operation)?;
+    print_branch_count(ui, "Created", branch_names.len(), args.quiet)?;
     Ok(())
 }
 
@@ -289,57 +1012,327 @@ fn cmd_branch_set(
     command: &CommandHelper,
     args: &BranchSetArgs,
 ) -> Result<(), CommandError> {
-    let branch_names = &args.names;
     let mut workspace_command = command.workspace_helper(ui)?;
-    if branch_names.len() > 1 {
-        writeln!(
-            ui.warning(),
-            "warning: Updating multiple branches ({}).",
-            branch_names.len()
-        )?;
-    }
+    let branch_names = args
+        .names
+        .iter()
+        .map(|arg| arg.split_once('=').map_or(arg.as_str(), |(name, _)| name))
+        .collect_vec();
+    warn_multiple_branches(ui, "Updating", &branch_names)?;
 
-    let target_commit =
-        workspace_command.resolve_single_rev(args.revision.as_deref().unwrap_or("@"), ui)?;
-    if !args.allow_backwards
-        && !branch_names.iter().all(|branch_name| {
-            is_fast_forward(
-                workspace_command.repo().as_ref(),
-                branch_name,
-                target_commit.id(),
-            )
+    let default_revision = args.revision.as_deref().unwrap_or("@");
+    let branch_targets: Vec<(&str, Commit)> = args
+        .names
+        .iter()
+        .map(|arg| {
+            if args.to_parent && !arg.contains('=') {
+                let target_commit =
+                    resolve_parent_branch_target(workspace_command.repo().as_ref(), arg)?;
+                return Ok((arg.as_str(), target_commit));
+            }
+            if let (None, Some(remote_name)) = (arg.split_once('='), &args.to_remote) {
+                let target_commit = resolve_remote_branch_target(
+                    workspace_command.repo().as_ref(),
+                    arg,
+                    remote_name,
+                )?;
+                return Ok((arg.as_str(), target_commit));
+            }
+            let (branch_name, revision) = arg.split_once('=').unwrap_or((arg, default_revision));
+            let target_commit = workspace_command.resolve_single_rev(revision, ui)?;
+            Ok::<_, CommandError>((branch_name, target_commit))
         })
-    {
-        return Err(user_error_with_hint(
-            "Refusing to move branch backwards or sideways.",
-            "Use --allow-backwards to allow it.",
-        ));
+        .try_collect()?;
+    let repo = workspace_command.repo().as_ref();
+    let new_branch_names = branch_targets
+        .iter()
+        .map(|(name, _)| *name)
+        .filter(|name| repo.view().get_local_branch(name).is_absent());
+    for branch_name in new_branch_names {
+        if !args.allow_new {
+            return Err(user_error_with_hint(
+                format!("No such branch: {branch_name}"),
+                "Use `jj branch create` or --allow-new to create it.",
+            ));
+        }
+        if !args.allow_nonstandard {
+            validate_branch_name(branch_name)?;
+        }
+    }
+    // Moving to the parent is backwards by definition.
+    if !args.allow_backwards && !args.to_parent {
+        check_fast_forward(repo, &branch_targets)?;
+    }
+    // Branches already pointing to the target don't need to be updated.
+    let branch_targets = branch_targets
+        .into_iter()
+        .filter(|(branch_name, target_commit)| {
+            repo.view().get_local_branch(branch_name).as_normal() != Some(target_commit.id())
+        })
+        .collect_vec();
+    if branch_targets.is_empty() {
+        writeln!(ui.stderr(), "Nothing changed.")?;
+        return Ok(());
+    }
+    if args.dry_run {
+        let changes = branch_targets
+            .iter()
+            .map(|(name, commit)| (*name, RefTarget::normal(commit.id().clone())))
+            .collect_vec();
+        return print_branch_changes(ui, &workspace_command, &changes);
+    }
+    let branch_names = branch_targets.iter().map(|(name, _)| *name).collect_vec();
+    if !confirm_branch_changes(ui, command, "Update", &branch_names, args.yes, false)? {
+        return Ok(());
     }
-    let mut tx = workspace_command.start_transaction(&format!(
-        "point {} to commit {}",
-        make_branch_term(branch_names),
-        target_commit.id().hex()
-    ));
-    for branch_name in branch_names {
+    let description = make_set_branches_description(repo, &branch_targets);
+    let mut tx = workspace_command.start_transaction(&description);
+    for (branch_name, target_commit) in &branch_targets {
         tx.mut_repo()
             .set_local_branch_target(branch_name, RefTarget::normal(target_commit.id().clone()));
     }
-    tx.finish(ui)?;
+    finish_branch_transaction(ui, tx, &args.operation)?;
+    print_branch_count(ui, "Updated", branch_targets.len(), args.quiet)?;
+    print_diverged_branch_hints(ui, workspace_command.repo().as_ref(), &branch_targets)?;
     Ok(())
 }
 
-fn find_local_branches(
-    view: &View,
-    name_patterns: &[StringPattern],
-) -> Result<Vec<String>, CommandError> {
-    find_branches_with(name_patterns, |pattern| {
-        view.local_branches_matching(pattern)
-            .map(|(name, _)| name.to_owned())
-    })
-}
-
-fn find_forgettable_branches(
-    view: &View,
+/// Prints a hint for each branch whose new target can't be fast-forwarded from
+/// one of its tracked remote branches, since pushing it will rewrite the remote.
+fn print_diverged_branch_hints(
+    ui: &Ui,
+    repo: &dyn Repo,
+    branch_targets: &[(&str, Commit)],
+) -> Result<(), CommandError> {
+    for (branch_name, target_commit) in branch_targets {
+        let tracked_remote_refs =
+            repo.view()
+                .all_remote_branches()
+                .filter(|&((name, remote_name), remote_ref)| {
+                    name == *branch_name
+                        && remote_name != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO
+                        && remote_ref.is_tracking()
+                });
+        for ((_, remote_name), remote_ref) in tracked_remote_refs {
+            let remote_ids = remote_ref.target.added_ids().cloned().collect_vec();
+            let diverged = revset::walk_revs(repo, &remote_ids, &[target_commit.id().clone()])?
+                .iter()
+                .next()
+                .is_some();
+            if diverged {
+                writeln!(
+                    ui.hint(),
+                    "Hint: Branch {branch_name} has diverged from {branch_name}@{remote_name}. \
+                     The next `jj git push` will force-push it."
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Looks up the commit the remote branch points to.
+fn resolve_remote_branch_target(
+    repo: &dyn Repo,
+    branch_name: &str,
+    remote_name: &str,
+) -> Result<Commit, CommandError> {
+    let target = &repo
+        .view()
+        .get_remote_branch(branch_name, remote_name)
+        .target;
+    if target.is_absent() {
+        return Err(user_error_with_hint(
+            format!("No such remote branch: {branch_name}@{remote_name}"),
+            "Use `jj git fetch` to update the remote branches.",
+        ));
+    }
+    let Some(id) = target.as_normal() else {
+        return Err(user_error_with_hint(
+            format!("Remote branch {branch_name}@{remote_name} is conflicted"),
+            format!("Use `jj branch list {branch_name}` to see the conflicting targets."),
+        ));
+    };
+    Ok(repo.store().get_commit(id)?)
+}
+
+/// Looks up the parent of the commit the local branch points to.
+fn resolve_parent_branch_target(
+    repo: &dyn Repo,
+    branch_name: &str,
+) -> Result<Commit, CommandError> {
+    let target = repo.view().get_local_branch(branch_name);
+    if target.is_absent() {
+        return Err(user_error(format!("No such branch: {branch_name}")));
+    }
+    let Some(id) = target.as_normal() else {
+        return Err(user_error_with_hint(
+            format!("Branch {branch_name} is conflicted"),
+            format!("Use `jj branch list {branch_name}` to see the conflicting targets."),
+        ));
+    };
+    let commit = repo.store().get_commit(id)?;
+    match commit.parents().as_slice() {
+        [parent] => Ok(parent.clone()),
+        [] => Err(user_error(format!(
+            "Branch {branch_name} points to the root commit, which has no parent"
+        ))),
+        parents => Err(user_error_with_hint(
+            format!(
+                "Branch {branch_name} points to a merge commit with {} parents",
+                parents.len()
+            ),
+            format!("Use `jj branch set {branch_name} -r REVISION` to choose one of them."),
+        )),
+    }
+}
+
+fn cmd_branch_move(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BranchMoveArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo().as_ref();
+    let branch_names: Vec<String> = if args.names.is_empty() {
+        let from_commit =
+            workspace_command.resolve_single_rev(args.from.as_deref().unwrap_or("@-"), ui)?;
+        let branch_names = repo
+            .view()
+            .local_branches()
+            .filter(|(_, target)| {
+                if target.has_conflict() {
+                    args.include_conflicted && target.added_ids().contains(from_commit.id())
+                } else {
+                    target.as_normal() == Some(from_commit.id())
+                }
+            })
+            .map(|(name, _)| name.to_owned())
+            .collect_vec();
+        if branch_names.is_empty() {
+            return Err(user_error(format!(
+                "No branches point to commit {}",
+                short_commit_hash(from_commit.id())
+            )));
+        }
+        branch_names
+    } else {
+        args.names.clone()
+    };
+    let to_commit = args
+        .to
+        .as_ref()
+        .map(|revision| workspace_command.resolve_single_rev(revision, ui))
+        .transpose()?;
+    let mut branch_targets: Vec<(&str, Commit)> = vec![];
+    for branch_name in &branch_names {
+        let current_target = repo.view().get_local_branch(branch_name);
+        if current_target.is_absent() {
+            return Err(user_error(format!("No such branch: {branch_name}")));
+        } else if current_target.has_conflict() && !args.include_conflicted {
+            return Err(user_error_with_hint(
+                format!("Branch {branch_name} is conflicted"),
+                "Use --include-conflicted to move it anyway.",
+            ));
+        }
+        let target_commit = if let Some(commit) = &to_commit {
+            commit.clone()
+        } else {
+            let expression =
+                RevsetExpression::commits(current_target.added_ids().cloned().collect())
+                    .descendants()
+                    .heads();
+            let heads: Vec<_> = workspace_command
+                .evaluate_revset(expression)?
+                .iter()
+                .take(2)
+                .collect();
+            match &heads[..] {
+                [id] => repo.store().get_commit(id)?,
+                _ => {
+                    return Err(user_error_with_hint(
+                        format!("Branch {branch_name} has diverged descendants"),
+                        "Use --to to specify which revision to move it to.",
+                    ))
+                }
+            }
+        };
+        branch_targets.push((branch_name, target_commit));
+    }
+    if !args.allow_backwards {
+        check_fast_forward(repo, &branch_targets)?;
+    }
+    let mut tx =
+        workspace_command.start_transaction(&make_point_branches_description(&branch_targets));
+    for (branch_name, target_commit) in &branch_targets {
+        tx.mut_repo()
+            .set_local_branch_target(branch_name, RefTarget::normal(target_commit.id().clone()));
+    }
+    for (branch_name, target_commit) in &branch_targets {
+        write!(ui.stderr(), "Moved branch {branch_name} to ")?;
+        tx.write_commit_summary(ui.stderr_formatter().as_mut(), target_commit)?;
+        writeln!(ui.stderr())?;
+    }
+    finish_branch_transaction(ui, tx, &args.operation)?;
+    Ok(())
+}
+
+fn make_point_branches_description(branch_targets: &[(&str, Commit)]) -> String {
+    format!("point {}", describe_branch_targets(branch_targets, "to"))
+}
+
+/// Describes new and existing branches separately so the operation log tells
+/// which branches were created.
+fn make_set_branches_description(repo: &dyn Repo, branch_targets: &[(&str, Commit)]) -> String {
+    let (new_targets, existing_targets): (Vec<_>, Vec<_>) = branch_targets
+        .iter()
+        .cloned()
+        .partition(|(name, _)| repo.view().get_local_branch(name).is_absent());
+    let mut descriptions = vec![];
+    if !new_targets.is_empty() {
+        let description = describe_branch_targets(&new_targets, "pointing to");
+        descriptions.push(format!("create {description}"));
+    }
+    if !existing_targets.is_empty() {
+        descriptions.push(make_point_branches_description(&existing_targets));
+    }
+    descriptions.join(", ")
+}
+
+fn describe_branch_targets(branch_targets: &[(&str, Commit)], preposition: &str) -> String {
+    if branch_targets
+        .iter()
+        .map(|(_, commit)| commit.id())
+        .all_equal()
+    {
+        let branch_names = branch_targets.iter().map(|(name, _)| name).collect_vec();
+        format!(
+            "{} {preposition} commit {}",
+            make_branch_term(&branch_names),
+            branch_targets[0].1.id().hex()
+        )
+    } else {
+        branch_targets
+            .iter()
+            .map(|(name, commit)| {
+                format!("branch {name} {preposition} commit {}", commit.id().hex())
+            })
+            .join(", ")
+    }
+}
+
+fn find_local_branches(
+    view: &View,
+    name_patterns: &[StringPattern],
+) -> Result<Vec<String>, CommandError> {
+    find_branches_with(name_patterns, |pattern| {
+        view.local_branches_matching(pattern)
+            .map(|(name, _)| name.to_owned())
+    })
+}
+
+fn find_forgettable_branches(
+    view: &View,
     name_patterns: &[StringPattern],
 ) -> Result<Vec<String>, CommandError> {
     find_branches_with(name_patterns, |pattern| {
@@ -349,11 +1342,51 @@ fn find_forgettable_branches(
     })
 }
 
-fn find_branches_with<'a, I: Iterator<Item = String>>(
+/// A branch selected by a name pattern.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum BranchMatch {
+    Local(String),
+    Remote(RemoteBranchName),
+}
+
+impl fmt::Display for BranchMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BranchMatch::Local(name) => write!(f, "{name}"),
+            BranchMatch::Remote(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// Like `find_forgettable_branches()`, but also matches the patterns against
+/// the remote branches as `NAME@REMOTE`. Git-tracking branches aren't matched.
+fn find_forgettable_branches_with_remotes(
+    view: &View,
+    name_patterns: &[StringPattern],
+) -> Result<Vec<BranchMatch>, CommandError> {
+    find_branches_with(name_patterns, |pattern| {
+        let local_matches = view
+            .branches()
+            .filter(|(name, _)| pattern.matches(name))
+            .map(|(name, _)| BranchMatch::Local(name.to_owned()));
+        let remote_matches = view
+            .all_remote_branches()
+            .filter(|&((_, remote), _)| remote != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO)
+            .map(|((branch, remote), _)| RemoteBranchName {
+                branch: branch.to_owned(),
+                remote: remote.to_owned(),
+            })
+            .filter(|name| pattern.matches(&name.to_string()))
+            .map(BranchMatch::Remote);
+        local_matches.chain(remote_matches)
+    })
+}
+
+fn find_branches_with<'a, T: Ord, I: Iterator<Item = T>>(
     name_patterns: &'a [StringPattern],
     mut find_matches: impl FnMut(&'a StringPattern) -> I,
-) -> Result<Vec<String>, CommandError> {
-    let mut matching_branches: Vec<String> = vec![];
+) -> Result<Vec<T>, CommandError> {
+    let mut matching_branches: Vec<T> = vec![];
     let mut unmatched_patterns = vec![];
     for pattern in name_patterns {
         let mut names = find_matches(pattern).peekable();
@@ -428,17 +1461,134 @@ fn cmd_branch_delete(
         )?;
     }
     let name_patterns = [&args.names[..], &args.glob[..]].concat();
-    let names = find_local_branches(view, &name_patterns)?;
+    // An exact `NAME@REMOTE` that isn't a local branch name selects the remote
+    // branch to be deleted on the next push, leaving the local branch intact.
+    let (remote_patterns, local_patterns): (Vec<_>, Vec<_>) =
+        name_patterns.into_iter().partition(|pattern| {
+            pattern
+                .as_exact()
+                .is_some_and(|name| name.contains('@') && view.get_local_branch(name).is_absent())
+        });
+    let remote_names = find_deletable_remote_branches(view, &remote_patterns)?;
+    if args.retain_remote {
+        if let Some(name) = remote_names.first() {
+            return Err(user_error(format!(
+                "--retain-remote cannot be used with remote branch {name}"
+            )));
+        }
+    }
+    let local_names = if local_patterns.is_empty() {
+        vec![]
+    } else {
+        find_local_branches(view, &local_patterns)?
+    };
+    if args.dry_run {
+        let changes = local_names
+            .iter()
+            .map(|name| {
+                (
+                    name.clone(),
+                    view.get_local_branch(name),
+                    RefTarget::absent(),
+                )
+            })
+            .chain(remote_names.iter().map(|name| {
+                let remote_ref = view.get_remote_branch(&name.branch, &name.remote);
+                (name.to_string(), &remote_ref.target, RefTarget::absent())
+            }))
+            .collect_vec();
+        return print_ref_changes(ui, &workspace_command, &changes);
+    }
+    let names = local_names
+        .iter()
+        .cloned()
+        .map(BranchMatch::Local)
+        .chain(remote_names.iter().cloned().map(BranchMatch::Remote))
+        .collect_vec();
+    let display_names = names.iter().map(|name| name.to_string()).collect_vec();
+    if !confirm_branch_changes(ui, command, "Delete", &display_names, args.yes, true)? {
+        return Ok(());
+    }
     let mut tx =
         workspace_command.start_transaction(&format!("delete {}", make_branch_term(&names)));
-    for branch_name in names.iter() {
+    for branch_name in &local_names {
+        if args.retain_remote {
+            forget_local_branch(tx.mut_repo(), branch_name);
+        } else {
+            tx.mut_repo()
+                .set_local_branch_target(branch_name, RefTarget::absent());
+        }
+    }
+    for name in &remote_names {
         tx.mut_repo()
-            .set_local_branch_target(branch_name, RefTarget::absent());
+            .set_remote_branch_deletion_pending(&name.branch, &name.remote, true);
     }
-    tx.finish(ui)?;
-    if names.len() > 1 {
-        writeln!(ui.stderr(), "Deleted {} branches.", names.len())?;
+    finish_branch_transaction(ui, tx, &args.operation)?;
+    print_branch_count(ui, "Deleted", names.len(), args.quiet)?;
+    Ok(())
+}
+
+/// Resolves exact `NAME@REMOTE` patterns to the remote branches to be deleted
+/// on the next push. The remote branches must exist and be tracked.
+fn find_deletable_remote_branches(
+    view: &View,
+    name_patterns: &[StringPattern],
+) -> Result<Vec<RemoteBranchName>, CommandError> {
+    let mut names = vec![];
+    for name in name_patterns
+        .iter()
+        .filter_map(|pattern| pattern.as_exact())
+    {
+        let (branch, remote) = name.rsplit_once('@').unwrap();
+        if remote == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+            return Err(user_error(format!(
+                "Git-tracking branch cannot be deleted separately: {name}"
+            )));
+        }
+        let remote_ref = view.get_remote_branch(branch, remote);
+        if remote_ref.is_absent() {
+            return Err(user_error(format!("No such remote branch: {name}")));
+        }
+        if !remote_ref.is_tracking() {
+            return Err(user_error_with_hint(
+                format!("Cannot delete non-tracking remote branch {name}"),
+                format!("Run `jj branch track {name}` to track it first."),
+            ));
+        }
+        names.push(RemoteBranchName {
+            branch: branch.to_owned(),
+            remote: remote.to_owned(),
+        });
     }
+    names.sort_unstable();
+    names.dedup();
+    Ok(names)
+}
+
+fn cmd_branch_describe(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BranchDescribeArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let name = &args.name;
+    if repo.view().get_local_branch(name).is_absent() {
+        return Err(user_error(format!("No such branch: {name}")));
+    }
+    let old_description = repo.view().get_branch_description(name);
+    let description = if let Some(message) = &args.message {
+        text_util::complete_newline(message.trim_matches('\n'))
+    } else {
+        edit_description(repo, old_description, command.settings())?
+    };
+    if description == old_description {
+        writeln!(ui.stderr(), "Nothing changed.")?;
+        return Ok(());
+    }
+    let mut tx = workspace_command.start_transaction(&format!("describe branch {name}"));
+    tx.mut_repo().set_branch_description(name, &description);
+    finish_branch_transaction(ui, tx, &args.operation)?;
     Ok(())
 }
 
@@ -455,20 +1605,229 @@ fn cmd_branch_forget(
             "--glob has been deprecated. Please prefix the pattern with `glob:` instead."
         )?;
     }
-    let name_patterns = [&args.names[..], &args.glob[..]].concat();
-    let names = find_forgettable_branches(view, &name_patterns)?;
+    let (exclude_patterns, name_patterns): (Vec<_>, Vec<_>) = args
+        .names
+        .iter()
+        .chain(&args.glob)
+        .cloned()
+        .partition(is_negated_glob);
+    if name_patterns.is_empty() && args.at.is_none() {
+        return Err(user_error_with_hint(
+            "No branches to forget: all of the patterns are exclusions",
+            "Add patterns to select branches to forget, e.g. `'glob:wip/*' 'glob:!wip/keep-me'`.",
+        ));
+    }
+    let exclude_patterns: Vec<_> = exclude_patterns
+        .iter()
+        .map(|pattern| StringPattern::glob(&pattern.as_str()[1..]))
+        .try_collect()
+        .map_err(|err| user_error(err.to_string()))?;
+    let mut matches = if args.include_remotes {
+        find_forgettable_branches_with_remotes(view, &name_patterns)?
+    } else {
+        find_forgettable_branches(view, &name_patterns)?
+            .into_iter()
+            .map(BranchMatch::Local)
+            .collect()
+    };
+    if let Some(revision) = &args.at {
+        let commit = workspace_command.resolve_single_rev(revision, ui)?;
+        let names_at_commit = find_branches_at_commit(view, commit.id());
+        // It's fine for no branches to be at the commit if the patterns matched
+        // some.
+        if names_at_commit.is_empty() && matches.is_empty() {
+            return Err(user_error(format!(
+                "No branches point to commit {}",
+                short_commit_hash(commit.id())
+            )));
+        }
+        matches.extend(names_at_commit.into_iter().map(BranchMatch::Local));
+        matches.sort_unstable();
+        matches.dedup();
+    }
+    matches.retain(|branch_match| {
+        let name = branch_match.to_string();
+        !exclude_patterns
+            .iter()
+            .any(|pattern| pattern.matches(&name))
+    });
+    if matches.is_empty() {
+        return Err(user_error(
+            "No branches to forget: all of the matching branches are excluded",
+        ));
+    }
+    if !args.keep_remotes {
+        // Forgetting a local branch also forgets its remote branches.
+        let local_names: HashSet<String> = matches
+            .iter()
+            .filter_map(|branch_match| match branch_match {
+                BranchMatch::Local(name) => Some(name.clone()),
+                BranchMatch::Remote(_) => None,
+            })
+            .collect();
+        matches.retain(|branch_match| match branch_match {
+            BranchMatch::Local(_) => true,
+            BranchMatch::Remote(name) => !local_names.contains(&name.branch),
+        });
+    }
+    if args.dry_run {
+        let changes = matches
+            .iter()
+            .map(|branch_match| {
+                let old_target = match branch_match {
+                    BranchMatch::Local(name) => view.get_local_branch(name),
+                    BranchMatch::Remote(name) => {
+                        &view.get_remote_branch(&name.branch, &name.remote).target
+                    }
+                };
+                (branch_match.to_string(), old_target, RefTarget::absent())
+            })
+            .collect_vec();
+        return print_ref_changes(ui, &workspace_command, &changes);
+    }
+    let names = matches
+        .iter()
+        .map(|branch_match| branch_match.to_string())
+        .collect_vec();
+    if !confirm_branch_changes(ui, command, "Forget", &names, args.yes, true)? {
+        return Ok(());
+    }
     let mut tx =
         workspace_command.start_transaction(&format!("forget {}", make_branch_term(&names)));
-    for branch_name in names.iter() {
-        tx.mut_repo().remove_branch(branch_name);
+    for branch_match in &matches {
+        match branch_match {
+            BranchMatch::Local(branch_name) if args.keep_remotes => {
+                forget_local_branch(tx.mut_repo(), branch_name);
+            }
+            BranchMatch::Local(branch_name) => {
+                tx.mut_repo().remove_branch(branch_name);
+            }
+            BranchMatch::Remote(name) => {
+                tx.mut_repo()
+                    .set_remote_branch(&name.branch, &name.remote, RemoteRef::absent());
+            }
+        }
     }
-    tx.finish(ui)?;
-    if names.len() > 1 {
-        writeln!(ui.stderr(), "Forgot {} branches.", names.len())?;
+    finish_branch_transaction(ui, tx, &args.operation)?;
+    print_branch_count(ui, "Forgot", names.len(), args.quiet)?;
+    Ok(())
+}
+
+/// Returns the names of the branches whose local or remote targets point to the
+/// commit.
+fn find_branches_at_commit(view: &View, commit_id: &CommitId) -> Vec<String> {
+    view.branches()
+        .filter(|(_, branch_target)| {
+            let local_target = branch_target.local_target;
+            let remote_targets = branch_target
+                .remote_refs
+                .iter()
+                .map(|(_, remote_ref)| &remote_ref.target);
+            iter::once(local_target)
+                .chain(remote_targets)
+                .any(|target| target.added_ids().contains(commit_id))
+        })
+        .map(|(name, _)| name.to_owned())
+        .collect()
+}
+
+/// Returns true if the pattern is a glob starting with `!`, which excludes the
+/// matching branches.
+fn is_negated_glob(pattern: &StringPattern) -> bool {
+    matches!(pattern, StringPattern::Glob(_)) && pattern.as_str().starts_with('!')
+}
+
+/// Removes the local branch, and untracks its remote branches so they won't be
+/// deleted on push. The Git-tracking branch is removed as it would be by
+/// `remove_branch()`.
+fn forget_local_branch(mut_repo: &mut MutableRepo, branch_name: &str) {
+    let remote_names = mut_repo
+        .view()
+        .branches()
+        .find(|&(name, _)| name == branch_name)
+        .map(|(_, branch_target)| {
+            branch_target
+                .remote_refs
+                .iter()
+                .map(|&(remote, _)| remote.to_owned())
+                .collect_vec()
+        })
+        .unwrap_or_default();
+    mut_repo.set_local_branch_target(branch_name, RefTarget::absent());
+    for remote_name in remote_names {
+        if remote_name == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+            mut_repo.set_remote_branch(branch_name, &remote_name, RemoteRef::absent());
+        } else {
+            mut_repo.untrack_remote_branch(branch_name, &remote_name);
+        }
+    }
+}
+
+fn cmd_branch_diff(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BranchDiffArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo().as_ref();
+    let view = repo.view();
+    let target_ids = |branch_name: &str| {
+        let local_target = view.get_local_branch(branch_name);
+        if local_target.is_absent() {
+            return Err(user_error(format!("No such branch: {branch_name}")));
+        }
+        Ok(local_target.added_ids().cloned().collect_vec())
+    };
+    let a_ids = target_ids(&args.a)?;
+    let b_ids = target_ids(&args.b)?;
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    let formatter = formatter.as_mut();
+    let write_commits = |formatter: &mut dyn Formatter,
+                         wanted: &[CommitId],
+                         unwanted: &[CommitId],
+                         indent: &str| {
+        for commit in revset::walk_revs(repo, wanted, unwanted)?
+            .iter()
+            .commits(repo.store())
+        {
+            write!(formatter, "{indent}")?;
+            workspace_command.write_commit_summary(formatter, &commit?)?;
+            writeln!(formatter)?;
+        }
+        Ok::<_, CommandError>(())
+    };
+    if args.both {
+        writeln!(formatter, "Commits in {} but not in {}:", args.a, args.b)?;
+        write_commits(formatter, &a_ids, &b_ids, "  ")?;
+        writeln!(formatter, "Commits in {} but not in {}:", args.b, args.a)?;
+        write_commits(formatter, &b_ids, &a_ids, "  ")?;
+    } else {
+        write_commits(formatter, &a_ids, &b_ids, "")?;
     }
     Ok(())
 }
 
+fn cmd_branch_exists(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BranchExistsArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let view = workspace_command.repo().view();
+    let exists = match &args.remote {
+        Some(remote) => view.get_remote_branch(&args.name, remote).is_present(),
+        None => view.get_local_branch(&args.name).is_present(),
+    };
+    if exists {
+        Ok(())
+    } else {
+        // The exit status is the result, so no message is printed.
+        Err(CommandError::CheckFailed(String::new()))
+    }
+}
+
 fn cmd_branch_track(
     ui: &mut Ui,
     command: &CommandHelper,
@@ -490,7 +1849,7 @@ fn cmd_branch_track(
         tx.mut_repo()
             .track_remote_branch(&name.branch, &name.remote);
     }
-    tx.finish(ui)?;
+    finish_branch_transaction(ui, tx, &args.operation)?;
     if names.len() > 1 {
         writeln!(
             ui.stderr(),
@@ -528,7 +1887,7 @@ fn cmd_branch_untrack(
         tx.mut_repo()
             .untrack_remote_branch(&name.branch, &name.remote);
     }
-    tx.finish(ui)?;
+    finish_branch_transaction(ui, tx, &args.operation)?;
     if names.len() > 1 {
         writeln!(
             ui.stderr(),
@@ -547,76 +1906,445 @@ fn cmd_branch_list(
     let workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo();
     let view = repo.view();
-    let branch_names_to_list: Option<HashSet<&str>> = if !args.revisions.is_empty() {
-        // Match against local targets only, which is consistent with "jj git push".
-        let filter_expressions: Vec<_> = args
-            .revisions
-            .iter()
-            .map(|revision_str| workspace_command.parse_revset(revision_str, Some(ui)))
-            .try_collect()?;
-        let filter_expression = RevsetExpression::union_all(&filter_expressions);
-        // Intersects with the set of local branch targets to minimize the lookup space.
-        let revset_expression = RevsetExpression::branches(StringPattern::everything())
-            .intersection(&filter_expression);
-        let revset_expression = revset::optimize(revset_expression);
-        let revset = workspace_command.evaluate_revset(revset_expression)?;
-        let filtered_targets: HashSet<CommitId> = revset.iter().collect();
-        // TODO: Suppose we have name-based filter like --glob, should these filters
-        // be AND-ed or OR-ed? Maybe OR as "jj git push" would do. Perhaps, we
-        // can consider these options as producers of branch names, not filters
-        // of different kind (which are typically intersected.)
-        let branch_names = view
-            .local_branches()
-            .filter(|(_, target)| target.added_ids().any(|id| filtered_targets.contains(id)))
-            .map(|(name, _)| name)
-            .collect();
-        Some(branch_names)
+    let branch_names_to_list: Option<HashSet<&str>> =
+        if !args.names.is_empty() || !args.revisions.is_empty() {
+            // Name patterns and revisions are producers of branch names, so the
+            // selected branches are unioned. Remote-only branches are matched by
+            // their bare names.
+            let mut branch_names: HashSet<&str> = view
+                .branches()
+                .map(|(name, _)| name)
+                .filter(|name| args.names.iter().any(|pattern| pattern.matches(name)))
+                .collect();
+            if !args.revisions.is_empty() {
+                // Match against local targets only, which is consistent with "jj git push".
+                let filter_expressions: Vec<_> = args
+                    .revisions
+                    .iter()
+                    .map(|revision_str| workspace_command.parse_revset(revision_str, Some(ui)))
+                    .try_collect()?;
+                let filter_expression = RevsetExpression::union_all(&filter_expressions);
+                // Intersects with the set of local branch targets to minimize the lookup space.
+                let revset_expression = RevsetExpression::branches(StringPattern::everything())
+                    .intersection(&filter_expression);
+                let revset_expression = revset::optimize(revset_expression);
+                let revset = workspace_command.evaluate_revset(revset_expression)?;
+                let filtered_targets: HashSet<CommitId> = revset.iter().collect();
+                branch_names.extend(
+                    view.local_branches()
+                        .filter(|(_, target)| {
+                            target.added_ids().any(|id| filtered_targets.contains(id))
+                        })
+                        .map(|(name, _)| name),
+                );
+            }
+            Some(branch_names)
+        } else {
+            None
+        };
+
+    let remote_views = &view.store_view().remote_views;
+    for remote in &args.remotes {
+        if !remote_views.contains_key(remote) {
+            return Err(user_error_with_hint(
+                format!("No such remote: {remote}"),
+                format!("Known remotes are: {}", remote_views.keys().join(", ")),
+            ));
+        }
+    }
+
+    let contained_id = args
+        .contains
+        .as_ref()
+        .map(|revision_str| workspace_command.resolve_single_rev(revision_str, ui))
+        .transpose()?
+        .map(|commit| commit.id().clone());
+    let merged_id = args
+        .merged
+        .as_ref()
+        .map(|revision_str| workspace_command.resolve_single_rev(revision_str, ui))
+        .transpose()?
+        .map(|commit| commit.id().clone());
+    let no_merged_id = args
+        .no_merged
+        .as_ref()
+        .map(|revision_str| workspace_command.resolve_single_rev(revision_str, ui))
+        .transpose()?
+        .map(|commit| commit.id().clone());
+    let stale_cutoff = args.stale.map(|duration| {
+        let now = command.settings().signature().timestamp.timestamp;
+        let millis = i64::try_from(duration.as_millis()).unwrap_or(i64::MAX);
+        now.0.saturating_sub(millis)
+    });
+    // Deleted branches are neither merged nor unmerged.
+    let is_merged = |local_target: &RefTarget, base_id: &CommitId| {
+        local_target.is_present()
+            && local_target
+                .added_ids()
+                .all(|id| repo.index().is_ancestor(id, base_id))
+    };
+
+    let branches_to_list = view
+        .branches()
+        .filter(|&(name, _)| {
+            branch_names_to_list
+                .as_ref()
+                .map_or(true, |branch_names| branch_names.contains(name))
+        })
+        .map(|(name, mut branch_target)| {
+            if !args.remotes.is_empty() {
+                branch_target
+                    .remote_refs
+                    .retain(|&(remote, _)| args.remotes.iter().any(|r| r == remote));
+            }
+            (name, branch_target)
+        })
+        .filter(|(_, branch_target)| {
+            args.remotes.is_empty() || !branch_target.remote_refs.is_empty()
+        })
+        .filter(|(_, branch_target)| {
+            if !args.tracked && !args.untracked {
+                return true;
+            }
+            let on_remote = branch_target
+                .remote_refs
+                .iter()
+                .any(|&(remote, _)| remote != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO);
+            on_remote == args.tracked
+        })
+        .filter(|(_, branch_target)| {
+            !args.hide_deleted
+                || branch_target.local_target.is_present()
+                || branch_target
+                    .remote_refs
+                    .iter()
+                    .any(|&(_, remote_ref)| !remote_ref.is_tracking())
+        })
+        .filter(|(_, branch_target)| {
+            contained_id.as_ref().map_or(true, |contained_id| {
+                branch_target
+                    .local_target
+                    .added_ids()
+                    .any(|id| repo.index().is_ancestor(contained_id, id))
+            })
+        })
+        .filter(|(_, branch_target)| {
+            merged_id.as_ref().map_or(true, |merged_id| {
+                is_merged(branch_target.local_target, merged_id)
+            })
+        })
+        .filter(|(_, branch_target)| {
+            no_merged_id.as_ref().map_or(true, |no_merged_id| {
+                branch_target.local_target.is_present()
+                    && !is_merged(branch_target.local_target, no_merged_id)
+            })
+        })
+        .collect_vec();
+    let branches_to_list = if let Some(stale_cutoff) = stale_cutoff {
+        let mut stale_branches = vec![];
+        for (name, branch_target) in branches_to_list {
+            let local_target = branch_target.local_target;
+            let mut is_stale = local_target.is_present();
+            for id in local_target.added_ids() {
+                let commit = repo.store().get_commit(id)?;
+                is_stale &= commit.committer().timestamp.timestamp.0 < stale_cutoff;
+            }
+            if is_stale {
+                stale_branches.push((name, branch_target));
+            }
+        }
+        stale_branches
     } else {
-        None
+        branches_to_list
+    };
+    let branches_to_list = if args.conflicted {
+        let mut conflicted_branches = vec![];
+        for (name, branch_target) in branches_to_list {
+            if is_conflicted_or_diverged(repo.as_ref(), &branch_target)? {
+                conflicted_branches.push((name, branch_target));
+            }
+        }
+        conflicted_branches
+    } else {
+        branches_to_list
+    };
+    let branches_to_list = sort_branches(repo.as_ref(), branches_to_list, args.sort, args.reverse)?;
+    let conflicted_names = branches_to_list
+        .iter()
+        .filter(|(_, branch_target)| {
+            branch_target.local_target.has_conflict()
+                || branch_target
+                    .remote_refs
+                    .iter()
+                    .any(|(_, remote_ref)| remote_ref.target.has_conflict())
+        })
+        .map(|(name, _)| *name)
+        .collect_vec();
+    let check_conflicts = || {
+        if args.check && !conflicted_names.is_empty() {
+            Err(CommandError::CheckFailed(format!(
+                "Found conflicted branches: {}",
+                conflicted_names.join(", ")
+            )))
+        } else {
+            Ok(())
+        }
     };
 
+    if args.count {
+        let local_count = branches_to_list
+            .iter()
+            .filter(|(_, branch_target)| branch_target.local_target.is_present())
+            .count();
+        let mut formatter = ui.stdout_formatter();
+        writeln!(formatter, "{local_count}")?;
+        if args.all {
+            let remote_count = branches_to_list
+                .iter()
+                .flat_map(|(_, branch_target)| &branch_target.remote_refs)
+                .filter(|&&(remote, _)| remote != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO)
+                .count();
+            writeln!(formatter, "{remote_count}")?;
+        }
+        return check_conflicts();
+    }
+
+    if args.names_only {
+        let mut formatter = ui.stdout_formatter();
+        for (name, branch_target) in &branches_to_list {
+            let listed = args.all
+                || branch_target.local_target.is_present()
+                || branch_target
+                    .remote_refs
+                    .iter()
+                    .any(|(_, remote_ref)| remote_ref.is_tracking());
+            if listed {
+                writeln!(formatter, "{name}")?;
+            }
+        }
+        return check_conflicts();
+    }
+
+    if args.format == BranchListFormat::Json {
+        let entries: Vec<_> = branches_to_list
+            .into_iter()
+            .map(|(name, mut branch_target)| {
+                if args.local_only {
+                    branch_target.remote_refs.clear();
+                }
+                branch_list_json_entry(repo.as_ref(), name, branch_target, args.all)
+            })
+            .filter_map_ok(|entry| entry)
+            .try_collect()?;
+        let mut formatter = ui.stdout_formatter();
+        serde_json::to_writer_pretty(formatter.as_mut(), &entries).map_err(io::Error::from)?;
+        writeln!(formatter)?;
+        return check_conflicts();
+    }
+
+    if let Some(template_text) = &args.template {
+        let template = workspace_command.parse_branch_list_template(template_text)?;
+        ui.request_pager();
+        let mut formatter = ui.stdout_formatter();
+        let formatter = formatter.as_mut();
+        for (name, branch_target) in branches_to_list {
+            let items = branch_list_items(repo.store(), name, branch_target, args.all)?
+                .into_iter()
+                .filter(|item| !args.local_only || item.remote.is_none());
+            for item in items {
+                template.format(&item, formatter)?;
+                writeln!(formatter)?;
+            }
+        }
+        return check_conflicts();
+    }
+
     let no_branches_template = workspace_command.parse_commit_template(
         &command
             .settings()
             .config()
             .get_string("templates.commit_summary_no_branches")?,
     )?;
+    // Every tracking remote is listed with its ahead/behind counts, but the
+    // underlying Git repo is only listed if it's out of sync. Non-tracking
+    // remotes are only listed with --all.
+    let is_remote_ref_listed =
+        |remote: &str, remote_ref: &RemoteRef, local_target: &RefTarget| -> bool {
+            if args.local_only {
+                false
+            } else if !remote_ref.is_tracking() {
+                args.all
+            } else {
+                let synced = remote_ref.target == *local_target;
+                let hidden_git_ref = remote == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO
+                    && synced
+                    && !args.all
+                    && args.remotes.is_empty();
+                !hidden_git_ref
+            }
+        };
+    // Reading the target commits one by one is slow with a remote-backed
+    // store, so the listed ones are all read up front on the thread pool.
+    let target_ids: HashSet<&CommitId> = branches_to_list
+        .iter()
+        .flat_map(|(_, branch_target)| {
+            let local_target = branch_target.local_target;
+            let remote_targets = branch_target
+                .remote_refs
+                .iter()
+                .filter(move |&&(remote, remote_ref)| {
+                    is_remote_ref_listed(remote, remote_ref, local_target)
+                })
+                .map(|(_, remote_ref)| &remote_ref.target);
+            iter::once(local_target).chain(remote_targets)
+        })
+        .flat_map(|target| target.added_ids().chain(target.removed_ids()))
+        .collect();
+    let target_commits: HashMap<&CommitId, Commit> = target_ids
+        .into_par_iter()
+        .map(|id| Ok::<_, BackendError>((id, repo.store().get_commit(id)?)))
+        .collect::<Result<_, _>>()?;
     let print_branch_target =
         |formatter: &mut dyn Formatter, target: &RefTarget| -> Result<(), CommandError> {
             if let Some(id) = target.as_normal() {
                 write!(formatter, ": ")?;
-                let commit = repo.store().get_commit(id)?;
-                no_branches_template.format(&commit, formatter)?;
+                no_branches_template.format(&target_commits[id], formatter)?;
                 writeln!(formatter)?;
             } else {
                 write!(formatter, " ")?;
                 write!(formatter.labeled("conflict"), "(conflicted)")?;
                 writeln!(formatter, ":")?;
                 for id in target.removed_ids() {
-                    let commit = repo.store().get_commit(id)?;
                     write!(formatter, "  - ")?;
-                    no_branches_template.format(&commit, formatter)?;
+                    no_branches_template.format(&target_commits[id], formatter)?;
                     writeln!(formatter)?;
                 }
                 for id in target.added_ids() {
-                    let commit = repo.store().get_commit(id)?;
                     write!(formatter, "  + ")?;
-                    no_branches_template.format(&commit, formatter)?;
+                    no_branches_template.format(&target_commits[id], formatter)?;
                     writeln!(formatter)?;
                 }
             }
             Ok(())
         };
 
+    let max_count = command
+        .settings()
+        .config()
+        .get::<usize>("branches.max-count")?
+        .max(1);
+    // The count is truncated once it exceeds the limit.
+    let format_count = |count: usize| {
+        if count > max_count {
+            format!("{max_count}+")
+        } else {
+            count.to_string()
+        }
+    };
+    let print_ahead_behind = |formatter: &mut dyn Formatter,
+                              local_target: &RefTarget,
+                              remote_target: &RefTarget|
+     -> Result<(), CommandError> {
+        if local_target.is_absent() {
+            return Ok(());
+        }
+        let (remote_ahead_count, local_ahead_count) =
+            count_ahead_behind(repo.as_ref(), local_target, remote_target, Some(max_count))?;
+        if remote_ahead_count == 0 && local_ahead_count == 0 {
+            write!(formatter, " (in sync)")?;
+            return Ok(());
+        }
+        write!(formatter, " (")?;
+        if remote_ahead_count != 0 {
+            write!(
+                formatter.labeled("ahead"),
+                "ahead by {} commits",
+                format_count(remote_ahead_count)
+            )?;
+        }
+        if remote_ahead_count != 0 && local_ahead_count != 0 {
+            write!(formatter, ", ")?;
+        }
+        if local_ahead_count != 0 {
+            write!(
+                formatter.labeled("behind"),
+                "behind by {} commits",
+                format_count(local_ahead_count)
+            )?;
+        }
+        write!(formatter, ")")?;
+        Ok(())
+    };
+
+    let base = match &args.base {
+        Some(Some(revision_str)) => Some(revision_str.to_string()),
+        Some(None) => Some(
+            command
+                .settings()
+                .config()
+                .get_string("revsets.trunk")
+                .optional()?
+                .unwrap_or_else(|| "trunk()".to_owned()),
+        ),
+        None => None,
+    };
+    let base_ids: Vec<CommitId> = if let Some(base) = &base {
+        let expression = workspace_command.parse_revset(base, Some(ui))?;
+        let ids = workspace_command
+            .evaluate_revset(expression)?
+            .iter()
+            .collect_vec();
+        if ids.is_empty() {
+            return Err(user_error(format!(
+                "Base revset \"{base}\" didn't resolve to any revisions"
+            )));
+        }
+        ids
+    } else {
+        vec![]
+    };
+    let print_base_ahead_behind =
+        |formatter: &mut dyn Formatter, local_target: &RefTarget| -> Result<(), CommandError> {
+            let Some(base) = &base else {
+                return Ok(());
+            };
+            // A conflicted branch is compared by the union of its added targets.
+            let local_ids = local_target.added_ids().cloned().collect_vec();
+            let (ahead_count, behind_count) =
+                count_ahead_behind_ids(repo.as_ref(), &base_ids, &local_ids, Some(max_count))?;
+            write!(formatter, " (")?;
+            write!(
+                formatter.labeled("ahead"),
+                "{} ahead of {base}",
+                format_count(ahead_count)
+            )?;
+            write!(formatter, ", ")?;
+            write!(
+                formatter.labeled("behind"),
+                "{} behind {base}",
+                format_count(behind_count)
+            )?;
+            write!(formatter, ")")?;
+            Ok(())
+        };
+
+    let last_ops = if args.last_op {
+        let names = branches_to_list.iter().map(|(name, _)| *name).collect_vec();
+        find_last_branch_operations(repo.operation(), &names, MAX_LAST_OP_SEARCH_COUNT)?
+    } else {
+        HashMap::new()
+    };
+
+    // A local branch not tracking any remote would be created by "jj git push"
+    // only if there's a remote to push to.
+    let has_push_remote = remote_views
+        .keys()
+        .any(|remote| remote != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO);
+
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
 
-    let branches_to_list = view.branches().filter(|&(name, _)| {
-        branch_names_to_list
-            .as_ref()
-            .map_or(true, |branch_names| branch_names.contains(name))
-    });
     for (name, branch_target) in branches_to_list {
         let (tracking_remote_refs, untracked_remote_refs) =
             branch_target
@@ -625,40 +2353,65 @@ fn cmd_branch_list(
                 .partition::<Vec<_>, _>(|&(_, remote_ref)| remote_ref.is_tracking());
 
         if branch_target.local_target.is_present() || !tracking_remote_refs.is_empty() {
+            // Mark the branch if "jj git push" would create, move, or delete it
+            // on any tracking remote.
+            let mut pushable_remote_refs = tracking_remote_refs
+                .iter()
+                .filter(|&&(remote, _)| remote != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO)
+                .peekable();
+            let out_of_sync = if pushable_remote_refs.peek().is_none() {
+                has_push_remote && branch_target.local_target.is_present()
+            } else {
+                pushable_remote_refs.any(|&(remote, remote_ref)| {
+                    remote_ref.target != *branch_target.local_target
+                        || view.is_remote_branch_deletion_pending(name, remote)
+                })
+            };
             write!(formatter.labeled("branch"), "{name}")?;
+            if out_of_sync {
+                write!(formatter.labeled("branch_out_of_sync"), "*")?;
+            }
             if branch_target.local_target.is_present() {
+                print_base_ahead_behind(formatter, branch_target.local_target)?;
                 print_branch_target(formatter, branch_target.local_target)?;
+                for line in view.get_branch_description(name).lines() {
+                    write!(formatter, "  ")?;
+                    writeln!(formatter.labeled("description"), "{line}")?;
+                }
             } else {
                 writeln!(formatter, " (deleted)")?;
             }
+            if let Some(op) = last_ops.get(name) {
+                let metadata = &op.store_operation().metadata;
+                write!(formatter, "  Last changed by operation ")?;
+                formatter.with_label("op_log", |formatter| {
+                    write!(formatter.labeled("id"), "{}", short_operation_hash(op.id()))
+                })?;
+                writeln!(
+                    formatter,
+                    " at {}: {}",
+                    format_absolute_timestamp(&metadata.end_time),
+                    metadata.description
+                )?;
+            } else if args.last_op {
+                writeln!(
+                    formatter,
+                    "  Last changed by an operation older than the latest \
+                     {MAX_LAST_OP_SEARCH_COUNT} operations"
+                )?;
+            }
         }
 
         for &(remote, remote_ref) in &tracking_remote_refs {
-            let synced = remote_ref.target == *branch_target.local_target;
-            if !args.all && synced {
+            if !is_remote_ref_listed(remote, remote_ref, branch_target.local_target) {
                 continue;
             }
             write!(formatter, "  ")?;
             write!(formatter.labeled("branch"), "@{remote}")?;
-            let local_target = branch_target.local_target;
-            if local_target.is_present() && !synced {
-                let remote_added_ids = remote_ref.target.added_ids().cloned().collect_vec();
-                let local_added_ids = local_target.added_ids().cloned().collect_vec();
-                let remote_ahead_count =
-                    revset::walk_revs(repo.as_ref(), &remote_added_ids, &local_added_ids)?.count();
-                let local_ahead_count =
-                    revset::walk_revs(repo.as_ref(), &local_added_ids, &remote_added_ids)?.count();
-                if remote_ahead_count != 0 && local_ahead_count == 0 {
-                    write!(formatter, " (ahead by {remote_ahead_count} commits)")?;
-                } else if remote_ahead_count == 0 && local_ahead_count != 0 {
-                    write!(formatter, " (behind by {local_ahead_count} commits)")?;
-                } else if remote_ahead_count != 0 && local_ahead_count != 0 {
-                    write!(
-                        formatter,
-                        " (ahead by {remote_ahead_count} commits, behind by {local_ahead_count} \
-                         commits)"
-                    )?;
-                }
+            if view.is_remote_branch_deletion_pending(name, remote) {
+                write!(formatter, " (to be deleted)")?;
+            } else {
+                print_ahead_behind(formatter, branch_target.local_target, &remote_ref.target)?;
             }
             print_branch_target(formatter, &remote_ref.target)?;
         }
@@ -682,26 +2435,398 @@ fn cmd_branch_list(
             }
         }
 
-        if args.all {
-            for &(remote, remote_ref) in &untracked_remote_refs {
-                write!(formatter.labeled("branch"), "{name}@{remote}")?;
-                print_branch_target(formatter, &remote_ref.target)?;
+        for &(remote, remote_ref) in &untracked_remote_refs {
+            if !is_remote_ref_listed(remote, remote_ref, branch_target.local_target) {
+                continue;
             }
+            write!(formatter.labeled("branch"), "{name}@{remote}")?;
+            print_ahead_behind(formatter, branch_target.local_target, &remote_ref.target)?;
+            print_branch_target(formatter, &remote_ref.target)?;
         }
     }
 
-    Ok(())
+    check_conflicts()
 }
 
-fn is_fast_forward(repo: &dyn Repo, branch_name: &str, new_target_id: &CommitId) -> bool {
-    let current_target = repo.view().get_local_branch(branch_name);
-    if current_target.is_present() {
-        // Strictly speaking, "all" current targets should be ancestors, but we allow
-        // conflict resolution by setting branch to "any" of the old target descendants.
-        current_target
-            .added_ids()
-            .any(|add| repo.index().is_ancestor(add, new_target_id))
-    } else {
-        true
+/// Maximum number of operations `jj branch list --last-op` looks at.
+const MAX_LAST_OP_SEARCH_COUNT: usize = 1000;
+
+/// Finds the most recent operation that changed the local target of each of
+/// the `branch_names`, searching at most `max_ops` operations from `head_op`.
+/// Branches not changed by any of the searched operations are omitted.
+fn find_last_branch_operations<'a>(
+    head_op: &Operation,
+    branch_names: &[&'a str],
+    max_ops: usize,
+) -> Result<HashMap<&'a str, Operation>, CommandError> {
+    let mut found = HashMap::new();
+    let mut pending_names = branch_names.to_vec();
+    for op in operation::walk_ancestors(head_op).take(max_ops) {
+        if pending_names.is_empty() {
+            break;
+        }
+        let view = op.view()?;
+        let parent_views: Vec<_> = op
+            .parents()
+            .iter()
+            .map(|parent| parent.view())
+            .try_collect()?;
+        pending_names.retain(|&name| {
+            let target = view.store_view().local_branches.get(name);
+            // The root operation changed the branch only if it created it.
+            let changed = if parent_views.is_empty() {
+                target.is_some()
+            } else {
+                parent_views
+                    .iter()
+                    .any(|parent_view| parent_view.store_view().local_branches.get(name) != target)
+            };
+            if changed {
+                found.insert(name, op.clone());
+            }
+            !changed
+        });
+    }
+    Ok(found)
+}
+
+fn is_conflicted_or_diverged(
+    repo: &dyn Repo,
+    branch_target: &BranchTarget,
+) -> Result<bool, CommandError> {
+    let local_target = branch_target.local_target;
+    if local_target.has_conflict() {
+        return Ok(true);
+    }
+    for (_, remote_ref) in &branch_target.remote_refs {
+        if remote_ref.target.has_conflict() {
+            return Ok(true);
+        }
+        if remote_ref.is_tracking() && local_target.is_present() {
+            // Only whether the counts are nonzero matters, so a single commit
+            // is walked on each side.
+            let (ahead, behind) =
+                count_ahead_behind(repo, local_target, &remote_ref.target, Some(0))?;
+            if ahead != 0 && behind != 0 {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Sorts the name-ordered `branches` by the given key, and then reverses the
+/// whole order if `reverse` is set.
+fn sort_branches<'a>(
+    repo: &dyn Repo,
+    mut branches: Vec<(&'a str, BranchTarget<'a>)>,
+    sort_key: Option<BranchListSortKey>,
+    reverse: bool,
+) -> Result<Vec<(&'a str, BranchTarget<'a>)>, CommandError> {
+    if let Some(sort_key) = sort_key {
+        branches = sort_branches_by_key(repo, branches, sort_key)?;
+    }
+    if reverse {
+        branches.reverse();
+    }
+    Ok(branches)
+}
+
+/// Sorts the `branches` by the given key. Branches with equal keys are sorted
+/// by name in ascending order.
+fn sort_branches_by_key<'a>(
+    repo: &dyn Repo,
+    branches: Vec<(&'a str, BranchTarget<'a>)>,
+    sort_key: BranchListSortKey,
+) -> Result<Vec<(&'a str, BranchTarget<'a>)>, CommandError> {
+    let get_signature: Option<fn(&Commit) -> &Signature> = match sort_key.kind {
+        BranchListSort::Name => None,
+        BranchListSort::CommitterDate => Some(Commit::committer),
+        BranchListSort::AuthorDate => Some(Commit::author),
+    };
+    let mut keyed_branches: Vec<_> = branches
+        .into_iter()
+        .map(|(name, branch_target)| {
+            let mut timestamp = None;
+            if let Some(get_signature) = get_signature {
+                for id in branch_target.local_target.added_ids() {
+                    let commit = repo.store().get_commit(id)?;
+                    let commit_timestamp = get_signature(&commit).timestamp.timestamp.clone();
+                    timestamp = timestamp.max(Some(commit_timestamp));
+                }
+            }
+            Ok::<_, CommandError>((timestamp, name, branch_target))
+        })
+        .try_collect()?;
+    keyed_branches.sort_by(|(t1, name1, target1), (t2, name2, target2)| {
+        let order = match sort_key.kind {
+            BranchListSort::Name => name1.cmp(name2),
+            BranchListSort::CommitterDate | BranchListSort::AuthorDate => t1.cmp(t2),
+        };
+        let order = if sort_key.descending {
+            order.reverse()
+        } else {
+            order
+        };
+        // Deleted branches go last regardless of the sort key.
+        let deleted1 = target1.local_target.is_absent();
+        let deleted2 = target2.local_target.is_absent();
+        deleted1
+            .cmp(&deleted2)
+            .then(order)
+            .then_with(|| name1.cmp(name2))
+    });
+    Ok(keyed_branches
+        .into_iter()
+        .map(|(_, name, branch_target)| (name, branch_target))
+        .collect())
+}
+
+/// Flattens the branch into local and remote entries in the same order and
+/// with the same visibility rules as the default output.
+fn branch_list_items(
+    store: &Arc<Store>,
+    name: &str,
+    branch_target: BranchTarget,
+    include_synced_and_untracked: bool,
+) -> Result<Vec<BranchListItem>, BackendError> {
+    let local_target = branch_target.local_target;
+    let remotes = branch_target
+        .remote_refs
+        .iter()
+        .map(|&(remote, _)| remote.to_owned())
+        .collect_vec();
+    let (tracking_remote_refs, untracked_remote_refs) = branch_target
+        .remote_refs
+        .into_iter()
+        .partition::<Vec<_>, _>(|&(_, remote_ref)| remote_ref.is_tracking());
+    let make_item = |remote: Option<&str>, target: &RefTarget| {
+        Ok::<_, BackendError>(BranchListItem {
+            name: name.to_owned(),
+            remote: remote.map(|remote| remote.to_owned()),
+            target: target.clone(),
+            target_commits: target
+                .added_ids()
+                .map(|id| store.get_commit(id))
+                .try_collect()?,
+            remotes: remotes.clone(),
+        })
+    };
+
+    let mut items = vec![];
+    if local_target.is_present() || !tracking_remote_refs.is_empty() {
+        items.push(make_item(None, local_target)?);
+    }
+    for (remote, remote_ref) in tracking_remote_refs {
+        if include_synced_and_untracked || remote_ref.target != *local_target {
+            items.push(make_item(Some(remote), &remote_ref.target)?);
+        }
+    }
+    if include_synced_and_untracked {
+        for (remote, remote_ref) in untracked_remote_refs {
+            items.push(make_item(Some(remote), &remote_ref.target)?);
+        }
+    }
+    Ok(items)
+}
+
+#[derive(serde::Serialize)]
+struct BranchListJsonEntry<'a> {
+    name: &'a str,
+    local_target: Option<RefTargetJson>,
+    conflicted: bool,
+    remotes: BTreeMap<&'a str, RemoteBranchJsonEntry>,
+}
+
+#[derive(serde::Serialize)]
+struct RemoteBranchJsonEntry {
+    target: Option<RefTargetJson>,
+    tracking: bool,
+    ahead: Option<usize>,
+    behind: Option<usize>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+enum RefTargetJson {
+    Normal(String),
+    Conflicted {
+        adds: Vec<String>,
+        removes: Vec<String>,
+    },
+}
+
+impl RefTargetJson {
+    /// Returns `None` if the target is absent.
+    fn new(target: &RefTarget) -> Option<Self> {
+        if target.is_absent() {
+            None
+        } else if let Some(id) = target.as_normal() {
+            Some(RefTargetJson::Normal(id.hex()))
+        } else {
+            Some(RefTargetJson::Conflicted {
+                adds: target.added_ids().map(|id| id.hex()).collect(),
+                removes: target.removed_ids().map(|id| id.hex()).collect(),
+            })
+        }
+    }
+}
+
+/// Builds the JSON representation of the branch, or returns `None` if the
+/// branch wouldn't be listed.
+fn branch_list_json_entry<'a>(
+    repo: &dyn Repo,
+    name: &'a str,
+    branch_target: BranchTarget<'a>,
+    include_untracked: bool,
+) -> Result<Option<BranchListJsonEntry<'a>>, CommandError> {
+    let local_target = branch_target.local_target;
+    let mut remotes = BTreeMap::new();
+    for (remote, remote_ref) in branch_target.remote_refs {
+        if !remote_ref.is_tracking() && !include_untracked {
+            continue;
+        }
+        let (ahead, behind) = if remote_ref.is_tracking() && local_target.is_present() {
+            let (ahead, behind) = count_ahead_behind(repo, local_target, &remote_ref.target, None)?;
+            (Some(ahead), Some(behind))
+        } else {
+            (None, None)
+        };
+        let entry = RemoteBranchJsonEntry {
+            target: RefTargetJson::new(&remote_ref.target),
+            tracking: remote_ref.is_tracking(),
+            ahead,
+            behind,
+        };
+        remotes.insert(remote, entry);
+    }
+    if local_target.is_absent() && remotes.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(BranchListJsonEntry {
+        name,
+        local_target: RefTargetJson::new(local_target),
+        conflicted: local_target.has_conflict(),
+        remotes,
+    }))
+}
+
+/// Returns the numbers of commits the remote target is ahead and behind the
+/// local target.
+///
+/// If `max_count` is specified, each count stops at `max_count + 1` so that
+/// hugely diverged branches don't have to be walked entirely. A count larger
+/// than `max_count` means that there are more commits.
+fn count_ahead_behind(
+    repo: &dyn Repo,
+    local_target: &RefTarget,
+    remote_target: &RefTarget,
+    max_count: Option<usize>,
+) -> Result<(usize, usize), CommandError> {
+    let local_added_ids = local_target.added_ids().cloned().collect_vec();
+    let remote_added_ids = remote_target.added_ids().cloned().collect_vec();
+    count_ahead_behind_ids(repo, &local_added_ids, &remote_added_ids, max_count)
+}
+
+/// Counts the commits only reachable from `other_ids`, and the ones only
+/// reachable from `ids`.
+fn count_ahead_behind_ids(
+    repo: &dyn Repo,
+    ids: &[CommitId],
+    other_ids: &[CommitId],
+    max_count: Option<usize>,
+) -> Result<(usize, usize), CommandError> {
+    let count = |wanted: &[CommitId], unwanted: &[CommitId]| -> Result<usize, CommandError> {
+        let revset = revset::walk_revs(repo, wanted, unwanted)?;
+        let count = match max_count {
+            Some(max_count) => revset.iter().take(max_count.saturating_add(1)).count(),
+            None => revset.iter().count(),
+        };
+        Ok(count)
+    };
+    let ahead = count(other_ids, ids)?;
+    let behind = count(ids, other_ids)?;
+    Ok((ahead, behind))
+}
+
+/// Returns the branches that can't be fast-forwarded to the new targets, in
+/// the given order.
+///
+/// A conflicted branch can be fast-forwarded only if every added target is an
+/// ancestor of the new target.
+///
+/// Branches are grouped by new target, and the current targets of each group
+/// are looked up by a single index traversal.
+fn find_backward_branches<'a>(
+    repo: &dyn Repo,
+    branch_targets: &[(&'a str, &'a CommitId)],
+) -> Result<Vec<(&'a str, &'a CommitId)>, CommandError> {
+    let mut branches_by_new_target: HashMap<&CommitId, Vec<(&str, &RefTarget)>> = HashMap::new();
+    for &(branch_name, new_target_id) in branch_targets {
+        let current_target = repo.view().get_local_branch(branch_name);
+        if current_target.is_present() {
+            branches_by_new_target
+                .entry(new_target_id)
+                .or_default()
+                .push((branch_name, current_target));
+        }
+    }
+    let mut backward_names = HashSet::new();
+    for (new_target_id, branches) in branches_by_new_target {
+        let current_ids = branches
+            .iter()
+            .flat_map(|(_, target)| target.added_ids())
+            .cloned()
+            .collect_vec();
+        let ancestor_ids: HashSet<CommitId> = RevsetExpression::commits(current_ids)
+            .intersection(&RevsetExpression::commit(new_target_id.clone()).ancestors())
+            .resolve(repo)?
+            .evaluate(repo)?
+            .iter()
+            .collect();
+        // A conflicted branch is fast-forwarded only if all of its adds are
+        // ancestors. Otherwise, the other adds would be silently orphaned.
+        backward_names.extend(
+            branches
+                .iter()
+                .filter(|(_, target)| !target.added_ids().all(|id| ancestor_ids.contains(id)))
+                .map(|&(name, _)| name),
+        );
+    }
+    Ok(branch_targets
+        .iter()
+        .filter(|(name, _)| backward_names.contains(name))
+        .copied()
+        .collect())
+}
+
+/// Fails if any of the branches would be moved backwards or sideways.
+fn check_fast_forward(
+    repo: &dyn Repo,
+    branch_targets: &[(&str, Commit)],
+) -> Result<(), CommandError> {
+    let branch_target_ids = branch_targets
+        .iter()
+        .map(|(branch_name, target_commit)| (*branch_name, target_commit.id()))
+        .collect_vec();
+    let backward_branches = find_backward_branches(repo, &branch_target_ids)?;
+    if backward_branches.is_empty() {
+        return Ok(());
+    }
+    let mut message = "Refusing to move branch backwards or sideways:".to_owned();
+    for (branch_name, new_target_id) in backward_branches {
+        let current_target = repo.view().get_local_branch(branch_name);
+        let current = current_target.added_ids().map(short_commit_hash).join(", ");
+        let current = if current_target.has_conflict() {
+            format!("conflicted ({current})")
+        } else {
+            current
+        };
+        let new = short_commit_hash(new_target_id);
+        message.push_str(&format!("\n  {branch_name}: {current} -> {new}"));
     }
+    Err(user_error_with_hint(
+        message,
+        "Use --allow-backwards to allow it.",
+    ))
 }
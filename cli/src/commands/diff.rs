@@ -12,11 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use jj_lib::merge::MergedTreeValue;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo_path::RepoPath;
 use jj_lib::rewrite::merge_commit_trees;
 use tracing::instrument;
 
-use crate::cli_util::{CommandError, CommandHelper, RevisionArg};
-use crate::diff_util::{diff_formats_for, show_diff, DiffFormatArgs};
+use crate::cli_util::{
+    user_error, CommandError, CommandHelper, PathGlobArgs, RevisionArg, WorkspaceCommandHelper,
+};
+use crate::diff_util::{diff_formats_for, show_diff, show_file_diff, DiffFormatArgs};
 use crate::ui::Ui;
 
 #[derive(clap::Args, Clone, Debug)]
@@ -33,6 +38,31 @@ pub(crate) struct DiffArgs {
     /// Restrict the diff to these paths
     #[arg(value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
+    /// Compare the file at this path in the "from" revision with the file at
+    /// `--to-path` in the "to" revision
+    ///
+    /// The two files are compared directly even if their paths differ, and the
+    /// rest of the tree is ignored.
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "to_path",
+        conflicts_with_all = ["paths", "glob", "no_glob"],
+        value_hint = clap::ValueHint::FilePath
+    )]
+    from_path: Option<String>,
+    /// Compare the file at `--from-path` in the "from" revision with the file
+    /// at this path in the "to" revision
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "from_path",
+        conflicts_with_all = ["paths", "glob", "no_glob"],
+        value_hint = clap::ValueHint::FilePath
+    )]
+    to_path: Option<String>,
+    #[command(flatten)]
+    path_globs: PathGlobArgs,
     #[command(flatten)]
     format: DiffFormatArgs,
 }
@@ -46,20 +76,46 @@ pub(crate) fn cmd_diff(
     let workspace_command = command.workspace_helper(ui)?;
     let from_tree;
     let to_tree;
+    let from_description;
+    let to_description;
     if args.from.is_some() || args.to.is_some() {
-        let from = workspace_command.resolve_single_rev(args.from.as_deref().unwrap_or("@"), ui)?;
+        let from_str = args.from.as_deref().unwrap_or("@");
+        let from = workspace_command.resolve_single_rev(from_str, ui)?;
         from_tree = from.tree()?;
-        let to = workspace_command.resolve_single_rev(args.to.as_deref().unwrap_or("@"), ui)?;
+        from_description = format!("revision {from_str}");
+        let to_str = args.to.as_deref().unwrap_or("@");
+        let to = workspace_command.resolve_single_rev(to_str, ui)?;
         to_tree = to.tree()?;
+        to_description = format!("revision {to_str}");
     } else {
-        let commit =
-            workspace_command.resolve_single_rev(args.revision.as_deref().unwrap_or("@"), ui)?;
+        let revision_str = args.revision.as_deref().unwrap_or("@");
+        let commit = workspace_command.resolve_single_rev(revision_str, ui)?;
         let parents = commit.parents();
         from_tree = merge_commit_trees(workspace_command.repo().as_ref(), &parents)?;
-        to_tree = commit.tree()?
+        to_tree = commit.tree()?;
+        from_description = format!("the parents of revision {revision_str}");
+        to_description = format!("revision {revision_str}");
+    }
+    let diff_formats = diff_formats_for(command.settings(), "diff", &args.format)?;
+    if let (Some(from_path), Some(to_path)) = (&args.from_path, &args.to_path) {
+        let (from_path, from_value) =
+            file_path_value(&workspace_command, &from_tree, from_path, &from_description)?;
+        let (to_path, to_value) =
+            file_path_value(&workspace_command, &to_tree, to_path, &to_description)?;
+        ui.request_pager();
+        show_file_diff(
+            ui,
+            ui.stdout_formatter().as_mut(),
+            &workspace_command,
+            &from_path,
+            &from_value,
+            &to_path,
+            &to_value,
+            &diff_formats,
+        )?;
+        return Ok(());
     }
-    let matcher = workspace_command.matcher_from_values(&args.paths)?;
-    let diff_formats = diff_formats_for(command.settings(), &args.format)?;
+    let matcher = workspace_command.matcher_from_path_args(&args.paths, &args.path_globs)?;
     ui.request_pager();
     show_diff(
         ui,
@@ -72,3 +128,24 @@ pub(crate) fn cmd_diff(
     )?;
     Ok(())
 }
+
+/// Looks up the file at `path_str` in `tree`.
+fn file_path_value(
+    workspace_command: &WorkspaceCommandHelper,
+    tree: &MergedTree,
+    path_str: &str,
+    revision_description: &str,
+) -> Result<(RepoPath, MergedTreeValue), CommandError> {
+    let path = workspace_command.parse_file_path(path_str)?;
+    let value = tree.path_value(&path);
+    if value.is_absent() {
+        return Err(user_error(format!(
+            "Path {path_str} doesn't exist in {revision_description}"
+        )));
+    } else if value.is_tree() {
+        return Err(user_error(format!(
+            "Path {path_str} is a directory in {revision_description}"
+        )));
+    }
+    Ok((path, value))
+}
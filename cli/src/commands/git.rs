@@ -700,6 +700,7 @@ fn cmd_git_push(
     let mut branch_updates = vec![];
     if args.all {
         for (branch_name, targets) in repo.view().local_remote_branches(&remote) {
+            let targets = targets_to_push(repo.view(), branch_name, &remote, targets);
             match classify_branch_update(branch_name, &remote, targets) {
                 Ok(Some(update)) => branch_updates.push((branch_name.to_owned(), update)),
                 Ok(None) => {}
@@ -709,6 +710,7 @@ fn cmd_git_push(
         tx_description = format!("push all branches to git remote {remote}");
     } else if args.deleted {
         for (branch_name, targets) in repo.view().local_remote_branches(&remote) {
+            let targets = targets_to_push(repo.view(), branch_name, &remote, targets);
             if targets.local_target.is_present() {
                 continue;
             }
@@ -812,9 +814,17 @@ fn cmd_git_push(
         let branches_targeted = repo
             .view()
             .local_remote_branches(&remote)
-            .filter(|(_, targets)| {
+            .filter(|&(branch_name, targets)| {
                 let mut local_ids = targets.local_target.added_ids();
                 local_ids.any(|id| revision_commit_ids.contains(id))
+                    || use_default_revset
+                        && repo
+                            .view()
+                            .is_remote_branch_deletion_pending(branch_name, &remote)
+            })
+            .map(|(branch_name, targets)| {
+                let targets = targets_to_push(repo.view(), branch_name, &remote, targets);
+                (branch_name, targets)
             })
             .collect_vec();
         for &(branch_name, targets) in &branches_targeted {
@@ -1044,6 +1054,24 @@ fn classify_branch_update(
     }
 }
 
+/// Returns the targets to push the branch with. If the remote branch is marked
+/// to be deleted, the local branch is considered absent.
+fn targets_to_push<'a>(
+    view: &View,
+    branch_name: &str,
+    remote_name: &str,
+    targets: TrackingRefPair<'a>,
+) -> TrackingRefPair<'a> {
+    if view.is_remote_branch_deletion_pending(branch_name, remote_name) {
+        TrackingRefPair {
+            local_target: RefTarget::absent_ref(),
+            ..targets
+        }
+    } else {
+        targets
+    }
+}
+
 fn find_branches_to_push<'a>(
     view: &'a View,
     branch_patterns: &[StringPattern],
@@ -1055,6 +1083,7 @@ fn find_branches_to_push<'a>(
     for pattern in branch_patterns {
         let mut matches = view
             .local_remote_branches_matching(pattern, remote_name)
+            .map(|(name, targets)| (name, targets_to_push(view, name, remote_name, targets)))
             .filter(|(_, targets)| {
                 // If the remote exists but is not tracking, the absent local shouldn't
                 // be considered a deleted branch.
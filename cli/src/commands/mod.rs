@@ -68,12 +68,12 @@ use tracing::instrument;
 use crate::cli_util::{
     self, check_stale_working_copy, print_checkout_stats, print_git_import_stats,
     resolve_multiple_nonempty_revsets_default_single, run_ui_editor, short_commit_hash, user_error,
-    user_error_with_hint, Args, CommandError, CommandHelper, LogContentFormat, RevisionArg,
-    WorkspaceCommandHelper,
+    user_error_with_hint, Args, CommandError, CommandHelper, LogContentFormat, PathGlobArgs,
+    RevisionArg, WorkspaceCommandHelper,
 };
 use crate::diff_util::{self, DiffFormat, DiffFormatArgs};
 use crate::formatter::{Formatter, PlainTextFormatter};
-use crate::graphlog::{get_graphlog, Edge};
+use crate::graphlog::{get_graphlog, Edge, GraphNodeSymbols};
 use crate::text_util;
 use crate::ui::Ui;
 
@@ -213,13 +213,16 @@ struct LogArgs {
     /// Show commits modifying the given paths
     #[arg(value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
+    #[command(flatten)]
+    path_globs: PathGlobArgs,
     /// Show revisions in the opposite order (older revisions first)
     #[arg(long)]
     reversed: bool,
     /// Limit number of revisions to show
     ///
-    /// Applied after revisions are filtered and reordered.
-    #[arg(long, short)]
+    /// Applied after revisions are filtered and reordered. Only the shown
+    /// revisions are read from the commit store.
+    #[arg(long, short, visible_short_alias = 'n')]
     limit: Option<usize>,
     /// Don't show the graph, show a flat list of revisions
     #[arg(long)]
@@ -269,9 +272,14 @@ struct ObslogArgs {
 ///
 /// This excludes changes from other commits by temporarily rebasing `--from`
 /// onto `--to`'s parents. If you wish to compare the same change across
-/// versions, consider `jj obslog -p` instead.
+/// versions, consider `jj obslog -p` or `--from-predecessor` instead.
 #[derive(clap::Args, Clone, Debug)]
-#[command(group(ArgGroup::new("to_diff").args(&["from", "to"]).multiple(true).required(true)))]
+#[command(group(
+    ArgGroup::new("to_diff")
+        .args(&["from", "to", "from_predecessor"])
+        .multiple(true)
+        .required(true)
+))]
 struct InterdiffArgs {
     /// Show changes from this revision
     #[arg(long)]
@@ -279,10 +287,17 @@ struct InterdiffArgs {
     /// Show changes to this revision
     #[arg(long)]
     to: Option<RevisionArg>,
+    /// Show changes from the previous version of the `--to` revision
+    ///
+    /// The previous version is the first predecessor shown by `jj obslog`.
+    #[arg(long, conflicts_with = "from")]
+    from_predecessor: bool,
     /// Restrict the diff to these paths
     #[arg(value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
     #[command(flatten)]
+    path_globs: PathGlobArgs,
+    #[command(flatten)]
     format: DiffFormatArgs,
 }
 
@@ -1036,7 +1051,7 @@ fn cmd_show(ui: &mut Ui, command: &CommandHelper, args: &ShowArgs) -> Result<(),
     let commit = workspace_command.resolve_single_rev(&args.revision, ui)?;
     let template_string = command.settings().config().get_string("templates.show")?;
     let template = workspace_command.parse_commit_template(&template_string)?;
-    let diff_formats = diff_util::diff_formats_for(command.settings(), &args.format)?;
+    let diff_formats = diff_util::diff_formats_for(command.settings(), "show", &args.format)?;
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
@@ -1079,6 +1094,8 @@ fn cmd_status(
                 formatter,
                 &workspace_command,
                 parent_tree.diff(&tree, &EverythingMatcher),
+                None,
+                None,
             )?;
         }
 
@@ -1168,25 +1185,31 @@ fn cmd_log(ui: &mut Ui, command: &CommandHelper, args: &LogArgs) -> Result<(), C
             RevsetExpression::union_all(&expressions)
         };
         if !args.paths.is_empty() {
-            let repo_paths: Vec<_> = args
-                .paths
-                .iter()
-                .map(|path_arg| workspace_command.parse_file_path(path_arg))
-                .try_collect()?;
-            expression = expression.intersection(&RevsetExpression::filter(
-                RevsetFilterPredicate::File(Some(repo_paths)),
-            ));
+            let (repo_paths, globs) =
+                workspace_command.parse_file_patterns(&args.paths, &args.path_globs)?;
+            let mut filters = vec![];
+            if !repo_paths.is_empty() {
+                filters.push(RevsetExpression::filter(RevsetFilterPredicate::File(Some(
+                    repo_paths,
+                ))));
+            }
+            if !globs.is_empty() {
+                filters.push(RevsetExpression::filter(RevsetFilterPredicate::FileGlobs(
+                    globs,
+                )));
+            }
+            expression = expression.intersection(&RevsetExpression::union_all(&filters));
         }
         revset::optimize(expression)
     };
     let repo = workspace_command.repo();
     let wc_commit_id = workspace_command.get_wc_commit_id();
-    let matcher = workspace_command.matcher_from_values(&args.paths)?;
-    let revset = workspace_command.evaluate_revset(revset_expression)?;
+    let matcher = workspace_command.matcher_from_path_args(&args.paths, &args.path_globs)?;
+    let revset = workspace_command.evaluate_revset(revset_expression.clone())?;
 
     let store = repo.store();
     let diff_formats =
-        diff_util::diff_formats_for_log(command.settings(), &args.diff_format, args.patch)?;
+        diff_util::diff_formats_for_log(command.settings(), "log", &args.diff_format, args.patch)?;
 
     let template_string = match &args.template {
         Some(value) => value.to_string(),
@@ -1201,8 +1224,24 @@ fn cmd_log(ui: &mut Ui, command: &CommandHelper, args: &LogArgs) -> Result<(), C
         let formatter = formatter.as_mut();
 
         if !args.no_graph {
-            let mut graph = get_graphlog(command.settings(), formatter.raw());
-            let default_node_symbol = graph.default_node_symbol().to_owned();
+            let mut graph = get_graphlog(command.settings(), formatter.raw())?;
+            let node_symbols =
+                GraphNodeSymbols::from_settings(command.settings(), graph.default_node_symbol())?;
+            // Only look for conflicted and immutable commits if they are drawn
+            // differently
+            let check_conflicts = node_symbols.conflict != node_symbols.default;
+            let immutable_revset = if node_symbols.immutable != node_symbols.default {
+                let immutable_expression = workspace_command.immutable_revset_expression()?;
+                Some(
+                    workspace_command
+                        .evaluate_revset(revset_expression.intersection(&immutable_expression))?,
+                )
+            } else {
+                None
+            };
+            let is_immutable = immutable_revset
+                .as_ref()
+                .map(|revset| revset.containing_fn());
             let forward_iter = TopoGroupedRevsetGraphIterator::new(revset.iter_graph());
             let iter: Box<dyn Iterator<Item = _>> = if args.reversed {
                 Box::new(ReverseRevsetGraphIterator::new(forward_iter))
@@ -1255,9 +1294,13 @@ fn cmd_log(ui: &mut Ui, command: &CommandHelper, args: &LogArgs) -> Result<(), C
                     )?;
                 }
                 let node_symbol = if Some(&commit_id) == wc_commit_id {
-                    "@"
+                    &node_symbols.working_copy
+                } else if check_conflicts && commit.has_conflict()? {
+                    &node_symbols.conflict
+                } else if is_immutable.as_ref().is_some_and(|f| f(&commit_id)) {
+                    &node_symbols.immutable
                 } else {
-                    &default_node_symbol
+                    &node_symbols.default
                 };
 
                 graph.add_node(
@@ -1323,8 +1366,12 @@ fn cmd_obslog(ui: &mut Ui, command: &CommandHelper, args: &ObslogArgs) -> Result
     let start_commit = workspace_command.resolve_single_rev(&args.revision, ui)?;
     let wc_commit_id = workspace_command.get_wc_commit_id();
 
-    let diff_formats =
-        diff_util::diff_formats_for_log(command.settings(), &args.diff_format, args.patch)?;
+    let diff_formats = diff_util::diff_formats_for_log(
+        command.settings(),
+        "obslog",
+        &args.diff_format,
+        args.patch,
+    )?;
 
     let template_string = match &args.template {
         Some(value) => value.to_string(),
@@ -1347,8 +1394,11 @@ fn cmd_obslog(ui: &mut Ui, command: &CommandHelper, args: &ObslogArgs) -> Result
         commits.truncate(n);
     }
     if !args.no_graph {
-        let mut graph = get_graphlog(command.settings(), formatter.raw());
-        let default_node_symbol = graph.default_node_symbol().to_owned();
+        let mut graph = get_graphlog(command.settings(), formatter.raw())?;
+        let node_symbols =
+            GraphNodeSymbols::from_settings(command.settings(), graph.default_node_symbol())?;
+        // Only look for conflicted commits if they are drawn differently
+        let check_conflicts = node_symbols.conflict != node_symbols.default;
         for commit in commits {
             let mut edges = vec![];
             for predecessor in &commit.predecessors() {
@@ -1374,9 +1424,11 @@ fn cmd_obslog(ui: &mut Ui, command: &CommandHelper, args: &ObslogArgs) -> Result
                 )?;
             }
             let node_symbol = if Some(commit.id()) == wc_commit_id {
-                "@"
+                &node_symbols.working_copy
+            } else if check_conflicts && commit.has_conflict()? {
+                &node_symbols.conflict
             } else {
-                &default_node_symbol
+                &node_symbols.default
             };
             graph.add_node(
                 commit.id(),
@@ -1430,13 +1482,22 @@ fn cmd_interdiff(
     args: &InterdiffArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
-    let from = workspace_command.resolve_single_rev(args.from.as_deref().unwrap_or("@"), ui)?;
     let to = workspace_command.resolve_single_rev(args.to.as_deref().unwrap_or("@"), ui)?;
+    let from = if args.from_predecessor {
+        to.predecessors().into_iter().next().ok_or_else(|| {
+            user_error(format!(
+                "Commit {} has no predecessor",
+                short_commit_hash(to.id())
+            ))
+        })?
+    } else {
+        workspace_command.resolve_single_rev(args.from.as_deref().unwrap_or("@"), ui)?
+    };
 
     let from_tree = rebase_to_dest_parent(&workspace_command, &from, &to)?;
     let to_tree = to.tree()?;
-    let matcher = workspace_command.matcher_from_values(&args.paths)?;
-    let diff_formats = diff_util::diff_formats_for(command.settings(), &args.format)?;
+    let matcher = workspace_command.matcher_from_path_args(&args.paths, &args.path_globs)?;
+    let diff_formats = diff_util::diff_formats_for(command.settings(), "interdiff", &args.format)?;
     ui.request_pager();
     diff_util::show_diff(
         ui,
@@ -2359,7 +2420,10 @@ fn description_template_for_commit(
         workspace_command,
         commit,
         &EverythingMatcher,
-        &[DiffFormat::Summary],
+        &[DiffFormat::Summary {
+            renames: None,
+            max_file_size: None,
+        }],
     )?;
     let description = if commit.description().is_empty() {
         settings.default_description()
@@ -2382,15 +2446,16 @@ fn description_template_for_cmd_split(
     from_tree: &MergedTree,
     to_tree: &MergedTree,
 ) -> Result<String, CommandError> {
-    let mut diff_summary_bytes = Vec::new();
-    diff_util::show_diff(
+    let diff_summary_bytes = diff_util::diff_as_bytes(
         ui,
-        &mut PlainTextFormatter::new(&mut diff_summary_bytes),
         workspace_command,
         from_tree,
         to_tree,
         &EverythingMatcher,
-        &[DiffFormat::Summary],
+        &[DiffFormat::Summary {
+            renames: None,
+            max_file_size: None,
+        }],
     )?;
     let description = if overall_commit_description.is_empty() {
         settings.default_description()
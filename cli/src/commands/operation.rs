@@ -4,7 +4,7 @@ use jj_lib::operation;
 use jj_lib::repo::Repo;
 
 use crate::cli_util::{user_error, CommandError, CommandHelper, LogContentFormat};
-use crate::graphlog::{get_graphlog, Edge};
+use crate::graphlog::{get_graphlog, Edge, GraphNodeSymbols};
 use crate::operation_templater;
 use crate::templater::Template as _;
 use crate::ui::Ui;
@@ -113,8 +113,9 @@ fn cmd_op_log(
     let formatter = formatter.as_mut();
     let iter = operation::walk_ancestors(&head_op).take(args.limit.unwrap_or(usize::MAX));
     if !args.no_graph {
-        let mut graph = get_graphlog(command.settings(), formatter.raw());
-        let default_node_symbol = graph.default_node_symbol().to_owned();
+        let mut graph = get_graphlog(command.settings(), formatter.raw())?;
+        let node_symbols =
+            GraphNodeSymbols::from_settings(command.settings(), graph.default_node_symbol())?;
         for op in iter {
             let mut edges = vec![];
             for parent in op.parents() {
@@ -133,9 +134,9 @@ fn cmd_op_log(
                 buffer.push(b'\n');
             }
             let node_symbol = if is_head_op {
-                "@"
+                &node_symbols.working_copy
             } else {
-                &default_node_symbol
+                &node_symbols.default
             };
             graph.add_node(
                 op.id(),
@@ -175,8 +176,10 @@ fn view_with_desired_portions_restored(
         head_ids: repo_source.head_ids.clone(),
         public_head_ids: repo_source.public_head_ids.clone(),
         local_branches: repo_source.local_branches.clone(),
+        branch_descriptions: repo_source.branch_descriptions.clone(),
         tags: repo_source.tags.clone(),
         remote_views: remote_source.remote_views.clone(),
+        remote_branch_deletions: repo_source.remote_branch_deletions.clone(),
         git_refs: current_view.git_refs.clone(),
         git_head: current_view.git_head.clone(),
         wc_commit_ids: repo_source.wc_commit_ids.clone(),
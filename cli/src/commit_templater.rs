@@ -15,6 +15,7 @@
 use std::cmp::max;
 use std::collections::HashMap;
 use std::io;
+use std::marker::PhantomData;
 use std::rc::Rc;
 
 use itertools::Itertools as _;
@@ -40,21 +41,36 @@ use crate::templater::{
 };
 use crate::text_util;
 
-struct CommitTemplateLanguage<'repo, 'b> {
+struct CommitTemplateLanguage<'repo, 'b, C> {
     repo: &'repo dyn Repo,
     workspace_id: &'b WorkspaceId,
     id_prefix_context: &'repo IdPrefixContext,
     keyword_cache: CommitKeywordCache,
+    context: PhantomData<fn(&C)>,
 }
 
-impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo, '_> {
-    type Context = Commit;
-    type Property = CommitTemplatePropertyKind<'repo>;
+/// Context type the commit template language can be evaluated against.
+///
+/// The types and methods are shared, but each context type provides its own
+/// set of top-level keywords.
+trait CommitTemplateContext: Sized {
+    fn build_keyword<'repo>(
+        language: &CommitTemplateLanguage<'repo, '_, Self>,
+        name: &str,
+        span: pest::Span,
+    ) -> TemplateParseResult<CommitTemplatePropertyKind<'repo, Self>>;
+}
+
+impl<'repo, C: CommitTemplateContext + 'repo> TemplateLanguage<'repo>
+    for CommitTemplateLanguage<'repo, '_, C>
+{
+    type Context = C;
+    type Property = CommitTemplatePropertyKind<'repo, C>;
 
     template_builder::impl_core_wrap_property_fns!('repo, CommitTemplatePropertyKind::Core);
 
     fn build_keyword(&self, name: &str, span: pest::Span) -> TemplateParseResult<Self::Property> {
-        build_commit_keyword(self, name, span)
+        C::build_keyword(self, name, span)
     }
 
     fn build_method(
@@ -101,64 +117,62 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo, '_> {
     }
 }
 
-// If we need to add multiple languages that support Commit types, this can be
-// turned into a trait which extends TemplateLanguage.
-impl<'repo> CommitTemplateLanguage<'repo, '_> {
+impl<'repo, C: 'repo> CommitTemplateLanguage<'repo, '_, C> {
     fn wrap_commit(
         &self,
-        property: impl TemplateProperty<Commit, Output = Commit> + 'repo,
-    ) -> CommitTemplatePropertyKind<'repo> {
+        property: impl TemplateProperty<C, Output = Commit> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo, C> {
         CommitTemplatePropertyKind::Commit(Box::new(property))
     }
 
     fn wrap_commit_list(
         &self,
-        property: impl TemplateProperty<Commit, Output = Vec<Commit>> + 'repo,
-    ) -> CommitTemplatePropertyKind<'repo> {
+        property: impl TemplateProperty<C, Output = Vec<Commit>> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo, C> {
         CommitTemplatePropertyKind::CommitList(Box::new(property))
     }
 
     fn wrap_ref_name(
         &self,
-        property: impl TemplateProperty<Commit, Output = RefName> + 'repo,
-    ) -> CommitTemplatePropertyKind<'repo> {
+        property: impl TemplateProperty<C, Output = RefName> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo, C> {
         CommitTemplatePropertyKind::RefName(Box::new(property))
     }
 
     fn wrap_ref_name_list(
         &self,
-        property: impl TemplateProperty<Commit, Output = Vec<RefName>> + 'repo,
-    ) -> CommitTemplatePropertyKind<'repo> {
+        property: impl TemplateProperty<C, Output = Vec<RefName>> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo, C> {
         CommitTemplatePropertyKind::RefNameList(Box::new(property))
     }
 
     fn wrap_commit_or_change_id(
         &self,
-        property: impl TemplateProperty<Commit, Output = CommitOrChangeId> + 'repo,
-    ) -> CommitTemplatePropertyKind<'repo> {
+        property: impl TemplateProperty<C, Output = CommitOrChangeId> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo, C> {
         CommitTemplatePropertyKind::CommitOrChangeId(Box::new(property))
     }
 
     fn wrap_shortest_id_prefix(
         &self,
-        property: impl TemplateProperty<Commit, Output = ShortestIdPrefix> + 'repo,
-    ) -> CommitTemplatePropertyKind<'repo> {
+        property: impl TemplateProperty<C, Output = ShortestIdPrefix> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo, C> {
         CommitTemplatePropertyKind::ShortestIdPrefix(Box::new(property))
     }
 }
 
-enum CommitTemplatePropertyKind<'repo> {
-    Core(CoreTemplatePropertyKind<'repo, Commit>),
-    Commit(Box<dyn TemplateProperty<Commit, Output = Commit> + 'repo>),
-    CommitList(Box<dyn TemplateProperty<Commit, Output = Vec<Commit>> + 'repo>),
-    RefName(Box<dyn TemplateProperty<Commit, Output = RefName> + 'repo>),
-    RefNameList(Box<dyn TemplateProperty<Commit, Output = Vec<RefName>> + 'repo>),
-    CommitOrChangeId(Box<dyn TemplateProperty<Commit, Output = CommitOrChangeId> + 'repo>),
-    ShortestIdPrefix(Box<dyn TemplateProperty<Commit, Output = ShortestIdPrefix> + 'repo>),
+enum CommitTemplatePropertyKind<'repo, C> {
+    Core(CoreTemplatePropertyKind<'repo, C>),
+    Commit(Box<dyn TemplateProperty<C, Output = Commit> + 'repo>),
+    CommitList(Box<dyn TemplateProperty<C, Output = Vec<Commit>> + 'repo>),
+    RefName(Box<dyn TemplateProperty<C, Output = RefName> + 'repo>),
+    RefNameList(Box<dyn TemplateProperty<C, Output = Vec<RefName>> + 'repo>),
+    CommitOrChangeId(Box<dyn TemplateProperty<C, Output = CommitOrChangeId> + 'repo>),
+    ShortestIdPrefix(Box<dyn TemplateProperty<C, Output = ShortestIdPrefix> + 'repo>),
 }
 
-impl<'repo> IntoTemplateProperty<'repo, Commit> for CommitTemplatePropertyKind<'repo> {
-    fn try_into_boolean(self) -> Option<Box<dyn TemplateProperty<Commit, Output = bool> + 'repo>> {
+impl<'repo, C: 'repo> IntoTemplateProperty<'repo, C> for CommitTemplatePropertyKind<'repo, C> {
+    fn try_into_boolean(self) -> Option<Box<dyn TemplateProperty<C, Output = bool> + 'repo>> {
         match self {
             CommitTemplatePropertyKind::Core(property) => property.try_into_boolean(),
             CommitTemplatePropertyKind::Commit(_) => None,
@@ -174,16 +188,14 @@ impl<'repo> IntoTemplateProperty<'repo, Commit> for CommitTemplatePropertyKind<'
         }
     }
 
-    fn try_into_integer(self) -> Option<Box<dyn TemplateProperty<Commit, Output = i64> + 'repo>> {
+    fn try_into_integer(self) -> Option<Box<dyn TemplateProperty<C, Output = i64> + 'repo>> {
         match self {
             CommitTemplatePropertyKind::Core(property) => property.try_into_integer(),
             _ => None,
         }
     }
 
-    fn try_into_plain_text(
-        self,
-    ) -> Option<Box<dyn TemplateProperty<Commit, Output = String> + 'repo>> {
+    fn try_into_plain_text(self) -> Option<Box<dyn TemplateProperty<C, Output = String> + 'repo>> {
         match self {
             CommitTemplatePropertyKind::Core(property) => property.try_into_plain_text(),
             _ => {
@@ -193,7 +205,7 @@ impl<'repo> IntoTemplateProperty<'repo, Commit> for CommitTemplatePropertyKind<'
         }
     }
 
-    fn try_into_template(self) -> Option<Box<dyn Template<Commit> + 'repo>> {
+    fn try_into_template(self) -> Option<Box<dyn Template<C> + 'repo>> {
         match self {
             CommitTemplatePropertyKind::Core(property) => property.try_into_template(),
             CommitTemplatePropertyKind::Commit(_) => None,
@@ -235,27 +247,37 @@ impl CommitKeywordCache {
     }
 }
 
+impl CommitTemplateContext for Commit {
+    fn build_keyword<'repo>(
+        language: &CommitTemplateLanguage<'repo, '_, Self>,
+        name: &str,
+        span: pest::Span,
+    ) -> TemplateParseResult<CommitTemplatePropertyKind<'repo, Self>> {
+        build_commit_keyword(language, name, span)
+    }
+}
+
 fn build_commit_keyword<'repo>(
-    language: &CommitTemplateLanguage<'repo, '_>,
+    language: &CommitTemplateLanguage<'repo, '_, Commit>,
     name: &str,
     span: pest::Span,
-) -> TemplateParseResult<CommitTemplatePropertyKind<'repo>> {
+) -> TemplateParseResult<CommitTemplatePropertyKind<'repo, Commit>> {
     // Commit object is lightweight (a few Arc + CommitId), so just clone it
     // to turn into a property type. Abstraction over "for<'a> (&'a T) -> &'a T"
     // and "(&T) -> T" wouldn't be simple. If we want to remove Clone/Rc/Arc,
     // maybe we can add an abstraction that takes "Fn(&Commit) -> O" and returns
-    // "TemplateProperty<Commit, Output = O>".
+    // "TemplateProperty<C, Output = O>".
     let property = TemplatePropertyFn(|commit: &Commit| commit.clone());
     build_commit_keyword_opt(language, property, name)
         .ok_or_else(|| TemplateParseError::no_such_keyword(name, span))
 }
 
-fn build_commit_method<'repo>(
-    language: &CommitTemplateLanguage<'repo, '_>,
-    _build_ctx: &BuildContext<CommitTemplatePropertyKind<'repo>>,
-    self_property: impl TemplateProperty<Commit, Output = Commit> + 'repo,
+fn build_commit_method<'repo, C: CommitTemplateContext + 'repo>(
+    language: &CommitTemplateLanguage<'repo, '_, C>,
+    _build_ctx: &BuildContext<CommitTemplatePropertyKind<'repo, C>>,
+    self_property: impl TemplateProperty<C, Output = Commit> + 'repo,
     function: &FunctionCallNode,
-) -> TemplateParseResult<CommitTemplatePropertyKind<'repo>> {
+) -> TemplateParseResult<CommitTemplatePropertyKind<'repo, C>> {
     if let Some(property) = build_commit_keyword_opt(language, self_property, function.name) {
         template_parser::expect_no_arguments(function)?;
         Ok(property)
@@ -264,22 +286,22 @@ fn build_commit_method<'repo>(
     }
 }
 
-fn build_commit_keyword_opt<'repo>(
-    language: &CommitTemplateLanguage<'repo, '_>,
-    property: impl TemplateProperty<Commit, Output = Commit> + 'repo,
+fn build_commit_keyword_opt<'repo, C: CommitTemplateContext + 'repo>(
+    language: &CommitTemplateLanguage<'repo, '_, C>,
+    property: impl TemplateProperty<C, Output = Commit> + 'repo,
     name: &str,
-) -> Option<CommitTemplatePropertyKind<'repo>> {
-    fn wrap_fn<'repo, O>(
-        property: impl TemplateProperty<Commit, Output = Commit> + 'repo,
+) -> Option<CommitTemplatePropertyKind<'repo, C>> {
+    fn wrap_fn<'repo, C: 'repo, O>(
+        property: impl TemplateProperty<C, Output = Commit> + 'repo,
         f: impl Fn(&Commit) -> O + 'repo,
-    ) -> impl TemplateProperty<Commit, Output = O> + 'repo {
+    ) -> impl TemplateProperty<C, Output = O> + 'repo {
         TemplateFunction::new(property, move |commit| f(&commit))
     }
-    fn wrap_repo_fn<'repo, O>(
+    fn wrap_repo_fn<'repo, C: 'repo, O>(
         repo: &'repo dyn Repo,
-        property: impl TemplateProperty<Commit, Output = Commit> + 'repo,
+        property: impl TemplateProperty<C, Output = Commit> + 'repo,
         f: impl Fn(&dyn Repo, &Commit) -> O + 'repo,
-    ) -> impl TemplateProperty<Commit, Output = O> + 'repo {
+    ) -> impl TemplateProperty<C, Output = O> + 'repo {
         TemplateFunction::new(property, move |commit| f(repo, &commit))
     }
 
@@ -382,6 +404,54 @@ fn build_commit_keyword_opt<'repo>(
     Some(property)
 }
 
+/// Local or remote branch to be rendered by `jj branch list`.
+#[derive(Clone, Debug)]
+pub struct BranchListItem {
+    /// Local name.
+    pub name: String,
+    /// Remote name if this is a remote branch.
+    pub remote: Option<String>,
+    /// Target of the local or remote branch.
+    pub target: RefTarget,
+    /// Commits the branch points to, which are the added targets of a
+    /// conflicted branch, and none for a deleted branch.
+    pub target_commits: Vec<Commit>,
+    /// Names of the remotes the branch exists on.
+    pub remotes: Vec<String>,
+}
+
+impl CommitTemplateContext for BranchListItem {
+    fn build_keyword<'repo>(
+        language: &CommitTemplateLanguage<'repo, '_, Self>,
+        name: &str,
+        span: pest::Span,
+    ) -> TemplateParseResult<CommitTemplatePropertyKind<'repo, Self>> {
+        build_branch_list_keyword(language, name, span)
+    }
+}
+
+fn build_branch_list_keyword<'repo>(
+    language: &CommitTemplateLanguage<'repo, '_, BranchListItem>,
+    name: &str,
+    span: pest::Span,
+) -> TemplateParseResult<CommitTemplatePropertyKind<'repo, BranchListItem>> {
+    fn wrap_fn<O, F: Fn(&BranchListItem) -> O>(f: F) -> TemplatePropertyFn<F> {
+        TemplatePropertyFn(f)
+    }
+
+    let property = match name {
+        "name" => language.wrap_string(wrap_fn(|item| item.name.clone())),
+        "remote" => language.wrap_string(wrap_fn(|item| item.remote.clone().unwrap_or_default())),
+        // There's no optional type, so the target commits are listed.
+        "target" => language.wrap_commit_list(wrap_fn(|item| item.target_commits.clone())),
+        "remotes" => language.wrap_string_list(wrap_fn(|item| item.remotes.clone())),
+        "conflicted" => language.wrap_boolean(wrap_fn(|item| item.target.has_conflict())),
+        "deleted" => language.wrap_boolean(wrap_fn(|item| item.target.is_absent())),
+        _ => return Err(TemplateParseError::no_such_keyword(name, span)),
+    };
+    Ok(property)
+}
+
 // TODO: return Vec<String>
 fn extract_working_copies(repo: &dyn Repo, commit: &Commit) -> String {
     let wc_commit_ids = repo.view().wc_commit_ids();
@@ -445,12 +515,12 @@ impl Template<()> for Vec<RefName> {
     }
 }
 
-fn build_ref_name_method<'repo>(
-    language: &CommitTemplateLanguage<'repo, '_>,
-    _build_ctx: &BuildContext<CommitTemplatePropertyKind<'repo>>,
-    self_property: impl TemplateProperty<Commit, Output = RefName> + 'repo,
+fn build_ref_name_method<'repo, C: CommitTemplateContext + 'repo>(
+    language: &CommitTemplateLanguage<'repo, '_, C>,
+    _build_ctx: &BuildContext<CommitTemplatePropertyKind<'repo, C>>,
+    self_property: impl TemplateProperty<C, Output = RefName> + 'repo,
     function: &FunctionCallNode,
-) -> TemplateParseResult<CommitTemplatePropertyKind<'repo>> {
+) -> TemplateParseResult<CommitTemplatePropertyKind<'repo, C>> {
     let property = match function.name {
         "name" => {
             template_parser::expect_no_arguments(function)?;
@@ -603,12 +673,12 @@ impl Template<()> for CommitOrChangeId {
     }
 }
 
-fn build_commit_or_change_id_method<'repo>(
-    language: &CommitTemplateLanguage<'repo, '_>,
-    build_ctx: &BuildContext<CommitTemplatePropertyKind<'repo>>,
-    self_property: impl TemplateProperty<Commit, Output = CommitOrChangeId> + 'repo,
+fn build_commit_or_change_id_method<'repo, C: CommitTemplateContext + 'repo>(
+    language: &CommitTemplateLanguage<'repo, '_, C>,
+    build_ctx: &BuildContext<CommitTemplatePropertyKind<'repo, C>>,
+    self_property: impl TemplateProperty<C, Output = CommitOrChangeId> + 'repo,
     function: &FunctionCallNode,
-) -> TemplateParseResult<CommitTemplatePropertyKind<'repo>> {
+) -> TemplateParseResult<CommitTemplatePropertyKind<'repo, C>> {
     let parse_optional_integer = |function| -> Result<Option<_>, TemplateParseError> {
         let ([], [len_node]) = template_parser::expect_arguments(function)?;
         len_node
@@ -674,12 +744,12 @@ impl ShortestIdPrefix {
     }
 }
 
-fn build_shortest_id_prefix_method<'repo>(
-    language: &CommitTemplateLanguage<'repo, '_>,
-    _build_ctx: &BuildContext<CommitTemplatePropertyKind<'repo>>,
-    self_property: impl TemplateProperty<Commit, Output = ShortestIdPrefix> + 'repo,
+fn build_shortest_id_prefix_method<'repo, C: CommitTemplateContext + 'repo>(
+    language: &CommitTemplateLanguage<'repo, '_, C>,
+    _build_ctx: &BuildContext<CommitTemplatePropertyKind<'repo, C>>,
+    self_property: impl TemplateProperty<C, Output = ShortestIdPrefix> + 'repo,
     function: &FunctionCallNode,
-) -> TemplateParseResult<CommitTemplatePropertyKind<'repo>> {
+) -> TemplateParseResult<CommitTemplatePropertyKind<'repo, C>> {
     let property = match function.name {
         "prefix" => {
             template_parser::expect_no_arguments(function)?;
@@ -716,11 +786,44 @@ pub fn parse<'repo>(
     template_text: &str,
     aliases_map: &TemplateAliasesMap,
 ) -> TemplateParseResult<Box<dyn Template<Commit> + 'repo>> {
+    parse_with_context(
+        repo,
+        workspace_id,
+        id_prefix_context,
+        template_text,
+        aliases_map,
+    )
+}
+
+pub fn parse_branch_list<'repo>(
+    repo: &'repo dyn Repo,
+    workspace_id: &WorkspaceId,
+    id_prefix_context: &'repo IdPrefixContext,
+    template_text: &str,
+    aliases_map: &TemplateAliasesMap,
+) -> TemplateParseResult<Box<dyn Template<BranchListItem> + 'repo>> {
+    parse_with_context(
+        repo,
+        workspace_id,
+        id_prefix_context,
+        template_text,
+        aliases_map,
+    )
+}
+
+fn parse_with_context<'repo, C: CommitTemplateContext + 'repo>(
+    repo: &'repo dyn Repo,
+    workspace_id: &WorkspaceId,
+    id_prefix_context: &'repo IdPrefixContext,
+    template_text: &str,
+    aliases_map: &TemplateAliasesMap,
+) -> TemplateParseResult<Box<dyn Template<C> + 'repo>> {
     let language = CommitTemplateLanguage {
         repo,
         workspace_id,
         id_prefix_context,
         keyword_cache: CommitKeywordCache::default(),
+        context: PhantomData,
     };
     let node = template_parser::parse(template_text, aliases_map)?;
     template_builder::build(&language, &node)
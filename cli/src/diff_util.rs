@@ -12,37 +12,44 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::cmp::max;
-use std::collections::VecDeque;
-use std::io;
+use std::borrow::Cow;
+use std::cmp::{max, Reverse};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::{Hash as _, Hasher as _};
+use std::io::{self, Read as _};
+use std::iter;
 use std::ops::Range;
 use std::sync::Arc;
 
 use futures::executor::block_on;
 use itertools::Itertools;
-use jj_lib::backend::{ObjectId, TreeValue};
+use jj_lib::backend::{BackendError, CommitId, FileId, ObjectId, TreeValue};
 use jj_lib::commit::Commit;
 use jj_lib::diff::{Diff, DiffHunk};
-use jj_lib::files::DiffLine;
-use jj_lib::matchers::Matcher;
+use jj_lib::files::{ContentHunk, DiffLine};
+use jj_lib::matchers::{EverythingMatcher, Matcher};
 use jj_lib::merge::{Merge, MergedTreeValue};
-use jj_lib::merged_tree::{MergedTree, TreeDiffIterator};
+use jj_lib::merged_tree::{MergedTree, MergedTreeBuilder, TreeDiffIterator};
 use jj_lib::repo::{ReadonlyRepo, Repo};
 use jj_lib::repo_path::RepoPath;
-use jj_lib::settings::{ConfigResultExt as _, UserSettings};
+use jj_lib::settings::{ConfigResultExt as _, HumanByteSize, UserSettings};
+use jj_lib::store::Store;
 use jj_lib::{conflicts, diff, files, rewrite};
+use rayon::prelude::*;
 use tracing::instrument;
 use unicode_width::UnicodeWidthStr as _;
 
 use crate::cli_util::{CommandError, WorkspaceCommandHelper};
-use crate::formatter::Formatter;
+use crate::formatter::{FormatRecorder, Formatter, PlainTextFormatter};
 use crate::merge_tools::{self, ExternalMergeTool, MergeTool};
 use crate::text_util;
 use crate::ui::Ui;
 
 #[derive(clap::Args, Clone, Debug)]
-#[command(group(clap::ArgGroup::new("short-format").args(&["summary", "stat", "types"])))]
-#[command(group(clap::ArgGroup::new("long-format").args(&["git", "color_words", "tool"])))]
+#[command(group(clap::ArgGroup::new("short-format").args(&["summary", "stat", "types", "name_only"])))]
+#[command(group(clap::ArgGroup::new("long-format").args(&["git", "color_words", "word_diff", "tool"])))]
 pub struct DiffFormatArgs {
     /// For each path, show only whether it was modified, added, or removed
     #[arg(long, short)]
@@ -50,6 +57,20 @@ pub struct DiffFormatArgs {
     /// Show a histogram of the changes
     #[arg(long)]
     pub stat: bool,
+    /// Show the distribution of changed lines across directories
+    ///
+    /// Like `git diff --dirstat=lines`, the added and removed lines of each
+    /// file are counted towards its directory, truncated to the first DEPTH
+    /// components (default: 3), and shown as a percentage of all changed
+    /// lines.
+    #[arg(
+        long,
+        value_name = "DEPTH",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "3"
+    )]
+    pub dirstat: Option<usize>,
     /// For each path, show only its type before and after
     ///
     /// The diff is shown as two letters. The first letter indicates the type
@@ -59,35 +80,254 @@ pub struct DiffFormatArgs {
     /// Git submodule.
     #[arg(long)]
     pub types: bool,
+    /// For each path, show only its name
+    #[arg(long)]
+    pub name_only: bool,
+    /// Separate the paths of `--name-only` by NUL characters instead of
+    /// newlines
+    #[arg(short = 'z', requires = "name_only")]
+    pub null_separated: bool,
     /// Show a Git-format diff
     #[arg(long)]
     pub git: bool,
     /// Show a word-level diff with changes indicated only by color
     #[arg(long)]
     pub color_words: bool,
+    /// Show a Git-format diff with the changed words marked within lines
+    #[arg(long)]
+    pub word_diff: bool,
     /// Generate diff by external command
     #[arg(long)]
     pub tool: Option<String>,
+    /// Number of lines of context to show
+    #[arg(long, short = 'U', value_parser = parse_context_lines)]
+    pub context: Option<usize>,
+    /// Ignore whitespace when comparing lines
+    #[arg(long, conflicts_with = "ignore_space_change")]
+    pub ignore_all_space: bool,
+    /// Ignore changes in amount of whitespace when comparing lines
+    #[arg(long)]
+    pub ignore_space_change: bool,
+    /// Show renamed files as removed and added files
+    #[arg(long)]
+    pub no_renames: bool,
+    /// Show binary files in the Git format as patches that `git apply` accepts
+    #[arg(long)]
+    pub binary: bool,
+    /// Show blocks of lines moved within the diff in different colors
+    ///
+    /// This applies to the Git and color-words formats. A block is shown as
+    /// moved if the same lines are removed in one place and added in another,
+    /// and they have at least 20 alphanumeric characters.
+    #[arg(long)]
+    pub color_moved: bool,
+    /// Show the changes of files larger than `ui.diff.max-file-size`
+    #[arg(long)]
+    pub no_size_limit: bool,
+    /// Highlight whitespace errors in added and modified lines
+    ///
+    /// This applies to the Git and color-words formats. Trailing whitespace
+    /// and spaces before a tab in the indentation are labeled as
+    /// `whitespace-error`. Context and removed lines aren't highlighted.
+    #[arg(long)]
+    pub ws_error_highlight: bool,
+}
+
+const DEFAULT_CONTEXT_LINES: usize = 3;
+const MAX_CONTEXT_LINES: usize = 100_000;
+
+fn parse_context_lines(s: &str) -> Result<usize, String> {
+    let num_lines: usize = s.parse().map_err(|err| format!("{err}"))?;
+    if num_lines > MAX_CONTEXT_LINES {
+        return Err(format!("must be at most {MAX_CONTEXT_LINES}"));
+    }
+    Ok(num_lines)
+}
+
+/// How lines are compared when computing the content diff.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LineCompareMode {
+    /// Compares lines literally.
+    #[default]
+    Exact,
+    /// Compares lines ignoring any whitespace occurrences.
+    IgnoreAllSpace,
+    /// Compares lines ignoring changes in whitespace amount.
+    IgnoreSpaceChange,
+}
+
+impl LineCompareMode {
+    fn from_args(args: &DiffFormatArgs) -> Self {
+        if args.ignore_all_space {
+            LineCompareMode::IgnoreAllSpace
+        } else if args.ignore_space_change {
+            LineCompareMode::IgnoreSpaceChange
+        } else {
+            LineCompareMode::Exact
+        }
+    }
+}
+
+const DEFAULT_RENAME_THRESHOLD: u8 = 50;
+/// Similarity of removed and added files is only compared if there are at most
+/// this many pairs of them. Exact renames are detected regardless.
+const MAX_RENAME_CANDIDATE_PAIRS: usize = 100_000;
+
+/// How renamed files are detected in the diff.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RenameDetection {
+    /// Minimum similarity of the file contents in percent.
+    pub threshold: u8,
+}
+
+impl RenameDetection {
+    fn from_settings_and_args(
+        settings: &UserSettings,
+        args: &DiffFormatArgs,
+    ) -> Result<Option<Self>, config::ConfigError> {
+        if args.no_renames {
+            return Ok(None);
+        }
+        let threshold = match settings
+            .config()
+            .get::<i64>("ui.diff.rename-threshold")
+            .optional()?
+        {
+            Some(threshold) => u8::try_from(threshold)
+                .ok()
+                .filter(|threshold| *threshold <= 100)
+                .ok_or_else(|| {
+                    config::ConfigError::Message(format!(
+                        "invalid rename threshold: {threshold} (expected 0 to 100)"
+                    ))
+                })?,
+            None => DEFAULT_RENAME_THRESHOLD,
+        };
+        Ok(Some(RenameDetection { threshold }))
+    }
+}
+
+const DEFAULT_MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
+
+/// Returns the size in bytes above which the contents of a file aren't diffed,
+/// or `None` if there's no limit.
+fn max_file_size_from_settings_and_args(
+    settings: &UserSettings,
+    args: &DiffFormatArgs,
+) -> Result<Option<u64>, config::ConfigError> {
+    if args.no_size_limit {
+        return Ok(None);
+    }
+    let max_size = settings
+        .config()
+        .get::<HumanByteSize>("ui.diff.max-file-size")
+        .optional()?
+        .map_or(DEFAULT_MAX_FILE_SIZE, |size| size.0);
+    // Like `snapshot.max-new-file-size`, 0 means no limit.
+    Ok((max_size != 0).then_some(max_size))
+}
+
+/// Returns whether whitespace errors in added lines should be highlighted.
+fn highlight_whitespace_from_settings_and_args(
+    settings: &UserSettings,
+    args: &DiffFormatArgs,
+) -> Result<bool, config::ConfigError> {
+    if args.ws_error_highlight {
+        return Ok(true);
+    }
+    Ok(settings
+        .config()
+        .get_bool("ui.diff.highlight-whitespace")
+        .optional()?
+        .unwrap_or(false))
+}
+
+/// A file whose contents aren't diffed since it's larger than the limit. Only
+/// the limit is known since the rest of the file isn't read.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct FileTooLarge {
+    max_size: u64,
+}
+
+impl fmt::Display for FileTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "file too large to diff: more than {}, see ui.diff.max-file-size",
+            HumanByteSize(self.max_size)
+        )
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DiffFormat {
-    Summary,
-    Stat,
+    Summary {
+        renames: Option<RenameDetection>,
+        max_file_size: Option<u64>,
+    },
+    Stat {
+        max_file_size: Option<u64>,
+    },
+    DirStat {
+        depth: usize,
+        max_file_size: Option<u64>,
+    },
     Types,
-    Git,
-    ColorWords,
+    NameOnly {
+        null_separated: bool,
+    },
+    Git {
+        context: usize,
+        line_compare: LineCompareMode,
+        renames: Option<RenameDetection>,
+        binary: bool,
+        color_moved: bool,
+        highlight_whitespace: bool,
+        max_file_size: Option<u64>,
+    },
+    ColorWords {
+        context: usize,
+        line_compare: LineCompareMode,
+        renames: Option<RenameDetection>,
+        color_moved: bool,
+        highlight_whitespace: bool,
+        max_file_size: Option<u64>,
+    },
+    WordDiff {
+        context: usize,
+        line_compare: LineCompareMode,
+        renames: Option<RenameDetection>,
+        binary: bool,
+        max_file_size: Option<u64>,
+    },
     Tool(Box<ExternalMergeTool>),
 }
 
-/// Returns a list of requested diff formats, which will never be empty.
+/// How the file contents are rendered by the `Git`, `ColorWords`, and
+/// `WordDiff` formats.
+#[derive(Clone, Copy, Debug)]
+struct DiffRenderOptions {
+    context: usize,
+    line_compare: LineCompareMode,
+    /// Show the changed words within the lines of a Git diff.
+    word_diff: bool,
+    /// Write binary patches instead of only reporting that binary files differ.
+    binary: bool,
+    color_moved: bool,
+    highlight_whitespace: bool,
+    max_file_size: Option<u64>,
+}
+
+/// Returns a list of requested diff formats, which will never be empty. If no
+/// format is requested, the default format of the `command_name` is used.
 pub fn diff_formats_for(
     settings: &UserSettings,
+    command_name: &str,
     args: &DiffFormatArgs,
 ) -> Result<Vec<DiffFormat>, config::ConfigError> {
     let formats = diff_formats_from_args(settings, args)?;
     if formats.is_empty() {
-        Ok(vec![default_diff_format(settings)?])
+        Ok(vec![default_diff_format(settings, command_name, args)?])
     } else {
         Ok(formats)
     }
@@ -97,13 +337,14 @@ pub fn diff_formats_for(
 /// empty.
 pub fn diff_formats_for_log(
     settings: &UserSettings,
+    command_name: &str,
     args: &DiffFormatArgs,
     patch: bool,
 ) -> Result<Vec<DiffFormat>, config::ConfigError> {
     let mut formats = diff_formats_from_args(settings, args)?;
     // --patch implies default if no format other than --summary is specified
-    if patch && matches!(formats.as_slice(), [] | [DiffFormat::Summary]) {
-        formats.push(default_diff_format(settings)?);
+    if patch && matches!(formats.as_slice(), [] | [DiffFormat::Summary { .. }]) {
+        formats.push(default_diff_format(settings, command_name, args)?);
         formats.dedup();
     }
     Ok(formats)
@@ -113,16 +354,70 @@ fn diff_formats_from_args(
     settings: &UserSettings,
     args: &DiffFormatArgs,
 ) -> Result<Vec<DiffFormat>, config::ConfigError> {
+    let context = args.context.unwrap_or(DEFAULT_CONTEXT_LINES);
+    let line_compare = LineCompareMode::from_args(args);
+    let renames = RenameDetection::from_settings_and_args(settings, args)?;
+    let max_file_size = max_file_size_from_settings_and_args(settings, args)?;
+    let highlight_whitespace = highlight_whitespace_from_settings_and_args(settings, args)?;
     let mut formats = [
-        (args.summary, DiffFormat::Summary),
+        (
+            args.summary,
+            DiffFormat::Summary {
+                renames,
+                max_file_size,
+            },
+        ),
         (args.types, DiffFormat::Types),
-        (args.git, DiffFormat::Git),
-        (args.color_words, DiffFormat::ColorWords),
-        (args.stat, DiffFormat::Stat),
+        (
+            args.name_only,
+            DiffFormat::NameOnly {
+                null_separated: args.null_separated,
+            },
+        ),
+        (
+            args.git,
+            DiffFormat::Git {
+                context,
+                line_compare,
+                renames,
+                binary: args.binary,
+                color_moved: args.color_moved,
+                highlight_whitespace,
+                max_file_size,
+            },
+        ),
+        (
+            args.color_words,
+            DiffFormat::ColorWords {
+                context,
+                line_compare,
+                renames,
+                color_moved: args.color_moved,
+                highlight_whitespace,
+                max_file_size,
+            },
+        ),
+        (
+            args.word_diff,
+            DiffFormat::WordDiff {
+                context,
+                line_compare,
+                renames,
+                binary: args.binary,
+                max_file_size,
+            },
+        ),
+        (args.stat, DiffFormat::Stat { max_file_size }),
     ]
     .into_iter()
     .filter_map(|(arg, format)| arg.then_some(format))
     .collect_vec();
+    if let Some(depth) = args.dirstat {
+        formats.push(DiffFormat::DirStat {
+            depth,
+            max_file_size,
+        });
+    }
     if let Some(name) = &args.tool {
         let tool = merge_tools::get_tool_config(settings, name)?
             .unwrap_or_else(|| MergeTool::External(ExternalMergeTool::with_program(name)));
@@ -136,34 +431,86 @@ fn diff_formats_from_args(
     Ok(formats)
 }
 
-fn default_diff_format(settings: &UserSettings) -> Result<DiffFormat, config::ConfigError> {
+/// Names of the diff formats which can be set as the default in the config.
+const CONFIGURABLE_DIFF_FORMATS: &[&str] = &[
+    "color-words",
+    "git",
+    "stat",
+    "summary",
+    "types",
+    "word-diff",
+];
+
+/// Returns the default diff format of the command. The command-specific
+/// `ui.diff.format-for.<command>` takes precedence over the other settings.
+fn default_diff_format(
+    settings: &UserSettings,
+    command_name: &str,
+    args: &DiffFormatArgs,
+) -> Result<DiffFormat, config::ConfigError> {
     let config = settings.config();
-    if let Some(args) = config.get("ui.diff.tool").optional()? {
-        // External "tool" overrides the internal "format" option.
-        let tool = merge_tools::get_tool_config_from_args(settings, &args)?
-            .unwrap_or_else(|| MergeTool::External(ExternalMergeTool::with_diff_args(&args)));
-        match tool {
-            MergeTool::Builtin => {}
-            MergeTool::External(tool) => {
-                return Ok(DiffFormat::Tool(Box::new(tool)));
+    let command_key = format!("ui.diff.format-for.{command_name}");
+    let (key, name) = if let Some(name) = config.get_string(&command_key).optional()? {
+        (command_key, name)
+    } else {
+        if let Some(args) = config.get("ui.diff.tool").optional()? {
+            // External "tool" overrides the internal "format" option.
+            let tool = merge_tools::get_tool_config_from_args(settings, &args)?
+                .unwrap_or_else(|| MergeTool::External(ExternalMergeTool::with_diff_args(&args)));
+            match tool {
+                MergeTool::Builtin => {}
+                MergeTool::External(tool) => {
+                    return Ok(DiffFormat::Tool(Box::new(tool)));
+                }
             }
         }
-    }
-    let name = if let Some(name) = config.get_string("ui.diff.format").optional()? {
-        name
-    } else if let Some(name) = config.get_string("diff.format").optional()? {
-        name // old config name
-    } else {
-        "color-words".to_owned()
+        if let Some(name) = config.get_string("ui.diff.format").optional()? {
+            ("ui.diff.format".to_owned(), name)
+        } else if let Some(name) = config.get_string("diff.format").optional()? {
+            ("diff.format".to_owned(), name) // old config name
+        } else {
+            ("ui.diff.format".to_owned(), "color-words".to_owned())
+        }
     };
+    let context = args.context.unwrap_or(DEFAULT_CONTEXT_LINES);
+    let line_compare = LineCompareMode::from_args(args);
+    let renames = RenameDetection::from_settings_and_args(settings, args)?;
+    let max_file_size = max_file_size_from_settings_and_args(settings, args)?;
+    let highlight_whitespace = highlight_whitespace_from_settings_and_args(settings, args)?;
     match name.as_ref() {
-        "summary" => Ok(DiffFormat::Summary),
+        "summary" => Ok(DiffFormat::Summary {
+            renames,
+            max_file_size,
+        }),
         "types" => Ok(DiffFormat::Types),
-        "git" => Ok(DiffFormat::Git),
-        "color-words" => Ok(DiffFormat::ColorWords),
-        "stat" => Ok(DiffFormat::Stat),
+        "git" => Ok(DiffFormat::Git {
+            context,
+            line_compare,
+            renames,
+            binary: args.binary,
+            color_moved: args.color_moved,
+            highlight_whitespace,
+            max_file_size,
+        }),
+        "color-words" => Ok(DiffFormat::ColorWords {
+            context,
+            line_compare,
+            renames,
+            color_moved: args.color_moved,
+            highlight_whitespace,
+            max_file_size,
+        }),
+        "word-diff" => Ok(DiffFormat::WordDiff {
+            context,
+            line_compare,
+            renames,
+            binary: args.binary,
+            max_file_size,
+        }),
+        "stat" => Ok(DiffFormat::Stat { max_file_size }),
         _ => Err(config::ConfigError::Message(format!(
-            "invalid diff format: {name}"
+            "invalid diff format in {key}: {name} (expected one of {})",
+            CONFIGURABLE_DIFF_FORMATS.join(", ")
         ))),
     }
 }
@@ -177,36 +524,228 @@ pub fn show_diff(
     matcher: &dyn Matcher,
     formats: &[DiffFormat],
 ) -> Result<(), CommandError> {
+    let repo = workspace_command.repo();
+    show_diff_entries(
+        ui,
+        formatter,
+        workspace_command,
+        formats,
+        |renames, max_file_size| {
+            let tree_diff = from_tree.diff(to_tree, matcher);
+            diff_entries(repo, tree_diff, renames, max_file_size)
+        },
+        |ui, formatter, tool| {
+            merge_tools::generate_diff(ui, formatter.raw(), from_tree, to_tree, matcher, tool)?;
+            Ok(())
+        },
+    )
+}
+
+/// Shows the change from `from_value` at `from_path` to `to_value` at
+/// `to_path` as a single file, even if the paths differ. Unlike in a diff of
+/// trees, the two values are always compared with each other.
+#[allow(clippy::too_many_arguments)]
+pub fn show_file_diff(
+    ui: &Ui,
+    formatter: &mut dyn Formatter,
+    workspace_command: &WorkspaceCommandHelper,
+    from_path: &RepoPath,
+    from_value: &MergedTreeValue,
+    to_path: &RepoPath,
+    to_value: &MergedTreeValue,
+    formats: &[DiffFormat],
+) -> Result<(), CommandError> {
+    let store = workspace_command.repo().store();
+    show_diff_entries(
+        ui,
+        formatter,
+        workspace_command,
+        formats,
+        |_renames, max_file_size| {
+            let renamed_from = if from_path != to_path {
+                let similarity = content_similarity(
+                    store,
+                    (from_path, from_value),
+                    (to_path, to_value),
+                    max_file_size,
+                )?;
+                Some((from_path.clone(), similarity))
+            } else if from_value == to_value {
+                return Ok(vec![]);
+            } else {
+                None
+            };
+            Ok(vec![DiffEntry {
+                path: to_path.clone(),
+                left_value: from_value.clone(),
+                right_value: to_value.clone(),
+                renamed_from,
+            }])
+        },
+        |ui, formatter, tool| {
+            // The tool is given both files at the same path so that it
+            // compares them with each other.
+            let single_file_tree = |value: &MergedTreeValue| {
+                let mut tree_builder = MergedTreeBuilder::new(store.empty_merged_tree_id());
+                tree_builder.set_or_remove(to_path.clone(), value.clone());
+                let tree_id = tree_builder.write_tree(store)?;
+                store.get_root_tree(&tree_id)
+            };
+            let from_tree = single_file_tree(from_value)?;
+            let to_tree = single_file_tree(to_value)?;
+            merge_tools::generate_diff(
+                ui,
+                formatter.raw(),
+                &from_tree,
+                &to_tree,
+                &EverythingMatcher,
+                tool,
+            )?;
+            Ok(())
+        },
+    )
+}
+
+/// Shows the entries returned by `collect_entries` in each of the `formats`.
+/// The entries are collected with the rename detection and file size limit of
+/// each format. The external diff tool is run by `show_tool_diff`.
+fn show_diff_entries(
+    ui: &Ui,
+    formatter: &mut dyn Formatter,
+    workspace_command: &WorkspaceCommandHelper,
+    formats: &[DiffFormat],
+    collect_entries: impl Fn(
+        Option<RenameDetection>,
+        Option<u64>,
+    ) -> Result<Vec<DiffEntry>, CommandError>,
+    show_tool_diff: impl Fn(&Ui, &mut dyn Formatter, &ExternalMergeTool) -> Result<(), CommandError>,
+) -> Result<(), CommandError> {
+    // The line counts are shared by --stat and --dirstat so that the files are
+    // diffed only once. Both formats have the same size limit.
+    let stat_max_file_size = formats.iter().find_map(|format| match format {
+        DiffFormat::Stat { max_file_size } | DiffFormat::DirStat { max_file_size, .. } => {
+            Some(*max_file_size)
+        }
+        _ => None,
+    });
+    let diff_stats = if let Some(max_file_size) = stat_max_file_size {
+        let entries = collect_entries(None, None)?;
+        collect_diff_stats(workspace_command, &entries, max_file_size)?
+    } else {
+        vec![]
+    };
     for format in formats {
         match format {
-            DiffFormat::Summary => {
-                let tree_diff = from_tree.diff(to_tree, matcher);
-                show_diff_summary(formatter, workspace_command, tree_diff)?;
+            DiffFormat::Summary {
+                renames,
+                max_file_size,
+            } => {
+                let entries = collect_entries(*renames, *max_file_size)?;
+                write_diff_summary(formatter, workspace_command, &entries)?;
             }
-            DiffFormat::Stat => {
-                let tree_diff = from_tree.diff(to_tree, matcher);
-                show_diff_stat(ui, formatter, workspace_command, tree_diff)?;
+            DiffFormat::Stat { .. } => {
+                show_diff_stat(ui, formatter, &diff_stats)?;
+            }
+            DiffFormat::DirStat { depth, .. } => {
+                show_diff_dirstat(formatter, workspace_command, &diff_stats, *depth)?;
             }
             DiffFormat::Types => {
-                let tree_diff = from_tree.diff(to_tree, matcher);
-                show_types(formatter, workspace_command, tree_diff)?;
+                let entries = collect_entries(None, None)?;
+                show_types(formatter, workspace_command, &entries)?;
+            }
+            DiffFormat::NameOnly { null_separated } => {
+                let entries = collect_entries(None, None)?;
+                show_names(formatter, workspace_command, &entries, *null_separated)?;
             }
-            DiffFormat::Git => {
-                let tree_diff = from_tree.diff(to_tree, matcher);
-                show_git_diff(formatter, workspace_command, tree_diff)?;
+            &DiffFormat::Git {
+                context,
+                line_compare,
+                renames,
+                binary,
+                color_moved,
+                highlight_whitespace,
+                max_file_size,
+            } => {
+                let options = DiffRenderOptions {
+                    context,
+                    line_compare,
+                    word_diff: false,
+                    binary,
+                    color_moved,
+                    highlight_whitespace,
+                    max_file_size,
+                };
+                let entries = collect_entries(renames, max_file_size)?;
+                show_git_diff(formatter, workspace_command, entries, &options)?;
             }
-            DiffFormat::ColorWords => {
-                let tree_diff = from_tree.diff(to_tree, matcher);
-                show_color_words_diff(formatter, workspace_command, tree_diff)?;
+            &DiffFormat::ColorWords {
+                context,
+                line_compare,
+                renames,
+                color_moved,
+                highlight_whitespace,
+                max_file_size,
+            } => {
+                let options = DiffRenderOptions {
+                    context,
+                    line_compare,
+                    word_diff: false,
+                    binary: false,
+                    color_moved,
+                    highlight_whitespace,
+                    max_file_size,
+                };
+                let entries = collect_entries(renames, max_file_size)?;
+                show_color_words_diff(formatter, workspace_command, entries, &options)?;
+            }
+            &DiffFormat::WordDiff {
+                context,
+                line_compare,
+                renames,
+                binary,
+                max_file_size,
+            } => {
+                let options = DiffRenderOptions {
+                    context,
+                    line_compare,
+                    word_diff: true,
+                    binary,
+                    color_moved: false,
+                    highlight_whitespace: false,
+                    max_file_size,
+                };
+                let entries = collect_entries(renames, max_file_size)?;
+                show_git_diff(formatter, workspace_command, entries, &options)?;
             }
             DiffFormat::Tool(tool) => {
-                merge_tools::generate_diff(ui, formatter.raw(), from_tree, to_tree, matcher, tool)?;
+                show_tool_diff(ui, formatter, tool)?;
             }
         }
     }
     Ok(())
 }
 
+pub fn diff_as_bytes(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    from_tree: &MergedTree,
+    to_tree: &MergedTree,
+    matcher: &dyn Matcher,
+    formats: &[DiffFormat],
+) -> Result<Vec<u8>, CommandError> {
+    let mut diff_bytes = vec![];
+    show_diff(
+        ui,
+        &mut PlainTextFormatter::new(&mut diff_bytes),
+        workspace_command,
+        from_tree,
+        to_tree,
+        matcher,
+        formats,
+    )?;
+    Ok(diff_bytes)
+}
+
 pub fn show_patch(
     ui: &Ui,
     formatter: &mut dyn Formatter,
@@ -232,16 +771,28 @@ pub fn show_patch(
 fn show_color_words_diff_hunks(
     left: &[u8],
     right: &[u8],
+    num_context_lines: usize,
+    line_compare: LineCompareMode,
+    moved_lines: &MovedLines,
+    highlight_whitespace: bool,
     formatter: &mut dyn Formatter,
 ) -> io::Result<()> {
     const SKIPPED_CONTEXT_LINE: &str = "    ...\n";
-    let num_context_lines = 3;
+    if is_binary(left) || is_binary(right) {
+        writeln!(
+            formatter.labeled("binary"),
+            "    (binary) {} -> {} bytes",
+            left.len(),
+            right.len()
+        )?;
+        return Ok(());
+    }
     let mut context = VecDeque::new();
     // Have we printed "..." for any skipped context?
     let mut skipped_context = false;
     // Are the lines in `context` to be printed before the next modified line?
     let mut context_before = true;
-    for diff_line in files::diff(left, right) {
+    for diff_line in color_words_diff_lines(left, right, line_compare) {
         if diff_line.is_unmodified() {
             context.push_back(diff_line.clone());
             let mut start_skipping_context = false;
@@ -253,7 +804,12 @@ fn show_color_words_diff_hunks(
                 }
             } else if context.len() > num_context_lines * 2 + 1 {
                 for line in context.drain(..num_context_lines) {
-                    show_color_words_diff_line(formatter, &line)?;
+                    show_color_words_diff_line(
+                        formatter,
+                        &line,
+                        moved_lines,
+                        highlight_whitespace,
+                    )?;
                 }
                 start_skipping_context = true;
             }
@@ -265,10 +821,10 @@ fn show_color_words_diff_hunks(
             }
         } else {
             for line in &context {
-                show_color_words_diff_line(formatter, line)?;
+                show_color_words_diff_line(formatter, line, moved_lines, highlight_whitespace)?;
             }
             context.clear();
-            show_color_words_diff_line(formatter, &diff_line)?;
+            show_color_words_diff_line(formatter, &diff_line, moved_lines, highlight_whitespace)?;
             context_before = false;
             skipped_context = false;
         }
@@ -280,7 +836,7 @@ fn show_color_words_diff_hunks(
             context_before = true;
         }
         for line in &context {
-            show_color_words_diff_line(formatter, line)?;
+            show_color_words_diff_line(formatter, line, moved_lines, highlight_whitespace)?;
         }
         if context_before {
             formatter.write_str(SKIPPED_CONTEXT_LINE)?;
@@ -297,10 +853,101 @@ fn show_color_words_diff_hunks(
     Ok(())
 }
 
+/// Writes the changes of each side of a conflict from its base, in the order
+/// the sides are materialized in conflict markers.
+fn show_color_words_conflict_sides(
+    sides: &Merge<ContentHunk>,
+    num_context_lines: usize,
+    line_compare: LineCompareMode,
+    highlight_whitespace: bool,
+    formatter: &mut dyn Formatter,
+) -> io::Result<()> {
+    let bases = sides.removes();
+    for (side_index, side) in sides.adds().iter().enumerate() {
+        // The first two sides are relative to the first base, and each
+        // following side to the next base.
+        let base_index = side_index.saturating_sub(1);
+        let base_name = if bases.len() == 1 {
+            "base".to_owned()
+        } else {
+            format!("base #{}", base_index + 1)
+        };
+        writeln!(
+            formatter.labeled("conflict"),
+            "  Changes from {base_name} to side #{}:",
+            side_index + 1
+        )?;
+        show_color_words_diff_hunks(
+            &bases[base_index].0,
+            &side.0,
+            num_context_lines,
+            line_compare,
+            &MovedLines::default(),
+            highlight_whitespace,
+            formatter,
+        )?;
+    }
+    Ok(())
+}
+
 fn show_color_words_diff_line(
     formatter: &mut dyn Formatter,
     diff_line: &DiffLine,
+    moved_lines: &MovedLines,
+    highlight_whitespace: bool,
 ) -> io::Result<()> {
+    let mut right_writer =
+        if highlight_whitespace && diff_line.has_right_content && !diff_line.is_unmodified() {
+            let right_line = diff_line
+                .hunks
+                .iter()
+                .flat_map(|hunk| match hunk {
+                    DiffHunk::Matching(data) => *data,
+                    DiffHunk::Different(data) => data[1],
+                })
+                .copied()
+                .collect_vec();
+            WhitespaceErrorWriter::new(&right_line)
+        } else {
+            WhitespaceErrorWriter::default()
+        };
+    // Lines removed or added as a whole may have been moved.
+    let moved_label = match (diff_line.has_left_content, diff_line.has_right_content) {
+        (true, false)
+            if moved_lines
+                .removed
+                .contains(&(diff_line.left_line_number as usize)) =>
+        {
+            Some("moved_removed")
+        }
+        (false, true)
+            if moved_lines
+                .added
+                .contains(&(diff_line.right_line_number as usize)) =>
+        {
+            Some("moved_added")
+        }
+        _ => None,
+    };
+    if let Some(label) = moved_label {
+        return formatter.with_label(label, |formatter| {
+            let side = if diff_line.has_left_content {
+                write!(formatter, "{:>4}     : ", diff_line.left_line_number)?;
+                0
+            } else {
+                write!(formatter, "     {:>4}: ", diff_line.right_line_number)?;
+                1
+            };
+            for hunk in &diff_line.hunks {
+                let data = match hunk {
+                    DiffHunk::Matching(data) => data,
+                    DiffHunk::Different(data) => data[side],
+                };
+                right_writer.write(formatter, data)?;
+            }
+            Ok(())
+        });
+    }
     if diff_line.has_left_content {
         write!(
             formatter.labeled("removed"),
@@ -321,19 +968,41 @@ fn show_color_words_diff_line(
     } else {
         formatter.write_str("    : ")?;
     }
-    for hunk in &diff_line.hunks {
+    let mut hunks = diff_line.hunks.iter().peekable();
+    while let Some(hunk) = hunks.next() {
         match hunk {
             DiffHunk::Matching(data) => {
-                formatter.write_all(data)?;
+                right_writer.write(formatter, data)?;
             }
             DiffHunk::Different(data) => {
-                let before = data[0];
-                let after = data[1];
-                if !before.is_empty() {
-                    formatter.with_label("removed", |formatter| formatter.write_all(before))?;
+                // A replaced word is usually split into removed and added hunks.
+                let mut different_data = vec![data];
+                while let Some(DiffHunk::Different(data)) =
+                    hunks.next_if(|hunk| matches!(hunk, DiffHunk::Different(_)))
+                {
+                    different_data.push(data);
                 }
-                if !after.is_empty() {
-                    formatter.with_label("added", |formatter| formatter.write_all(after))?;
+                let mut before = vec![];
+                let mut after = vec![];
+                for data in &different_data {
+                    before.extend_from_slice(data[0]);
+                    after.extend_from_slice(data[1]);
+                }
+                if let Some(char_diff) = refine_color_words_by_chars(&before, &after) {
+                    show_color_words_char_diff(formatter, &char_diff, &mut right_writer)?;
+                    continue;
+                }
+                for data in different_data {
+                    let before = data[0];
+                    let after = data[1];
+                    if !before.is_empty() {
+                        formatter.with_label("removed", |formatter| formatter.write_all(before))?;
+                    }
+                    if !after.is_empty() {
+                        formatter.with_label("added", |formatter| {
+                            right_writer.write(formatter, after)
+                        })?;
+                    }
                 }
             }
         }
@@ -342,35 +1011,203 @@ fn show_color_words_diff_line(
     Ok(())
 }
 
+/// Maximum length of replaced text to be refined by characters.
+const MAX_CHAR_DIFF_LEN: usize = 200;
+
+/// Diffs the replaced words by characters so that the changed characters can
+/// be highlighted. Returns `None` if the texts aren't suitable for that, e.g.
+/// if they aren't UTF-8, are too long, or have no characters in common.
+fn refine_color_words_by_chars<'input>(
+    before: &'input [u8],
+    after: &'input [u8],
+) -> Option<Diff<'input>> {
+    if before.is_empty()
+        || after.is_empty()
+        || before.len() > MAX_CHAR_DIFF_LEN
+        || after.len() > MAX_CHAR_DIFF_LEN
+    {
+        return None;
+    }
+    let (Ok(before_str), Ok(after_str)) = (std::str::from_utf8(before), std::str::from_utf8(after))
+    else {
+        return None;
+    };
+    // Multi-line replacements are better read as a whole.
+    if before_str.contains('\n') || after_str.contains('\n') {
+        return None;
+    }
+    let diff = Diff::for_tokenizer(&[before, after], &find_char_ranges);
+    let has_common_chars = diff
+        .hunks()
+        .any(|hunk| matches!(hunk, DiffHunk::Matching(_)));
+    has_common_chars.then_some(diff)
+}
+
+/// Splits UTF-8 `text` into characters.
+fn find_char_ranges(text: &[u8]) -> Vec<Range<usize>> {
+    let text = std::str::from_utf8(text).expect("text should have been validated as UTF-8");
+    text.char_indices()
+        .map(|(start, c)| start..start + c.len_utf8())
+        .collect()
+}
+
+/// Writes the removed and then the added text of the `char_diff`, labeling the
+/// changed characters as `token`. The added text is written by `right_writer`.
+fn show_color_words_char_diff(
+    formatter: &mut dyn Formatter,
+    char_diff: &Diff,
+    right_writer: &mut WhitespaceErrorWriter,
+) -> io::Result<()> {
+    let mut left_writer = WhitespaceErrorWriter::default();
+    for (side, label, writer) in [(0, "removed", &mut left_writer), (1, "added", right_writer)] {
+        formatter.with_label(label, |formatter| {
+            for hunk in char_diff.hunks() {
+                match hunk {
+                    DiffHunk::Matching(data) => writer.write(formatter, data)?,
+                    DiffHunk::Different(data) => {
+                        formatter
+                            .with_label("token", |formatter| writer.write(formatter, data[side]))?;
+                    }
+                }
+            }
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+/// Returns the ranges of whitespace errors in the `line`, which are the
+/// whitespace at the end of the line, and the indentation if a space comes
+/// before a tab in it.
+fn find_whitespace_errors(line: &[u8]) -> Vec<Range<usize>> {
+    let is_blank = |b: &u8| matches!(b, b' ' | b'\t');
+    let content = line.strip_suffix(b"\n").unwrap_or(line);
+    let indent_len = content.iter().take_while(|b| is_blank(b)).count();
+    let trailing_start = content.len() - content.iter().rev().take_while(|b| is_blank(b)).count();
+    let mut ranges = vec![];
+    // The indentation of a blank line is reported as trailing whitespace.
+    if indent_len < content.len() && content[..indent_len].windows(2).any(|w| w == b" \t") {
+        ranges.push(0..indent_len);
+    }
+    if trailing_start < content.len() {
+        ranges.push(trailing_start..content.len());
+    }
+    ranges
+}
+
+/// Writes a line in parts, labeling the whitespace errors found in the whole
+/// line as `whitespace-error`. The default writer labels nothing.
+#[derive(Debug, Default)]
+struct WhitespaceErrorWriter {
+    ranges: Vec<Range<usize>>,
+    offset: usize,
+}
+
+impl WhitespaceErrorWriter {
+    fn new(line: &[u8]) -> Self {
+        WhitespaceErrorWriter {
+            ranges: find_whitespace_errors(line),
+            offset: 0,
+        }
+    }
+
+    /// Writes the next part of the line.
+    fn write(&mut self, formatter: &mut dyn Formatter, data: &[u8]) -> io::Result<()> {
+        let start = self.offset;
+        let end = start + data.len();
+        self.offset = end;
+        let mut pos = start;
+        for range in &self.ranges {
+            let error_start = range.start.clamp(pos, end);
+            let error_end = range.end.clamp(pos, end);
+            if error_start == error_end {
+                continue;
+            }
+            formatter.write_all(&data[pos - start..error_start - start])?;
+            formatter.with_label("whitespace-error", |formatter| {
+                formatter.write_all(&data[error_start - start..error_end - start])
+            })?;
+            pos = error_end;
+        }
+        formatter.write_all(&data[pos - start..])
+    }
+}
+
+/// Reads the contents of the file, unless it's larger than `max_file_size`.
+/// Only up to `max_file_size` bytes of a larger file are read.
+fn read_file_content(
+    store: &Store,
+    path: &RepoPath,
+    id: &FileId,
+    max_file_size: Option<u64>,
+) -> Result<Result<Vec<u8>, FileTooLarge>, CommandError> {
+    let mut file_reader = store.read_file(path, id)?;
+    let mut content = vec![];
+    let Some(max_size) = max_file_size else {
+        file_reader.read_to_end(&mut content)?;
+        return Ok(Ok(content));
+    };
+    (&mut file_reader)
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut content)?;
+    if content.len() as u64 <= max_size {
+        Ok(Ok(content))
+    } else {
+        Ok(Err(FileTooLarge { max_size }))
+    }
+}
+
+/// Like `diff_content()`, but doesn't read files larger than `max_file_size`.
+fn diff_content_within_limit(
+    store: &Store,
+    path: &RepoPath,
+    value: &MergedTreeValue,
+    max_file_size: Option<u64>,
+) -> Result<Result<Vec<u8>, FileTooLarge>, CommandError> {
+    match value.as_resolved() {
+        Some(Some(TreeValue::File { id, .. })) => read_file_content(store, path, id, max_file_size),
+        _ => diff_content(store, path, value).map(Ok),
+    }
+}
+
+/// Returns the size of the content without reading the whole file into
+/// memory.
+fn diff_content_size(
+    store: &Store,
+    path: &RepoPath,
+    value: &MergedTreeValue,
+) -> Result<usize, CommandError> {
+    match value.as_resolved() {
+        Some(Some(TreeValue::File { id, .. })) => {
+            let mut file_reader = store.read_file(path, id)?;
+            let size = io::copy(&mut file_reader, &mut io::sink())?;
+            Ok(size as usize)
+        }
+        _ => Ok(diff_content(store, path, value)?.len()),
+    }
+}
+
 fn diff_content(
-    repo: &Arc<ReadonlyRepo>,
+    store: &Store,
     path: &RepoPath,
     value: &MergedTreeValue,
 ) -> Result<Vec<u8>, CommandError> {
     match value.as_resolved() {
         Some(None) => Ok(vec![]),
         Some(Some(TreeValue::File { id, .. })) => {
-            let mut file_reader = repo.store().read_file(path, id).unwrap();
+            let mut file_reader = store.read_file(path, id).unwrap();
             let mut content = vec![];
             file_reader.read_to_end(&mut content)?;
             Ok(content)
         }
         Some(Some(TreeValue::Symlink(id))) => {
-            let target = repo.store().read_symlink(path, id)?;
+            let target = store.read_symlink(path, id)?;
             Ok(target.into_bytes())
         }
-        Some(Some(TreeValue::GitSubmodule(id))) => {
-            Ok(format!("Git submodule checked out at {}", id.hex()).into_bytes())
-        }
+        Some(Some(TreeValue::GitSubmodule(id))) => Ok(submodule_content(id)),
         None => {
             let mut content = vec![];
-            block_on(conflicts::materialize(
-                value,
-                repo.store(),
-                path,
-                &mut content,
-            ))
-            .unwrap();
+            block_on(conflicts::materialize(value, store, path, &mut content)).unwrap();
             Ok(content)
         }
         Some(Some(TreeValue::Tree(_))) | Some(Some(TreeValue::Conflict(_))) => {
@@ -379,6 +1216,12 @@ fn diff_content(
     }
 }
 
+/// Returns the content a Git submodule is diffed as. Like Git, it is shown as
+/// the commit the submodule is checked out at.
+fn submodule_content(id: &CommitId) -> Vec<u8> {
+    format!("Subproject commit {}\n", id.hex()).into_bytes()
+}
+
 fn basic_diff_file_type(values: &MergedTreeValue) -> String {
     match values.as_resolved() {
         Some(None) => {
@@ -401,86 +1244,655 @@ fn basic_diff_file_type(values: &MergedTreeValue) -> String {
     }
 }
 
-pub fn show_color_words_diff(
+/// Returns the Git file mode of the resolved value, or `None` if the value is
+/// absent or conflicted.
+fn git_file_mode(value: &MergedTreeValue) -> Option<&'static str> {
+    match value.as_resolved()? {
+        Some(TreeValue::File {
+            executable: false, ..
+        }) => Some("100644"),
+        Some(TreeValue::File {
+            executable: true, ..
+        }) => Some("100755"),
+        Some(TreeValue::Symlink(_)) => Some("120000"),
+        Some(TreeValue::GitSubmodule(_)) => Some("160000"),
+        None | Some(TreeValue::Tree(_)) | Some(TreeValue::Conflict(_)) => None,
+    }
+}
+
+/// Returns the old and new Git file modes if the mode changed between the two
+/// resolved values.
+fn git_mode_change(
+    left: &MergedTreeValue,
+    right: &MergedTreeValue,
+) -> Option<(&'static str, &'static str)> {
+    let left_mode = git_file_mode(left)?;
+    let right_mode = git_file_mode(right)?;
+    (left_mode != right_mode).then_some((left_mode, right_mode))
+}
+
+/// A file-level change in a diff. The left value of a renamed file is read from
+/// its source path.
+struct DiffEntry {
+    path: RepoPath,
+    left_value: MergedTreeValue,
+    right_value: MergedTreeValue,
+    /// The source path and the similarity in percent of a renamed file.
+    renamed_from: Option<(RepoPath, u8)>,
+}
+
+impl DiffEntry {
+    fn source_path(&self) -> &RepoPath {
+        self.renamed_from
+            .as_ref()
+            .map_or(&self.path, |(source, _)| source)
+    }
+}
+
+/// Collects the changes from the `tree_diff`. If `renames` is set, removed and
+/// added files are paired into renames. The contents of files larger than
+/// `max_file_size` aren't compared, so they are only paired if identical.
+fn diff_entries(
+    repo: &Arc<ReadonlyRepo>,
+    tree_diff: TreeDiffIterator,
+    renames: Option<RenameDetection>,
+    max_file_size: Option<u64>,
+) -> Result<Vec<DiffEntry>, CommandError> {
+    let mut entries: Vec<DiffEntry> = tree_diff
+        .map(|(path, diff)| {
+            let (left_value, right_value) = diff?;
+            Ok::<_, BackendError>(DiffEntry {
+                path,
+                left_value,
+                right_value,
+                renamed_from: None,
+            })
+        })
+        .try_collect()?;
+    let Some(renames) = renames else {
+        return Ok(entries);
+    };
+    let renamed = find_renames(repo, &entries, renames.threshold, max_file_size)?;
+    if renamed.is_empty() {
+        return Ok(entries);
+    }
+    let mut source_indices = HashSet::new();
+    for (source_index, target_index, similarity) in renamed {
+        let source_path = entries[source_index].path.clone();
+        let left_value = entries[source_index].left_value.clone();
+        let target = &mut entries[target_index];
+        target.left_value = left_value;
+        target.renamed_from = Some((source_path, similarity));
+        source_indices.insert(source_index);
+    }
+    Ok(entries
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !source_indices.contains(index))
+        .map(|(_, entry)| entry)
+        .collect())
+}
+
+/// Pairs removed files with added files, and returns the indices of the source
+/// and target entries and their similarity in percent.
+///
+/// Files with the same content are paired first. The remaining files are
+/// compared by the lines they have in common, and the most similar pairs of at
+/// least `threshold` percent are chosen. Files larger than `max_file_size`
+/// aren't read, so they are only paired with files of the same content.
+fn find_renames(
+    repo: &Arc<ReadonlyRepo>,
+    entries: &[DiffEntry],
+    threshold: u8,
+    max_file_size: Option<u64>,
+) -> Result<Vec<(usize, usize, u8)>, CommandError> {
+    let file_id = |value: &MergedTreeValue| match value.as_resolved() {
+        Some(Some(TreeValue::File { id, .. })) => Some(id.clone()),
+        _ => None,
+    };
+    // Like Git, don't pair empty files since they have nothing in common.
+    let is_empty_file = |path: &RepoPath, id: &FileId| -> Result<bool, CommandError> {
+        let mut reader = repo.store().read_file(path, id)?;
+        Ok(reader.read(&mut [0])? == 0)
+    };
+    let mut sources = vec![];
+    let mut targets = vec![];
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.right_value.is_absent() {
+            if let Some(id) = file_id(&entry.left_value) {
+                if !is_empty_file(&entry.path, &id)? {
+                    sources.push((index, id));
+                }
+            }
+        } else if entry.left_value.is_absent() {
+            if let Some(id) = file_id(&entry.right_value) {
+                if !is_empty_file(&entry.path, &id)? {
+                    targets.push((index, id));
+                }
+            }
+        }
+    }
+
+    let mut renamed = vec![];
+    let mut sources_by_id: HashMap<_, VecDeque<usize>> = HashMap::new();
+    for (index, id) in &sources {
+        sources_by_id.entry(id).or_default().push_back(*index);
+    }
+    let mut remaining_targets = vec![];
+    for (target_index, id) in &targets {
+        if let Some(source_index) = sources_by_id.get_mut(id).and_then(|s| s.pop_front()) {
+            renamed.push((source_index, *target_index, 100));
+        } else {
+            remaining_targets.push(*target_index);
+        }
+    }
+    let remaining_sources = sources_by_id.into_values().flatten().sorted().collect_vec();
+    let num_pairs = remaining_sources.len() * remaining_targets.len();
+    if num_pairs == 0 || num_pairs > MAX_RENAME_CANDIDATE_PAIRS {
+        return Ok(renamed);
+    }
+
+    let read_contents = |indices: &[usize], get_value: fn(&DiffEntry) -> &MergedTreeValue| {
+        let mut contents = vec![];
+        for &index in indices {
+            let entry = &entries[index];
+            let value = get_value(entry);
+            if let Ok(content) =
+                diff_content_within_limit(repo.store(), &entry.path, value, max_file_size)?
+            {
+                contents.push((index, content));
+            }
+        }
+        Ok::<_, CommandError>(contents)
+    };
+    let source_contents = read_contents(&remaining_sources, |entry| &entry.left_value)?;
+    let target_contents = read_contents(&remaining_targets, |entry| &entry.right_value)?;
+    let source_lines = source_contents
+        .iter()
+        .map(|(index, content)| (*index, LineCounts::new(content)))
+        .collect_vec();
+    let target_lines = target_contents
+        .iter()
+        .map(|(index, content)| (*index, LineCounts::new(content)))
+        .collect_vec();
+    let mut candidates = vec![];
+    for (source_index, source) in &source_lines {
+        for (target_index, target) in &target_lines {
+            // Skip pairs whose sizes alone are too different.
+            let (min_len, max_len) = if source.len < target.len {
+                (source.len, target.len)
+            } else {
+                (target.len, source.len)
+            };
+            if min_len * 100 < usize::from(threshold) * max_len {
+                continue;
+            }
+            let similarity = source.similarity(target);
+            if similarity >= threshold {
+                candidates.push((*source_index, *target_index, similarity));
+            }
+        }
+    }
+    candidates.sort_by_key(|(_, _, similarity)| Reverse(*similarity));
+    let mut used_sources = HashSet::new();
+    let mut used_targets = HashSet::new();
+    for (source_index, target_index, similarity) in candidates {
+        if used_sources.contains(&source_index) || used_targets.contains(&target_index) {
+            continue;
+        }
+        used_sources.insert(source_index);
+        used_targets.insert(target_index);
+        renamed.push((source_index, target_index, similarity));
+    }
+    Ok(renamed)
+}
+
+/// Returns the similarity in percent of the contents of two values. Contents
+/// larger than `max_file_size` are only similar if they are identical.
+fn content_similarity(
+    store: &Store,
+    (left_path, left_value): (&RepoPath, &MergedTreeValue),
+    (right_path, right_value): (&RepoPath, &MergedTreeValue),
+    max_file_size: Option<u64>,
+) -> Result<u8, CommandError> {
+    if left_value == right_value {
+        return Ok(100);
+    }
+    let left_content = diff_content_within_limit(store, left_path, left_value, max_file_size)?;
+    let right_content = diff_content_within_limit(store, right_path, right_value, max_file_size)?;
+    let (Ok(left_content), Ok(right_content)) = (left_content, right_content) else {
+        return Ok(0);
+    };
+    if left_content.is_empty() && right_content.is_empty() {
+        return Ok(100);
+    }
+    Ok(LineCounts::new(&left_content).similarity(&LineCounts::new(&right_content)))
+}
+
+/// Number of occurrences of each line in a file content.
+struct LineCounts<'content> {
+    len: usize,
+    counts: HashMap<&'content [u8], usize>,
+}
+
+impl<'content> LineCounts<'content> {
+    fn new(content: &'content [u8]) -> Self {
+        let mut counts = HashMap::new();
+        for line in content.split_inclusive(|b| *b == b'\n') {
+            *counts.entry(line).or_default() += 1;
+        }
+        LineCounts {
+            len: content.len(),
+            counts,
+        }
+    }
+
+    /// Returns the percentage of the larger content made of common lines.
+    fn similarity(&self, other: &LineCounts) -> u8 {
+        let common_len: usize = self
+            .counts
+            .iter()
+            .filter_map(|(line, count)| {
+                let other_count = other.counts.get(line)?;
+                Some(line.len() * (*count).min(*other_count))
+            })
+            .sum();
+        (common_len * 100 / max(self.len, other.len)) as u8
+    }
+}
+
+/// Number of files whose diffs are computed concurrently before they are
+/// written out. This bounds the memory used by the file contents and the
+/// rendered output that are waiting to be written.
+const DIFF_BATCH_SIZE: usize = 64;
+
+/// Renders the diff of each of the `entries` on the thread pool, and writes the
+/// rendered diffs to the `formatter` in the order of the `entries`.
+///
+/// The output of each file is recorded along with its labels and then replayed,
+/// so it's exactly the same as if the files were rendered one after another.
+/// If rendering fails, the diffs of the preceding files are written before the
+/// error is returned.
+fn write_file_diffs<T: Sync>(
+    formatter: &mut dyn Formatter,
+    entries: &[T],
+    render: impl Fn(&mut dyn Formatter, &T) -> Result<(), CommandError> + Sync,
+) -> Result<(), CommandError> {
+    for batch in entries.chunks(DIFF_BATCH_SIZE) {
+        let recorded: Vec<_> = batch
+            .par_iter()
+            .map(|entry| {
+                let mut recorder = FormatRecorder::new();
+                render(&mut recorder, entry)?;
+                Ok::<_, CommandError>(recorder)
+            })
+            .collect();
+        for recorder in recorded {
+            recorder?.replay(formatter)?;
+        }
+    }
+    Ok(())
+}
+
+/// Minimum number of alphanumeric characters in a block of lines to be shown as
+/// moved, like Git's `--color-moved`.
+const MIN_MOVED_ALNUM_CHARS: usize = 20;
+/// Moved lines are only detected if at most this many lines are removed and
+/// added in the whole diff.
+const MAX_MOVED_CANDIDATE_LINES: usize = 100_000;
+/// Number of places an added line is looked up at when matching removed lines.
+const MAX_MOVED_CANDIDATES_PER_LINE: usize = 16;
+
+/// Hash and number of alphanumeric characters of a changed line.
+#[derive(Clone, Copy)]
+struct ChangedLine {
+    hash: u64,
+    alnum_chars: usize,
+}
+
+/// A run of consecutive removed or added lines of a file.
+struct ChangedBlock {
+    /// Number of the first line in the left or right content, starting at 1.
+    first_line: usize,
+    lines: Vec<ChangedLine>,
+}
+
+/// Removed and added blocks of lines of a file.
+#[derive(Default)]
+struct ChangedBlocks {
+    removed: Vec<ChangedBlock>,
+    added: Vec<ChangedBlock>,
+}
+
+/// Numbers of the removed and added lines of a file which are shown as moved.
+#[derive(Default)]
+struct MovedLines {
+    removed: HashSet<usize>,
+    added: HashSet<usize>,
+}
+
+impl ChangedBlocks {
+    fn new(left_content: &[u8], right_content: &[u8], line_compare: LineCompareMode) -> Self {
+        let mut blocks = ChangedBlocks::default();
+        if is_binary(left_content) || is_binary(right_content) {
+            return blocks;
+        }
+        let changed_block = |content: &[u8], first_line: usize| {
+            let lines = content
+                .split_inclusive(|b| *b == b'\n')
+                .map(|line| ChangedLine {
+                    hash: {
+                        let mut hasher = DefaultHasher::new();
+                        line.hash(&mut hasher);
+                        hasher.finish()
+                    },
+                    alnum_chars: line.iter().filter(|b| b.is_ascii_alphanumeric()).count(),
+                })
+                .collect_vec();
+            ChangedBlock { first_line, lines }
+        };
+        let count_lines = |content: &[u8]| content.split_inclusive(|b| *b == b'\n').count();
+        let mut left_line = 1;
+        let mut right_line = 1;
+        for hunk in diff_by_lines(left_content, right_content, line_compare) {
+            match hunk {
+                LineDiffHunk::Matching(right_range) => {
+                    let num_lines = count_lines(&right_content[right_range]);
+                    left_line += num_lines;
+                    right_line += num_lines;
+                }
+                LineDiffHunk::Different(left_range, right_range) => {
+                    let removed = changed_block(&left_content[left_range], left_line);
+                    let added = changed_block(&right_content[right_range], right_line);
+                    left_line += removed.lines.len();
+                    right_line += added.lines.len();
+                    for (block, blocks) in
+                        [(removed, &mut blocks.removed), (added, &mut blocks.added)]
+                    {
+                        if !block.lines.is_empty() {
+                            blocks.push(block);
+                        }
+                    }
+                }
+            }
+        }
+        blocks
+    }
+
+    fn num_lines(&self) -> usize {
+        self.removed
+            .iter()
+            .chain(&self.added)
+            .map(|block| block.lines.len())
+            .sum()
+    }
+}
+
+/// Finds the removed lines which are added elsewhere in the diff, and the added
+/// lines they were moved to. The `blocks` are the changed lines of each file.
+///
+/// Only the hashes of the lines are kept, and nothing is detected if too many
+/// lines changed, so the memory use is bounded.
+fn find_moved_lines(blocks: &[ChangedBlocks]) -> Vec<MovedLines> {
+    let mut moved_lines = blocks.iter().map(|_| MovedLines::default()).collect_vec();
+    let num_lines: usize = blocks.iter().map(ChangedBlocks::num_lines).sum();
+    if num_lines > MAX_MOVED_CANDIDATE_LINES {
+        return moved_lines;
+    }
+    // Places of the added lines by their hashes
+    let mut added_line_places: HashMap<u64, Vec<(usize, &ChangedBlock, usize)>> = HashMap::new();
+    for (file_index, file_blocks) in blocks.iter().enumerate() {
+        for block in &file_blocks.added {
+            for (line_index, line) in block.lines.iter().enumerate() {
+                let places = added_line_places.entry(line.hash).or_default();
+                if places.len() < MAX_MOVED_CANDIDATES_PER_LINE {
+                    places.push((file_index, block, line_index));
+                }
+            }
+        }
+    }
+    for (file_index, file_blocks) in blocks.iter().enumerate() {
+        for block in &file_blocks.removed {
+            let mut line_index = 0;
+            while line_index < block.lines.len() {
+                let removed_lines = &block.lines[line_index..];
+                // Find the longest run of added lines matching the removed
+                // lines starting at this line.
+                let best_match = added_line_places
+                    .get(&removed_lines[0].hash)
+                    .into_iter()
+                    .flatten()
+                    .map(|&(added_file_index, added_block, added_line_index)| {
+                        let len = removed_lines
+                            .iter()
+                            .zip(&added_block.lines[added_line_index..])
+                            .take_while(|(removed, added)| removed.hash == added.hash)
+                            .count();
+                        (len, added_file_index, added_block, added_line_index)
+                    })
+                    .max_by_key(|&(len, ..)| len);
+                let Some((len, added_file_index, added_block, added_line_index)) = best_match
+                else {
+                    line_index += 1;
+                    continue;
+                };
+                let alnum_chars: usize = removed_lines[..len]
+                    .iter()
+                    .map(|line| line.alnum_chars)
+                    .sum();
+                if alnum_chars < MIN_MOVED_ALNUM_CHARS {
+                    line_index += 1;
+                    continue;
+                }
+                let removed_start = block.first_line + line_index;
+                let added_start = added_block.first_line + added_line_index;
+                moved_lines[file_index]
+                    .removed
+                    .extend(removed_start..removed_start + len);
+                moved_lines[added_file_index]
+                    .added
+                    .extend(added_start..added_start + len);
+                line_index += len;
+            }
+        }
+    }
+    moved_lines
+}
+
+/// Loads the contents of the `entries` on the thread pool, and finds the lines
+/// moved between them. Files larger than `max_file_size` are treated as empty.
+fn find_moved_lines_in_entries<'a>(
+    store: &Store,
+    entries: impl IntoParallelIterator<Item = &'a DiffEntry>,
+    line_compare: LineCompareMode,
+    max_file_size: Option<u64>,
+) -> Result<Vec<MovedLines>, CommandError> {
+    let blocks: Vec<_> = entries
+        .into_par_iter()
+        .map(|entry| {
+            let content = |path, value| {
+                let content = diff_content_within_limit(store, path, value, max_file_size)?;
+                Ok::<_, CommandError>(content.unwrap_or_default())
+            };
+            let left_content = content(entry.source_path(), &entry.left_value)?;
+            let right_content = content(&entry.path, &entry.right_value)?;
+            Ok::<_, CommandError>(ChangedBlocks::new(
+                &left_content,
+                &right_content,
+                line_compare,
+            ))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(find_moved_lines(&blocks))
+}
+
+/// Writes a color-words diff. Files larger than `max_file_size` are listed
+/// without their changes.
+fn show_color_words_diff(
     formatter: &mut dyn Formatter,
     workspace_command: &WorkspaceCommandHelper,
-    tree_diff: TreeDiffIterator,
+    entries: Vec<DiffEntry>,
+    options: &DiffRenderOptions,
 ) -> Result<(), CommandError> {
-    let repo = workspace_command.repo();
+    let &DiffRenderOptions {
+        context: num_context_lines,
+        line_compare,
+        color_moved,
+        highlight_whitespace,
+        max_file_size,
+        ..
+    } = options;
+    let store = workspace_command.repo().store();
+    let moved_lines = if color_moved {
+        find_moved_lines_in_entries(store, &entries, line_compare, max_file_size)?
+    } else {
+        entries.iter().map(|_| MovedLines::default()).collect()
+    };
+    let entries = iter::zip(entries, moved_lines)
+        .map(|(entry, moved_lines)| {
+            let ui_path = workspace_command.format_file_path(&entry.path);
+            let ui_source_path = entry
+                .renamed_from
+                .as_ref()
+                .map(|(source_path, _)| workspace_command.format_file_path(source_path));
+            (entry, moved_lines, ui_path, ui_source_path)
+        })
+        .collect_vec();
     formatter.push_label("diff")?;
-    for (path, diff) in tree_diff {
-        let ui_path = workspace_command.format_file_path(&path);
-        let (left_value, right_value) = diff?;
-        if left_value.is_absent() {
-            let right_content = diff_content(repo, &path, &right_value)?;
-            let description = basic_diff_file_type(&right_value);
-            writeln!(
-                formatter.labeled("header"),
-                "Added {description} {ui_path}:"
-            )?;
-            if right_content.is_empty() {
-                writeln!(formatter.labeled("empty"), "    (empty)")?;
-            } else {
-                show_color_words_diff_hunks(&[], &right_content, formatter)?;
-            }
-        } else if right_value.is_present() {
-            let left_content = diff_content(repo, &path, &left_value)?;
-            let right_content = diff_content(repo, &path, &right_value)?;
-            let description = match (left_value.into_resolved(), right_value.into_resolved()) {
-                (
-                    Ok(Some(TreeValue::File {
-                        executable: left_executable,
-                        ..
-                    })),
-                    Ok(Some(TreeValue::File {
-                        executable: right_executable,
-                        ..
-                    })),
-                ) => {
-                    if left_executable && right_executable {
-                        "Modified executable file".to_string()
-                    } else if left_executable {
-                        "Executable file became non-executable at".to_string()
-                    } else if right_executable {
-                        "Non-executable file became executable at".to_string()
-                    } else {
-                        "Modified regular file".to_string()
-                    }
+    write_file_diffs(
+        formatter,
+        &entries,
+        |formatter, (entry, moved_lines, ui_path, ui_source_path)| {
+            let DiffEntry {
+                path,
+                left_value,
+                right_value,
+                renamed_from: _,
+            } = entry;
+            let left_content =
+                diff_content_within_limit(store, entry.source_path(), left_value, max_file_size)?;
+            let right_content = diff_content_within_limit(store, path, right_value, max_file_size)?;
+            // A conflict of files is shown side by side rather than as the
+            // materialized conflict markers.
+            let right_sides = match right_value.as_resolved() {
+                Some(_) => None,
+                None => right_value.to_file_merge(),
+            }
+            .map(|file_merge| {
+                block_on(conflicts::extract_as_single_hunk(&file_merge, store, path))
+            });
+            let show_hunks = |formatter: &mut dyn Formatter| {
+                if let Some(sides) = &right_sides {
+                    return show_color_words_conflict_sides(
+                        sides,
+                        num_context_lines,
+                        line_compare,
+                        highlight_whitespace,
+                        formatter,
+                    );
                 }
-                (Err(_), Err(_)) => "Modified conflict in".to_string(),
-                (Err(_), _) => "Resolved conflict in".to_string(),
-                (_, Err(_)) => "Created conflict in".to_string(),
-                (Ok(Some(TreeValue::Symlink(_))), Ok(Some(TreeValue::Symlink(_)))) => {
-                    "Symlink target changed at".to_string()
-                }
-                (Ok(left_value), Ok(right_value)) => {
-                    let left_type = basic_diff_file_type(&Merge::resolved(left_value));
-                    let right_type = basic_diff_file_type(&Merge::resolved(right_value));
-                    let (first, rest) = left_type.split_at(1);
-                    format!(
-                        "{}{} became {} at",
-                        first.to_ascii_uppercase(),
-                        rest,
-                        right_type
-                    )
+                match (&left_content, &right_content) {
+                    (Ok(left_content), Ok(right_content)) => show_color_words_diff_hunks(
+                        left_content,
+                        right_content,
+                        num_context_lines,
+                        line_compare,
+                        moved_lines,
+                        highlight_whitespace,
+                        formatter,
+                    ),
+                    (Err(too_large), _) | (_, Err(too_large)) => {
+                        writeln!(formatter, "    ({too_large})")
+                    }
                 }
             };
-            writeln!(formatter.labeled("header"), "{description} {ui_path}:")?;
-            show_color_words_diff_hunks(&left_content, &right_content, formatter)?;
-        } else {
-            let left_content = diff_content(repo, &path, &left_value)?;
-            let description = basic_diff_file_type(&left_value);
-            writeln!(
-                formatter.labeled("header"),
-                "Removed {description} {ui_path}:"
-            )?;
-            if left_content.is_empty() {
-                writeln!(formatter.labeled("empty"), "    (empty)")?;
+            let is_empty = |content: &Result<Vec<u8>, FileTooLarge>| {
+                content.as_ref().is_ok_and(|content| content.is_empty())
+            };
+            if let Some(ui_source_path) = ui_source_path {
+                let description = basic_diff_file_type(right_value);
+                writeln!(
+                    formatter.labeled("header"),
+                    "Renamed {description} {ui_source_path} to {ui_path}:"
+                )?;
+                if left_content != right_content {
+                    show_hunks(formatter)?;
+                }
+            } else if left_value.is_absent() {
+                let description = basic_diff_file_type(right_value);
+                writeln!(
+                    formatter.labeled("header"),
+                    "Added {description} {ui_path}:"
+                )?;
+                if is_empty(&right_content) {
+                    writeln!(formatter.labeled("empty"), "    (empty)")?;
+                } else {
+                    show_hunks(formatter)?;
+                }
+            } else if right_value.is_present() {
+                let description = match (
+                    left_value.clone().into_resolved(),
+                    right_value.clone().into_resolved(),
+                ) {
+                    (
+                        Ok(Some(TreeValue::File {
+                            executable: left_executable,
+                            ..
+                        })),
+                        Ok(Some(TreeValue::File {
+                            executable: right_executable,
+                            ..
+                        })),
+                    ) => {
+                        if left_executable && right_executable {
+                            "Modified executable file".to_string()
+                        } else if left_executable {
+                            "Executable file became non-executable at".to_string()
+                        } else if right_executable {
+                            "Non-executable file became executable at".to_string()
+                        } else {
+                            "Modified regular file".to_string()
+                        }
+                    }
+                    (Err(_), Err(_)) => "Modified conflict in".to_string(),
+                    (Err(_), _) => "Resolved conflict in".to_string(),
+                    (_, Err(_)) => "Created conflict in".to_string(),
+                    (Ok(Some(TreeValue::Symlink(_))), Ok(Some(TreeValue::Symlink(_)))) => {
+                        "Symlink target changed at".to_string()
+                    }
+                    (
+                        Ok(Some(TreeValue::GitSubmodule(_))),
+                        Ok(Some(TreeValue::GitSubmodule(_))),
+                    ) => "Modified Git submodule".to_string(),
+                    (Ok(left_value), Ok(right_value)) => {
+                        let left_type = basic_diff_file_type(&Merge::resolved(left_value));
+                        let right_type = basic_diff_file_type(&Merge::resolved(right_value));
+                        let (first, rest) = left_type.split_at(1);
+                        format!(
+                            "{}{} became {} at",
+                            first.to_ascii_uppercase(),
+                            rest,
+                            right_type
+                        )
+                    }
+                };
+                writeln!(formatter.labeled("header"), "{description} {ui_path}:")?;
+                show_hunks(formatter)?;
             } else {
-                show_color_words_diff_hunks(&left_content, &[], formatter)?;
+                let description = basic_diff_file_type(left_value);
+                writeln!(
+                    formatter.labeled("header"),
+                    "Removed {description} {ui_path}:"
+                )?;
+                if is_empty(&left_content) {
+                    writeln!(formatter.labeled("empty"), "    (empty)")?;
+                } else {
+                    show_hunks(formatter)?;
+                }
             }
-        }
-    }
+            Ok(())
+        },
+    )?;
     formatter.pop_label()?;
     Ok(())
 }
@@ -488,13 +1900,14 @@ pub fn show_color_words_diff(
 struct GitDiffPart {
     mode: String,
     hash: String,
-    content: Vec<u8>,
+    content: Result<Vec<u8>, FileTooLarge>,
 }
 
 fn git_diff_part(
-    repo: &Arc<ReadonlyRepo>,
+    store: &Store,
     path: &RepoPath,
     value: &MergedTreeValue,
+    max_file_size: Option<u64>,
 ) -> Result<GitDiffPart, CommandError> {
     let mode;
     let hash;
@@ -507,40 +1920,36 @@ fn git_diff_part(
                 "100644".to_string()
             };
             hash = id.hex();
-            let mut file_reader = repo.store().read_file(path, id).unwrap();
-            file_reader.read_to_end(&mut content)?;
+            return Ok(GitDiffPart {
+                mode,
+                hash,
+                content: read_file_content(store, path, id, max_file_size)?,
+            });
         }
         Some(Some(TreeValue::Symlink(id))) => {
             mode = "120000".to_string();
             hash = id.hex();
-            let target = repo.store().read_symlink(path, id)?;
+            let target = store.read_symlink(path, id)?;
             content = target.into_bytes();
         }
         Some(Some(TreeValue::GitSubmodule(id))) => {
-            // TODO: What should we actually do here?
-            mode = "040000".to_string();
+            mode = "160000".to_string();
             hash = id.hex();
+            content = submodule_content(id);
         }
         None => {
             mode = "100644".to_string();
-            hash = "0000000000".to_string();
-            block_on(conflicts::materialize(
-                value,
-                repo.store(),
-                path,
-                &mut content,
-            ))
-            .unwrap();
+            hash = "0".repeat(40);
+            block_on(conflicts::materialize(value, store, path, &mut content)).unwrap();
         }
         Some(Some(TreeValue::Tree(_))) | Some(Some(TreeValue::Conflict(_))) | Some(None) => {
             panic!("Unexpected {value:?} in diff at path {path:?}");
         }
     }
-    let hash = hash[0..10].to_string();
     Ok(GitDiffPart {
         mode,
         hash,
-        content,
+        content: Ok(content),
     })
 }
 
@@ -557,10 +1966,150 @@ struct UnifiedDiffHunk<'content> {
     lines: Vec<(DiffLineType, &'content [u8])>,
 }
 
+/// Line-level diff hunk. The ranges are the byte ranges of the lines in the
+/// left and right contents. Matching lines are referenced in the right content.
+enum LineDiffHunk {
+    Matching(Range<usize>),
+    Different(Range<usize>, Range<usize>),
+}
+
+/// Diffs the contents line by line. Lines are compared after normalizing them
+/// according to `line_compare`, so lines in matching hunks may still differ in
+/// whitespace.
+fn diff_by_lines(
+    left_content: &[u8],
+    right_content: &[u8],
+    line_compare: LineCompareMode,
+) -> Vec<LineDiffHunk> {
+    let left_normalized = normalize_lines(left_content, line_compare);
+    let right_normalized = normalize_lines(right_content, line_compare);
+    let left_line_ranges = diff::find_line_ranges(left_content);
+    let right_line_ranges = diff::find_line_ranges(right_content);
+    let line_offset = |content: &[u8], line_ranges: &[Range<usize>], line: usize| {
+        line_ranges
+            .get(line)
+            .map_or(content.len(), |range| range.start)
+    };
+    let count_lines = |content: &[u8]| content.split_inclusive(|b| *b == b'\n').count();
+    let mut hunks = vec![];
+    let mut left_line = 0;
+    let mut right_line = 0;
+    let diff = Diff::for_tokenizer(
+        &[&left_normalized, &right_normalized],
+        &diff::find_line_ranges,
+    );
+    for hunk in diff.hunks() {
+        let (left_count, right_count) = match &hunk {
+            DiffHunk::Matching(content) => (count_lines(content), count_lines(content)),
+            DiffHunk::Different(contents) => (count_lines(contents[0]), count_lines(contents[1])),
+        };
+        let left_range = line_offset(left_content, &left_line_ranges, left_line)
+            ..line_offset(left_content, &left_line_ranges, left_line + left_count);
+        let right_range = line_offset(right_content, &right_line_ranges, right_line)
+            ..line_offset(right_content, &right_line_ranges, right_line + right_count);
+        left_line += left_count;
+        right_line += right_count;
+        match hunk {
+            DiffHunk::Matching(_) => hunks.push(LineDiffHunk::Matching(right_range)),
+            DiffHunk::Different(_) => hunks.push(LineDiffHunk::Different(left_range, right_range)),
+        }
+    }
+    hunks
+}
+
+/// Normalizes the whitespace of each line in `content` so that lines which
+/// only differ in ignored whitespace compare equal.
+fn normalize_lines(content: &[u8], line_compare: LineCompareMode) -> Cow<'_, [u8]> {
+    if line_compare == LineCompareMode::Exact {
+        return Cow::Borrowed(content);
+    }
+    let mut normalized = Vec::with_capacity(content.len());
+    for line in content.split_inclusive(|b| *b == b'\n') {
+        let (text, has_newline) = match line.strip_suffix(b"\n") {
+            Some(text) => (text, true),
+            None => (line, false),
+        };
+        match line_compare {
+            LineCompareMode::Exact => normalized.extend_from_slice(text),
+            LineCompareMode::IgnoreAllSpace => {
+                normalized.extend(text.iter().filter(|b| !b.is_ascii_whitespace()));
+            }
+            LineCompareMode::IgnoreSpaceChange => {
+                // Whitespace runs are collapsed into a single space, and
+                // trailing whitespace is dropped.
+                let mut in_space = false;
+                for &b in text {
+                    if b.is_ascii_whitespace() {
+                        in_space = true;
+                    } else {
+                        if in_space {
+                            normalized.push(b' ');
+                            in_space = false;
+                        }
+                        normalized.push(b);
+                    }
+                }
+            }
+        }
+        // Terminate every line so that lines emptied by the normalization
+        // aren't lost, but keep a missing newline at the end significant.
+        if !has_newline {
+            normalized.push(b'\0');
+        }
+        normalized.push(b'\n');
+    }
+    Cow::Owned(normalized)
+}
+
+/// Diffs the contents for the color-words format. Lines considered equal by
+/// `line_compare` are reported as unmodified with their right-side content.
+fn color_words_diff_lines<'content>(
+    left_content: &'content [u8],
+    right_content: &'content [u8],
+    line_compare: LineCompareMode,
+) -> Vec<DiffLine<'content>> {
+    if line_compare == LineCompareMode::Exact {
+        return files::diff(left_content, right_content).collect();
+    }
+    let mut diff_lines = vec![];
+    let mut left_line_number = 1;
+    let mut right_line_number = 1;
+    for hunk in diff_by_lines(left_content, right_content, line_compare) {
+        match hunk {
+            LineDiffHunk::Matching(right_range) => {
+                for line in right_content[right_range].split_inclusive(|b| *b == b'\n') {
+                    diff_lines.push(DiffLine {
+                        left_line_number,
+                        right_line_number,
+                        has_left_content: true,
+                        has_right_content: true,
+                        hunks: vec![DiffHunk::Matching(line)],
+                    });
+                    left_line_number += 1;
+                    right_line_number += 1;
+                }
+            }
+            LineDiffHunk::Different(left_range, right_range) => {
+                let left = &left_content[left_range];
+                let right = &right_content[right_range];
+                for mut diff_line in files::diff(left, right) {
+                    diff_line.left_line_number += left_line_number - 1;
+                    diff_line.right_line_number += right_line_number - 1;
+                    diff_lines.push(diff_line);
+                }
+                left_line_number += left.split_inclusive(|b| *b == b'\n').count() as u32;
+                right_line_number += right.split_inclusive(|b| *b == b'\n').count() as u32;
+            }
+        }
+    }
+    diff_lines
+}
+
 fn unified_diff_hunks<'content>(
     left_content: &'content [u8],
     right_content: &'content [u8],
     num_context_lines: usize,
+    line_compare: LineCompareMode,
 ) -> Vec<UnifiedDiffHunk<'content>> {
     let mut hunks = vec![];
     let mut current_hunk = UnifiedDiffHunk {
@@ -569,11 +2118,13 @@ fn unified_diff_hunks<'content>(
         lines: vec![],
     };
     let mut show_context_after = false;
-    let diff = Diff::for_tokenizer(&[left_content, right_content], &diff::find_line_ranges);
-    for hunk in diff.hunks() {
+    for hunk in diff_by_lines(left_content, right_content, line_compare) {
         match hunk {
-            DiffHunk::Matching(content) => {
-                let lines = content.split_inclusive(|b| *b == b'\n').collect_vec();
+            // Matching lines are shown as in the right content.
+            LineDiffHunk::Matching(right_range) => {
+                let lines = right_content[right_range]
+                    .split_inclusive(|b| *b == b'\n')
+                    .collect_vec();
                 // Number of context lines to print after the previous non-matching hunk.
                 let num_after_lines = lines.len().min(if show_context_after {
                     num_context_lines
@@ -608,10 +2159,14 @@ fn unified_diff_hunks<'content>(
                     current_hunk.lines.push((DiffLineType::Context, line));
                 }
             }
-            DiffHunk::Different(content) => {
+            LineDiffHunk::Different(left_range, right_range) => {
                 show_context_after = true;
-                let left_lines = content[0].split_inclusive(|b| *b == b'\n').collect_vec();
-                let right_lines = content[1].split_inclusive(|b| *b == b'\n').collect_vec();
+                let left_lines = left_content[left_range]
+                    .split_inclusive(|b| *b == b'\n')
+                    .collect_vec();
+                let right_lines = right_content[right_range]
+                    .split_inclusive(|b| *b == b'\n')
+                    .collect_vec();
                 if !left_lines.is_empty() {
                     current_hunk.left_line_range.end += left_lines.len();
                     for line in left_lines {
@@ -637,20 +2192,42 @@ fn unified_diff_hunks<'content>(
     hunks
 }
 
+fn write_unified_hunk_header(
+    formatter: &mut dyn Formatter,
+    hunk: &UnifiedDiffHunk,
+) -> io::Result<()> {
+    // Like Git, an empty range is identified by the line before it, so that
+    // e.g. an added file starts with `@@ -0,0`.
+    let range_start = |range: &Range<usize>| {
+        if range.is_empty() {
+            range.start - 1
+        } else {
+            range.start
+        }
+    };
+    writeln!(
+        formatter.labeled("hunk_header"),
+        "@@ -{},{} +{},{} @@",
+        range_start(&hunk.left_line_range),
+        hunk.left_line_range.len(),
+        range_start(&hunk.right_line_range),
+        hunk.right_line_range.len()
+    )
+}
+
 fn show_unified_diff_hunks(
     formatter: &mut dyn Formatter,
     left_content: &[u8],
     right_content: &[u8],
+    num_context_lines: usize,
+    line_compare: LineCompareMode,
+    moved_lines: &MovedLines,
+    highlight_whitespace: bool,
 ) -> Result<(), CommandError> {
-    for hunk in unified_diff_hunks(left_content, right_content, 3) {
-        writeln!(
-            formatter.labeled("hunk_header"),
-            "@@ -{},{} +{},{} @@",
-            hunk.left_line_range.start,
-            hunk.left_line_range.len(),
-            hunk.right_line_range.start,
-            hunk.right_line_range.len()
-        )?;
+    for hunk in unified_diff_hunks(left_content, right_content, num_context_lines, line_compare) {
+        write_unified_hunk_header(formatter, &hunk)?;
+        let mut left_line = hunk.left_line_range.start;
+        let mut right_line = hunk.right_line_range.start;
         for (line_type, content) in hunk.lines {
             match line_type {
                 DiffLineType::Context => {
@@ -658,18 +2235,36 @@ fn show_unified_diff_hunks(
                         formatter.write_str(" ")?;
                         formatter.write_all(content)
                     })?;
+                    left_line += 1;
+                    right_line += 1;
                 }
                 DiffLineType::Removed => {
-                    formatter.with_label("removed", |formatter| {
+                    let label = if moved_lines.removed.contains(&left_line) {
+                        "moved_removed"
+                    } else {
+                        "removed"
+                    };
+                    formatter.with_label(label, |formatter| {
                         formatter.write_str("-")?;
                         formatter.write_all(content)
                     })?;
+                    left_line += 1;
                 }
                 DiffLineType::Added => {
-                    formatter.with_label("added", |formatter| {
+                    let label = if moved_lines.added.contains(&right_line) {
+                        "moved_added"
+                    } else {
+                        "added"
+                    };
+                    formatter.with_label(label, |formatter| {
                         formatter.write_str("+")?;
-                        formatter.write_all(content)
+                        if highlight_whitespace {
+                            WhitespaceErrorWriter::new(content).write(formatter, content)
+                        } else {
+                            formatter.write_all(content)
+                        }
                     })?;
+                    right_line += 1;
                 }
             }
             if !content.ends_with(b"\n") {
@@ -680,108 +2275,393 @@ fn show_unified_diff_hunks(
     Ok(())
 }
 
-pub fn show_git_diff(
+/// Like `show_unified_diff_hunks()`, but writes runs of removed and added lines
+/// as one text with only the changed words marked.
+fn show_word_diff_hunks(
+    formatter: &mut dyn Formatter,
+    left_content: &[u8],
+    right_content: &[u8],
+    num_context_lines: usize,
+    line_compare: LineCompareMode,
+) -> Result<(), CommandError> {
+    for hunk in unified_diff_hunks(left_content, right_content, num_context_lines, line_compare) {
+        write_unified_hunk_header(formatter, &hunk)?;
+        let mut removed_lines = vec![];
+        let mut added_lines = vec![];
+        for (line_type, content) in hunk.lines {
+            match line_type {
+                DiffLineType::Context => {
+                    show_word_diff_region(formatter, &removed_lines, &added_lines)?;
+                    removed_lines.clear();
+                    added_lines.clear();
+                    formatter.with_label("context", |formatter| formatter.write_all(content))?;
+                    if !content.ends_with(b"\n") {
+                        formatter.write_str("\n")?;
+                    }
+                }
+                DiffLineType::Removed => removed_lines.extend_from_slice(content),
+                DiffLineType::Added => added_lines.extend_from_slice(content),
+            }
+        }
+        show_word_diff_region(formatter, &removed_lines, &added_lines)?;
+    }
+    Ok(())
+}
+
+/// Writes the changed lines `left` and `right` as a single text, marking the
+/// removed words with `[-...-]` and the added words with `{+...+}`.
+fn show_word_diff_region(
+    formatter: &mut dyn Formatter,
+    left: &[u8],
+    right: &[u8],
+) -> io::Result<()> {
+    if left.is_empty() && right.is_empty() {
+        return Ok(());
+    }
+    let mut ends_with_newline = false;
+    let diff = Diff::default_refinement(&[left, right]);
+    for hunk in diff.hunks() {
+        match hunk {
+            DiffHunk::Matching(content) => {
+                formatter.with_label("context", |formatter| formatter.write_all(content))?;
+                ends_with_newline = content.ends_with(b"\n");
+            }
+            DiffHunk::Different(contents) => {
+                for (content, label, open, close) in [
+                    (contents[0], "removed", "[-", "-]"),
+                    (contents[1], "added", "{+", "+}"),
+                ] {
+                    if content.is_empty() {
+                        continue;
+                    }
+                    // Close the marker at each line end so every line can be
+                    // read on its own.
+                    for line in content.split_inclusive(|b| *b == b'\n') {
+                        let text = line.strip_suffix(b"\n").unwrap_or(line);
+                        formatter.with_label(label, |formatter| {
+                            formatter.write_str(open)?;
+                            formatter.write_all(text)?;
+                            formatter.write_str(close)
+                        })?;
+                        if text.len() != line.len() {
+                            formatter.write_str("\n")?;
+                        }
+                    }
+                    ends_with_newline = content.ends_with(b"\n");
+                }
+            }
+        }
+    }
+    if !ends_with_newline {
+        formatter.write_str("\n")?;
+    }
+    Ok(())
+}
+
+/// Writes a Git-format diff. With `word_diff`, the changed lines are shown
+/// with only the changed words marked, like `git diff --word-diff`. With
+/// `color_moved`, blocks of lines moved within the diff are labeled as moved.
+/// With `highlight_whitespace`, whitespace errors in added lines are labeled.
+/// Files larger than `max_file_size` are written with only the file header.
+fn show_git_diff(
     formatter: &mut dyn Formatter,
     workspace_command: &WorkspaceCommandHelper,
-    tree_diff: TreeDiffIterator,
+    entries: Vec<DiffEntry>,
+    options: &DiffRenderOptions,
 ) -> Result<(), CommandError> {
-    let repo = workspace_command.repo();
+    let &DiffRenderOptions {
+        context: num_context_lines,
+        line_compare,
+        word_diff,
+        binary: binary_patch,
+        color_moved,
+        highlight_whitespace,
+        max_file_size,
+    } = options;
+    let store = workspace_command.repo().store();
+    let moved_lines = if color_moved && !word_diff {
+        find_moved_lines_in_entries(store, &entries, line_compare, max_file_size)?
+    } else {
+        entries.iter().map(|_| MovedLines::default()).collect()
+    };
+    let entries = iter::zip(entries, moved_lines).collect_vec();
     formatter.push_label("diff")?;
-    for (path, diff) in tree_diff {
-        let path_string = path.to_internal_file_string();
-        let (left_value, right_value) = diff?;
-        if left_value.is_absent() {
-            let right_part = git_diff_part(repo, &path, &right_value)?;
-            formatter.with_label("file_header", |formatter| {
-                writeln!(formatter, "diff --git a/{path_string} b/{path_string}")?;
-                writeln!(formatter, "new file mode {}", &right_part.mode)?;
-                writeln!(formatter, "index 0000000000..{}", &right_part.hash)?;
-                writeln!(formatter, "--- /dev/null")?;
-                writeln!(formatter, "+++ b/{path_string}")
-            })?;
-            show_unified_diff_hunks(formatter, &[], &right_part.content)?;
-        } else if right_value.is_present() {
-            let left_part = git_diff_part(repo, &path, &left_value)?;
-            let right_part = git_diff_part(repo, &path, &right_value)?;
-            formatter.with_label("file_header", |formatter| {
-                writeln!(formatter, "diff --git a/{path_string} b/{path_string}")?;
-                if left_part.mode != right_part.mode {
-                    writeln!(formatter, "old mode {}", &left_part.mode)?;
-                    writeln!(formatter, "new mode {}", &right_part.mode)?;
-                    if left_part.hash != right_part.hash {
-                        writeln!(formatter, "index {}...{}", &left_part.hash, right_part.hash)?;
-                    }
-                } else if left_part.hash != right_part.hash {
+    write_file_diffs(formatter, &entries, |formatter, (entry, moved_lines)| {
+        let path_string = entry.path.to_internal_file_string();
+        let left_path = entry.source_path();
+        let left_path_string = left_path.to_internal_file_string();
+        let DiffEntry {
+            path,
+            left_value,
+            right_value,
+            renamed_from,
+        } = entry;
+        let left_part = if left_value.is_present() {
+            Some(git_diff_part(store, left_path, left_value, max_file_size)?)
+        } else {
+            None
+        };
+        let right_part = if right_value.is_present() {
+            Some(git_diff_part(store, path, right_value, max_file_size)?)
+        } else {
+            None
+        };
+        let left_content = left_part
+            .as_ref()
+            .map_or(Ok(&[][..]), |part| part.content.as_deref());
+        let right_content = right_part
+            .as_ref()
+            .map_or(Ok(&[][..]), |part| part.content.as_deref());
+        let binary = [left_content, right_content]
+            .iter()
+            .any(|content| content.is_ok_and(is_binary));
+        // `git apply` needs the full ids to apply a binary patch.
+        let full_index = binary && binary_patch;
+        let index_hash = |part: &Option<GitDiffPart>| match (part, full_index) {
+            (Some(part), true) => part.hash.clone(),
+            (Some(part), false) => part.hash[..10].to_owned(),
+            (None, true) => "0".repeat(40),
+            (None, false) => "0".repeat(10),
+        };
+        formatter.with_label("file_header", |formatter| {
+            writeln!(formatter, "diff --git a/{left_path_string} b/{path_string}")?;
+            match (&left_part, &right_part) {
+                (None, Some(right)) => {
+                    writeln!(formatter, "new file mode {}", &right.mode)?;
+                    writeln!(
+                        formatter,
+                        "index {}..{}",
+                        index_hash(&left_part),
+                        index_hash(&right_part)
+                    )?;
+                }
+                (Some(left), None) => {
+                    writeln!(formatter, "deleted file mode {}", &left.mode)?;
                     writeln!(
                         formatter,
-                        "index {}...{} {}",
-                        &left_part.hash, right_part.hash, left_part.mode
+                        "index {}..{}",
+                        index_hash(&left_part),
+                        index_hash(&right_part)
                     )?;
                 }
-                if left_part.content != right_part.content {
-                    writeln!(formatter, "--- a/{path_string}")?;
-                    writeln!(formatter, "+++ b/{path_string}")?;
+                (Some(left), Some(right)) => {
+                    if left.mode != right.mode {
+                        writeln!(formatter, "old mode {}", &left.mode)?;
+                        writeln!(formatter, "new mode {}", &right.mode)?;
+                    }
+                    if let Some((_, similarity)) = renamed_from {
+                        writeln!(formatter, "similarity index {similarity}%")?;
+                        writeln!(formatter, "rename from {left_path_string}")?;
+                        writeln!(formatter, "rename to {path_string}")?;
+                    }
+                    if left.hash != right.hash {
+                        write!(
+                            formatter,
+                            "index {}..{}",
+                            index_hash(&left_part),
+                            index_hash(&right_part)
+                        )?;
+                        if left.mode == right.mode {
+                            write!(formatter, " {}", left.mode)?;
+                        }
+                        writeln!(formatter)?;
+                    }
                 }
-                Ok(())
-            })?;
-            show_unified_diff_hunks(formatter, &left_part.content, &right_part.content)?;
+                (None, None) => panic!("absent path in diff"),
+            }
+            Ok(())
+        })?;
+        let (left_content, right_content) = match (left_content, right_content) {
+            (Ok(left_content), Ok(right_content)) => (left_content, right_content),
+            (Err(too_large), _) | (_, Err(too_large)) => {
+                let left_hash = left_part.as_ref().map(|part| &part.hash);
+                let right_hash = right_part.as_ref().map(|part| &part.hash);
+                if left_hash != right_hash {
+                    writeln!(formatter, "({too_large})")?;
+                }
+                return Ok(());
+            }
+        };
+        // Like Git, write only the file header for mode-only changes, pure
+        // renames, and added or deleted empty files.
+        if left_content == right_content {
+            return Ok(());
+        }
+        let left_label = if left_part.is_some() {
+            format!("a/{left_path_string}")
+        } else {
+            "/dev/null".to_owned()
+        };
+        let right_label = if right_part.is_some() {
+            format!("b/{path_string}")
+        } else {
+            "/dev/null".to_owned()
+        };
+        if binary && binary_patch {
+            show_git_binary_patch(formatter, left_content, right_content)?;
+        } else if binary {
+            writeln!(
+                formatter,
+                "Binary files {left_label} and {right_label} differ"
+            )?;
         } else {
-            let left_part = git_diff_part(repo, &path, &left_value)?;
             formatter.with_label("file_header", |formatter| {
-                writeln!(formatter, "diff --git a/{path_string} b/{path_string}")?;
-                writeln!(formatter, "deleted file mode {}", &left_part.mode)?;
-                writeln!(formatter, "index {}..0000000000", &left_part.hash)?;
-                writeln!(formatter, "--- a/{path_string}")?;
-                writeln!(formatter, "+++ /dev/null")
+                writeln!(formatter, "--- {left_label}")?;
+                writeln!(formatter, "+++ {right_label}")
             })?;
-            show_unified_diff_hunks(formatter, &left_part.content, &[])?;
+            if word_diff {
+                show_word_diff_hunks(
+                    formatter,
+                    left_content,
+                    right_content,
+                    num_context_lines,
+                    line_compare,
+                )?;
+            } else {
+                show_unified_diff_hunks(
+                    formatter,
+                    left_content,
+                    right_content,
+                    num_context_lines,
+                    line_compare,
+                    moved_lines,
+                    highlight_whitespace,
+                )?;
+            }
         }
-    }
+        Ok(())
+    })?;
     formatter.pop_label()?;
     Ok(())
 }
 
+/// Writes the binary patch in Git's base85-encoded format, which `git apply`
+/// accepts. The file header should have been written by the caller.
+fn show_git_binary_patch(
+    formatter: &mut dyn Formatter,
+    left_content: &[u8],
+    right_content: &[u8],
+) -> Result<(), CommandError> {
+    let mut options = git2::DiffOptions::new();
+    options.show_binary(true).force_binary(true);
+    let patch =
+        git2::Patch::from_buffers(left_content, None, right_content, None, Some(&mut options))?
+            .to_buf()?;
+    // libgit2 writes its own file header, which isn't aware of the paths.
+    let marker = b"GIT binary patch\n";
+    let start = patch
+        .windows(marker.len())
+        .position(|window| window == marker)
+        .ok_or_else(|| CommandError::InternalError("Failed to create binary patch".to_owned()))?;
+    formatter.write_all(&patch[start..])?;
+    Ok(())
+}
+
 #[instrument(skip_all)]
 pub fn show_diff_summary(
     formatter: &mut dyn Formatter,
     workspace_command: &WorkspaceCommandHelper,
     tree_diff: TreeDiffIterator,
-) -> io::Result<()> {
+    renames: Option<RenameDetection>,
+    max_file_size: Option<u64>,
+) -> Result<(), CommandError> {
+    let entries = diff_entries(workspace_command.repo(), tree_diff, renames, max_file_size)?;
+    write_diff_summary(formatter, workspace_command, &entries)
+}
+
+fn write_diff_summary(
+    formatter: &mut dyn Formatter,
+    workspace_command: &WorkspaceCommandHelper,
+    entries: &[DiffEntry],
+) -> Result<(), CommandError> {
     formatter.with_label("diff", |formatter| {
-        for (repo_path, diff) in tree_diff {
-            let (before, after) = diff.unwrap();
-            if before.is_present() && after.is_present() {
+        for entry in entries {
+            let repo_path = &entry.path;
+            let (before, after) = (&entry.left_value, &entry.right_value);
+            let mode_change = git_mode_change(before, after)
+                .map(|(left_mode, right_mode)| format!(" (mode {left_mode} => {right_mode})"))
+                .unwrap_or_default();
+            if let Some((source_path, _)) = &entry.renamed_from {
+                // Braced so that it can't be mistaken for a removed file whose
+                // name contains a space.
+                writeln!(
+                    formatter.labeled("renamed"),
+                    "R {{{} => {}}}{mode_change}",
+                    workspace_command.format_file_path(source_path),
+                    workspace_command.format_file_path(repo_path)
+                )?;
+            } else if !after.is_resolved() {
+                writeln!(
+                    formatter.labeled("conflict"),
+                    "C {}",
+                    workspace_command.format_file_path(repo_path)
+                )?;
+            } else if before.is_present() && after.is_present() {
+                // Mode changes other than flipping the executable bit change
+                // the file type.
+                let type_changed = match git_mode_change(before, after) {
+                    Some(("100644", "100755")) | Some(("100755", "100644")) | None => false,
+                    Some(_) => true,
+                };
                 writeln!(
                     formatter.labeled("modified"),
-                    "M {}",
-                    workspace_command.format_file_path(&repo_path)
+                    "{} {}{mode_change}",
+                    if type_changed { 'T' } else { 'M' },
+                    workspace_command.format_file_path(repo_path)
                 )?;
             } else if before.is_absent() {
                 writeln!(
                     formatter.labeled("added"),
                     "A {}",
-                    workspace_command.format_file_path(&repo_path)
+                    workspace_command.format_file_path(repo_path)
                 )?;
             } else {
                 writeln!(
                     formatter.labeled("removed"),
                     "R {}",
-                    workspace_command.format_file_path(&repo_path)
+                    workspace_command.format_file_path(repo_path)
                 )?;
             }
         }
         Ok(())
-    })
+    })?;
+    Ok(())
 }
 
-struct DiffStat {
+pub struct DiffStat {
+    repo_path: RepoPath,
     path: String,
     added: usize,
     removed: usize,
+    /// File sizes before and after if either side is binary.
+    binary_sizes: Option<(usize, usize)>,
+    /// Git file modes before and after if the mode changed.
+    mode_change: Option<(&'static str, &'static str)>,
 }
 
-fn get_diff_stat(path: String, left_content: &[u8], right_content: &[u8]) -> DiffStat {
-    let hunks = unified_diff_hunks(left_content, right_content, 0);
+/// Returns true if the content looks like binary data. Like Git, this checks
+/// for a NUL byte near the start of the content.
+fn is_binary(content: &[u8]) -> bool {
+    content.iter().take(8000).any(|&b| b == 0)
+}
+
+fn get_diff_stat(
+    repo_path: RepoPath,
+    path: String,
+    left_content: &[u8],
+    right_content: &[u8],
+) -> DiffStat {
+    if is_binary(left_content) || is_binary(right_content) {
+        return DiffStat {
+            repo_path,
+            path,
+            added: 0,
+            removed: 0,
+            binary_sizes: Some((left_content.len(), right_content.len())),
+            mode_change: None,
+        };
+    }
+    let hunks = unified_diff_hunks(left_content, right_content, 0, LineCompareMode::Exact);
     let mut added = 0;
     let mut removed = 0;
     for hunk in hunks {
@@ -794,31 +2674,88 @@ fn get_diff_stat(path: String, left_content: &[u8], right_content: &[u8]) -> Dif
         }
     }
     DiffStat {
+        repo_path,
         path,
         added,
         removed,
+        binary_sizes: None,
+        mode_change: None,
     }
 }
 
-pub fn show_diff_stat(
-    ui: &Ui,
-    formatter: &mut dyn Formatter,
+/// Counts the changed lines of the entries. Files larger than `max_file_size`
+/// aren't diffed, but only their sizes are reported as if they were binary.
+fn collect_diff_stats(
     workspace_command: &WorkspaceCommandHelper,
-    tree_diff: TreeDiffIterator,
-) -> Result<(), CommandError> {
+    entries: &[DiffEntry],
+    max_file_size: Option<u64>,
+) -> Result<Vec<DiffStat>, CommandError> {
+    let store = workspace_command.repo().store();
     let mut stats: Vec<DiffStat> = vec![];
-    let mut max_path_width = 0;
-    let mut max_diffs = 0;
-    for (repo_path, diff) in tree_diff {
-        let (left, right) = diff?;
-        let path = workspace_command.format_file_path(&repo_path);
-        let left_content = diff_content(workspace_command.repo(), &repo_path, &left)?;
-        let right_content = diff_content(workspace_command.repo(), &repo_path, &right)?;
-        max_path_width = max(max_path_width, path.width());
-        let stat = get_diff_stat(path, &left_content, &right_content);
-        max_diffs = max(max_diffs, stat.added + stat.removed);
-        stats.push(stat);
+    for batch in entries.chunks(DIFF_BATCH_SIZE) {
+        let paths = batch
+            .iter()
+            .map(|entry| workspace_command.format_file_path(&entry.path))
+            .collect_vec();
+        let batch_stats: Vec<_> = batch
+            .par_iter()
+            .zip(paths)
+            .map(|(entry, path)| {
+                let DiffEntry {
+                    path: repo_path,
+                    left_value: left,
+                    right_value: right,
+                    renamed_from: _,
+                } = entry;
+                let left_content =
+                    diff_content_within_limit(store, entry.source_path(), left, max_file_size)?;
+                let right_content =
+                    diff_content_within_limit(store, repo_path, right, max_file_size)?;
+                let stat = match (left_content, right_content) {
+                    (Ok(left_content), Ok(right_content)) => {
+                        get_diff_stat(repo_path.clone(), path, &left_content, &right_content)
+                    }
+                    _ => DiffStat {
+                        repo_path: repo_path.clone(),
+                        path,
+                        added: 0,
+                        removed: 0,
+                        binary_sizes: Some((
+                            diff_content_size(store, entry.source_path(), left)?,
+                            diff_content_size(store, repo_path, right)?,
+                        )),
+                        mode_change: None,
+                    },
+                };
+                let mode_change = git_mode_change(left, right);
+                Ok::<_, CommandError>(DiffStat {
+                    mode_change,
+                    ..stat
+                })
+            })
+            .collect();
+        for stat in batch_stats {
+            stats.push(stat?);
+        }
     }
+    Ok(stats)
+}
+
+pub fn show_diff_stat(
+    ui: &Ui,
+    formatter: &mut dyn Formatter,
+    stats: &[DiffStat],
+) -> io::Result<()> {
+    let max_path_width = stats
+        .iter()
+        .map(|stat| stat.path.width())
+        .max()
+        .unwrap_or(0);
+    let max_diffs = stats
+        .iter()
+        .map(|stat| stat.added + stat.removed)
+        .max()
+        .unwrap_or(0);
 
     let number_padding = max_diffs.to_string().len();
     // 4 characters padding for the graph
@@ -838,7 +2775,7 @@ pub fn show_diff_stat(
         let mut total_added = 0;
         let mut total_removed = 0;
         let total_files = stats.len();
-        for stat in &stats {
+        for stat in stats {
             total_added += stat.added;
             total_removed += stat.removed;
             let bar_added = (stat.added as f64 * factor).ceil() as usize;
@@ -846,6 +2783,14 @@ pub fn show_diff_stat(
             // replace start of path with ellipsis if the path is too long
             let (path, path_width) = text_util::elide_start(&stat.path, "...", max_path_width);
             let path_pad_width = max_path_width - path_width;
+            if let Some((left_size, right_size)) = stat.binary_sizes {
+                writeln!(
+                    formatter,
+                    "{path}{:path_pad_width$} | Bin {left_size} -> {right_size} bytes",
+                    "", // pad to max_path_width
+                )?;
+                continue;
+            }
             write!(
                 formatter,
                 "{path}{:path_pad_width$} | {:>number_padding$}{}",
@@ -866,31 +2811,115 @@ pub fn show_diff_stat(
             total_removed,
             if total_removed == 1 { "" } else { "s" },
         )?;
+        // Like `git diff --stat --summary`, list the mode changes after the
+        // stats.
+        for stat in stats {
+            if let Some((left_mode, right_mode)) = stat.mode_change {
+                writeln!(
+                    formatter.labeled("modified"),
+                    " mode change {left_mode} => {right_mode} {}",
+                    stat.path
+                )?;
+            }
+        }
         Ok(())
-    })?;
-    Ok(())
+    })
 }
 
-pub fn show_types(
+pub fn show_diff_dirstat(
     formatter: &mut dyn Formatter,
     workspace_command: &WorkspaceCommandHelper,
-    tree_diff: TreeDiffIterator,
+    stats: &[DiffStat],
+    depth: usize,
+) -> io::Result<()> {
+    let mut dir_changes: BTreeMap<RepoPath, usize> = BTreeMap::new();
+    for stat in stats {
+        let changes = stat.added + stat.removed;
+        if changes == 0 {
+            continue;
+        }
+        let dir_components = stat
+            .repo_path
+            .components()
+            .split_last()
+            .map_or(&[][..], |(_, dir)| dir);
+        let dir = RepoPath::from_components(dir_components.iter().take(depth).cloned().collect());
+        *dir_changes.entry(dir).or_default() += changes;
+    }
+    let permilles = split_permille(&dir_changes.values().copied().collect_vec());
+    formatter.with_label("diff", |formatter| {
+        for (dir, permille) in dir_changes.keys().zip(permilles) {
+            let mut dir_path = workspace_command.format_file_path(dir);
+            if !dir_path.ends_with(std::path::MAIN_SEPARATOR) {
+                dir_path.push(std::path::MAIN_SEPARATOR);
+            }
+            writeln!(
+                formatter,
+                "{:>4}.{}% {dir_path}",
+                permille / 10,
+                permille % 10
+            )?;
+        }
+        Ok(())
+    })
+}
+
+/// Converts the counts to shares of their total in tenths of a percent. The
+/// largest remainders are rounded up so that the shares add up to exactly
+/// 100%.
+fn split_permille(counts: &[usize]) -> Vec<usize> {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return vec![0; counts.len()];
+    }
+    let mut permilles = counts
+        .iter()
+        .map(|&count| count * 1000 / total)
+        .collect_vec();
+    let missing = 1000 - permilles.iter().sum::<usize>();
+    let by_remainder = (0..counts.len()).sorted_by_key(|&i| Reverse(counts[i] * 1000 % total));
+    for i in by_remainder.take(missing) {
+        permilles[i] += 1;
+    }
+    permilles
+}
+
+fn show_types(
+    formatter: &mut dyn Formatter,
+    workspace_command: &WorkspaceCommandHelper,
+    entries: &[DiffEntry],
 ) -> io::Result<()> {
     formatter.with_label("diff", |formatter| {
-        for (repo_path, diff) in tree_diff {
-            let (before, after) = diff.unwrap();
+        for entry in entries {
             writeln!(
                 formatter.labeled("modified"),
                 "{}{} {}",
-                diff_summary_char(&before),
-                diff_summary_char(&after),
-                workspace_command.format_file_path(&repo_path)
+                diff_summary_char(&entry.left_value),
+                diff_summary_char(&entry.right_value),
+                workspace_command.format_file_path(&entry.path)
             )?;
         }
         Ok(())
     })
 }
 
+fn show_names(
+    formatter: &mut dyn Formatter,
+    workspace_command: &WorkspaceCommandHelper,
+    entries: &[DiffEntry],
+    null_separated: bool,
+) -> io::Result<()> {
+    let separator = if null_separated { '\0' } else { '\n' };
+    for entry in entries {
+        write!(
+            formatter,
+            "{}{separator}",
+            workspace_command.format_file_path(&entry.path)
+        )?;
+    }
+    Ok(())
+}
+
 fn diff_summary_char(value: &MergedTreeValue) -> char {
     match value.as_resolved() {
         Some(None) => '-',
@@ -903,3 +2932,177 @@ fn diff_summary_char(value: &MergedTreeValue) -> char {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::ColorFormatter;
+
+    fn format_color_words_diff_lines(
+        left: &[u8],
+        right: &[u8],
+        highlight_whitespace: bool,
+    ) -> String {
+        let config = config::Config::builder()
+            .add_source(config::File::from_str(
+                r#"
+                colors.removed = "red"
+                colors.added = "green"
+                colors."removed token" = { fg = "red", underline = true }
+                colors."added token" = { fg = "green", underline = true }
+                colors.whitespace-error = { bg = "red" }
+                "#,
+                config::FileFormat::Toml,
+            ))
+            .build()
+            .unwrap();
+        let mut output = Vec::new();
+        let mut formatter = ColorFormatter::for_config(&mut output, &config).unwrap();
+        for diff_line in files::diff(left, right) {
+            show_color_words_diff_line(
+                &mut formatter,
+                &diff_line,
+                &MovedLines::default(),
+                highlight_whitespace,
+            )
+            .unwrap();
+        }
+        drop(formatter);
+        String::from_utf8_lossy(&output).into_owned()
+    }
+
+    #[test]
+    fn test_color_words_diff_line_chars() {
+        // Only the changed characters are labeled as token
+        let output = format_color_words_diff_lines(
+            b"compute_branch_target()\n",
+            b"compute_branch_targets()\n",
+            false,
+        );
+        insta::assert_snapshot!(output, @r###"
+        [38;5;1m   1[39m [38;5;2m   1[39m: [38;5;1mcompute_branch_target[39m[38;5;2mcompute_branch_target[4ms[24m[39m()
+        "###);
+        let output = format_color_words_diff_lines(
+            "naïve café\n".as_bytes(),
+            "naive cafe\n".as_bytes(),
+            false,
+        );
+        insta::assert_snapshot!(output, @r###"
+        [38;5;1m   1[39m [38;5;2m   1[39m: [38;5;1mna[4mï[24mve[39m[38;5;2mna[4mi[24mve[39m [38;5;1mcaf[4mé[24m[39m[38;5;2mcaf[4me[24m[39m
+        "###);
+    }
+
+    #[test]
+    fn test_color_words_diff_line_chars_fallback() {
+        // Words with nothing in common
+        let output = format_color_words_diff_lines(b"foo\n", b"bar\n", false);
+        insta::assert_snapshot!(output, @r###"
+        [38;5;1m   1[39m [38;5;2m   1[39m: [38;5;1mfoo[39m[38;5;2mbar[39m
+        "###);
+        // Non-UTF-8 words
+        let output = format_color_words_diff_lines(b"a\xff\xfe\n", b"a\xff\xfd\n", false);
+        insta::assert_snapshot!(output, @r###"
+        [38;5;1m   1[39m [38;5;2m   1[39m: [38;5;1ma��[39m[38;5;2ma��[39m
+        "###);
+        // Very long words
+        let left = format!("{}a\n", "x".repeat(MAX_CHAR_DIFF_LEN));
+        let right = format!("{}b\n", "x".repeat(MAX_CHAR_DIFF_LEN));
+        let output = format_color_words_diff_lines(left.as_bytes(), right.as_bytes(), false);
+        assert!(!output.contains("\x1b[4m"), "{output:?}");
+    }
+
+    #[test]
+    fn test_color_words_diff_line_whitespace_errors() {
+        // Trailing whitespace of an added line
+        let output = format_color_words_diff_lines(b"x\n", b"x\nfoo \t\n", true);
+        insta::assert_snapshot!(output, @r###"
+        [38;5;1m   1[39m [38;5;2m   1[39m: x
+             [38;5;2m   2[39m: [38;5;2mfoo[48;5;1m 	[49m[39m
+        "###);
+        // Trailing whitespace added to a modified line, also by chars
+        let output = format_color_words_diff_lines(b"foo bar\n", b"foo bar  \n", true);
+        insta::assert_snapshot!(output, @r###"
+        [38;5;1m   1[39m [38;5;2m   1[39m: foo bar[38;5;2m[48;5;1m  [39m[49m
+        "###);
+        let output = format_color_words_diff_lines(b"foo bar\n", b"foo baz  \n", true);
+        insta::assert_snapshot!(output, @r###"
+        [38;5;1m   1[39m [38;5;2m   1[39m: foo [38;5;1mba[4mr[24m[39m[38;5;2mba[4mz[48;5;1m  [24m[39m[49m
+        "###);
+        // A space before a tab in the indentation
+        let output = format_color_words_diff_lines(b"x\n", b"x\n \tfoo\n", true);
+        insta::assert_snapshot!(output, @r###"
+        [38;5;1m   1[39m [38;5;2m   1[39m: x
+             [38;5;2m   2[39m: [38;5;2m[48;5;1m 	[49mfoo[39m
+        "###);
+        // Removed and unmodified lines aren't highlighted
+        let output = format_color_words_diff_lines(b"foo \nbar \n", b"foo \n", true);
+        insta::assert_snapshot!(output, @r###"
+        [38;5;1m   1[39m [38;5;2m   1[39m: foo 
+        [38;5;1m   2[39m     : [38;5;1mbar [39m
+        "###);
+        // Nothing is highlighted unless requested
+        let output = format_color_words_diff_lines(b"x\n", b"x\nfoo \n", false);
+        insta::assert_snapshot!(output, @r###"
+        [38;5;1m   1[39m [38;5;2m   1[39m: x
+             [38;5;2m   2[39m: [38;5;2mfoo [39m
+        "###);
+    }
+
+    #[test]
+    fn test_find_whitespace_errors() {
+        assert!(find_whitespace_errors(b"foo\n").is_empty());
+        assert_eq!(find_whitespace_errors(b"foo  \n"), vec![3..5]);
+        assert_eq!(find_whitespace_errors(b"foo\t"), vec![3..4]);
+        assert_eq!(find_whitespace_errors(b"  \n"), vec![0..2]);
+        assert!(find_whitespace_errors(b"\t  foo\n").is_empty());
+        assert_eq!(find_whitespace_errors(b"  \tfoo \n"), vec![0..3, 6..7]);
+    }
+
+    #[test]
+    fn test_split_permille() {
+        assert_eq!(split_permille(&[]), Vec::<usize>::new());
+        assert_eq!(split_permille(&[0, 0]), vec![0, 0]);
+        assert_eq!(split_permille(&[5]), vec![1000]);
+        assert_eq!(split_permille(&[1, 3]), vec![250, 750]);
+        // The largest remainders are rounded up, earlier counts first on ties
+        assert_eq!(split_permille(&[1, 1, 1]), vec![334, 333, 333]);
+        assert_eq!(split_permille(&[1, 2, 4]), vec![143, 286, 571]);
+        for counts in [&[1, 1, 1, 1, 1, 1][..], &[7, 13, 1, 1], &[999, 1, 1]] {
+            assert_eq!(split_permille(counts).iter().sum::<usize>(), 1000);
+        }
+    }
+
+    #[test]
+    fn test_find_moved_lines() {
+        let moved_block = "call_the_first_function();\ncall_the_second_function();\n";
+        let blocks = [
+            ChangedBlocks::new(
+                format!("a\n{moved_block}b\n").as_bytes(),
+                b"a\nb\nshort\n",
+                LineCompareMode::Exact,
+            ),
+            ChangedBlocks::new(
+                b"short\nc\n",
+                format!("c\n{moved_block}").as_bytes(),
+                LineCompareMode::Exact,
+            ),
+        ];
+        let moved_lines = find_moved_lines(&blocks);
+        let sorted = |lines: &HashSet<usize>| lines.iter().copied().sorted().collect_vec();
+        // Lines with too few alphanumeric characters aren't moved
+        assert_eq!(sorted(&moved_lines[0].removed), vec![2, 3]);
+        assert_eq!(sorted(&moved_lines[0].added), Vec::<usize>::new());
+        assert_eq!(sorted(&moved_lines[1].removed), Vec::<usize>::new());
+        assert_eq!(sorted(&moved_lines[1].added), vec![2, 3]);
+
+        // Nothing is detected if too many lines changed
+        let many_lines = moved_block.repeat(MAX_MOVED_CANDIDATE_LINES / 2);
+        let blocks = [
+            ChangedBlocks::new(many_lines.as_bytes(), b"", LineCompareMode::Exact),
+            ChangedBlocks::new(b"", moved_block.as_bytes(), LineCompareMode::Exact),
+        ];
+        let moved_lines = find_moved_lines(&blocks);
+        assert!(moved_lines[0].removed.is_empty());
+        assert!(moved_lines[1].added.is_empty());
+    }
+}
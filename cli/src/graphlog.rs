@@ -17,7 +17,7 @@ use std::io::Write;
 use std::{cmp, io};
 
 use itertools::Itertools;
-use jj_lib::settings::UserSettings;
+use jj_lib::settings::{ConfigResultExt as _, UserSettings};
 use renderdag::{Ancestor, GraphRowRenderer, Renderer};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -131,13 +131,16 @@ impl<'writer, R> SaplingGraphLog<'writer, R> {
     }
 }
 
+/// Names accepted by the `ui.graph.style` setting.
+pub const GRAPH_STYLES: &[&str] = &["curved", "square", "ascii", "ascii-large", "legacy"];
+
 pub fn get_graphlog<'a, K: Clone + Eq + Hash + 'a>(
     settings: &UserSettings,
     formatter: &'a mut dyn Write,
-) -> Box<dyn GraphLog<K> + 'a> {
+) -> Result<Box<dyn GraphLog<K> + 'a>, config::ConfigError> {
     let builder = GraphRowRenderer::new().output().with_min_row_height(0);
 
-    match settings.graph_style().as_str() {
+    let graph: Box<dyn GraphLog<K> + 'a> = match settings.graph_style().as_str() {
         "curved" => SaplingGraphLog::create(builder.build_box_drawing(), formatter, "◉"),
         "square" => SaplingGraphLog::create(
             builder.build_box_drawing().with_square_glyphs(),
@@ -146,7 +149,49 @@ pub fn get_graphlog<'a, K: Clone + Eq + Hash + 'a>(
         ),
         "ascii" => SaplingGraphLog::create(builder.build_ascii(), formatter, "o"),
         "ascii-large" => SaplingGraphLog::create(builder.build_ascii_large(), formatter, "o"),
-        _ => Box::new(AsciiGraphDrawer::new(formatter)),
+        "legacy" => Box::new(AsciiGraphDrawer::new(formatter)),
+        style => {
+            return Err(config::ConfigError::Message(format!(
+                "Invalid `ui.graph.style`: {style:?} (valid options are: {})",
+                GRAPH_STYLES
+                    .iter()
+                    .map(|name| format!("{name:?}"))
+                    .join(", ")
+            )));
+        }
+    };
+    Ok(graph)
+}
+
+/// Symbols used to draw the graph nodes, overridable by `ui.graph.node.*`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GraphNodeSymbols {
+    pub default: String,
+    pub working_copy: String,
+    pub immutable: String,
+    pub conflict: String,
+}
+
+impl GraphNodeSymbols {
+    /// Loads the node symbols, falling back to the graph style's
+    /// `default_node_symbol` for the node types that aren't configured.
+    pub fn from_settings(
+        settings: &UserSettings,
+        default_node_symbol: &str,
+    ) -> Result<Self, config::ConfigError> {
+        let get = |name: &str, default: &str| -> Result<String, config::ConfigError> {
+            Ok(settings
+                .config()
+                .get_string(&format!("ui.graph.node.{name}"))
+                .optional()?
+                .unwrap_or_else(|| default.to_owned()))
+        };
+        Ok(GraphNodeSymbols {
+            default: default_node_symbol.to_owned(),
+            working_copy: get("working-copy", "@")?,
+            immutable: get("immutable", default_node_symbol)?,
+            conflict: get("conflict", default_node_symbol)?,
+        })
     }
 }
 
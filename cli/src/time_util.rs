@@ -85,3 +85,32 @@ pub fn format_duration(from: &Timestamp, to: &Timestamp, format: &timeago::Forma
 pub fn format_timestamp_relative_to_now(timestamp: &Timestamp) -> String {
     format_duration(timestamp, &Timestamp::now(), &timeago::Formatter::new())
 }
+
+/// Parses a duration such as `30d` or `2w`.
+///
+/// The supported units are `m` (minutes), `h` (hours), `d` (days), and `w`
+/// (weeks).
+pub fn parse_duration(s: &str) -> Result<std::time::Duration, String> {
+    let unit_start = s
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| "missing unit (expected one of m, h, d, w)".to_string())?;
+    let (count, unit) = s.split_at(unit_start);
+    let count: u64 = count
+        .parse()
+        .map_err(|_| "expected a number followed by a unit, e.g. 30d".to_string())?;
+    let unit_secs = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "invalid unit `{unit}` (expected one of m, h, d, w)"
+            ))
+        }
+    };
+    count
+        .checked_mul(unit_secs)
+        .map(std::time::Duration::from_secs)
+        .ok_or_else(|| "duration is too large".to_string())
+}
@@ -342,6 +342,32 @@ impl Ui {
         Ok(buf)
     }
 
+    /// Asks a yes/no question, repeating it until a valid answer is given.
+    ///
+    /// An empty answer selects the `default` if any.
+    pub fn prompt_yes_no(&mut self, prompt: &str, default: Option<bool>) -> io::Result<bool> {
+        let choices = match default {
+            Some(true) => "[Y/n]",
+            Some(false) => "[y/N]",
+            None => "[y/n]",
+        };
+        loop {
+            let answer = self.prompt(&format!("{prompt} {choices}"))?;
+            if answer.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "Reached end of input while waiting for an answer",
+                ));
+            }
+            match (answer.trim().to_ascii_lowercase().as_str(), default) {
+                ("y" | "yes", _) => return Ok(true),
+                ("n" | "no", _) => return Ok(false),
+                ("", Some(default)) => return Ok(default),
+                _ => writeln!(self.stderr(), "Please answer y or n.")?,
+            }
+        }
+    }
+
     pub fn prompt_password(&mut self, prompt: &str) -> io::Result<String> {
         if !io::stdout().is_terminal() {
             return Err(io::Error::new(
@@ -24,10 +24,12 @@ fn test_branch_multiple_names() {
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "set", "foo", "bar"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "set", "--allow-new", "foo", "bar"]);
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    warning: Updating multiple branches (2).
+    warning: Updating multiple branches (2): foo, bar
+    Updated 2 branches.
     "###);
 
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
@@ -45,768 +47,2797 @@ fn test_branch_multiple_names() {
     @   230dd059e1b0
     ◉   000000000000
     "###);
-}
-
-#[test]
-fn test_branch_at_root() {
-    let test_env = TestEnvironment::default();
-    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
-    let repo_path = test_env.env_root().join("repo");
 
+    // The count isn't printed with --quiet
     let (stdout, stderr) =
-        test_env.jj_cmd_ok(&repo_path, &["branch", "create", "fred", "-r=root()"]);
-    insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @"");
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+        test_env.jj_cmd_ok(&repo_path, &["branch", "create", "--quiet", "foo", "bar"]);
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    Nothing changed.
-    Failed to export some branches:
-      fred
+    warning: Creating multiple branches (2): foo, bar
     "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "-q", "glob:*"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
 }
 
 #[test]
-fn test_branch_empty_name() {
+fn test_branch_create_invalid_name() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "create", ""]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "create", "feat..oops"]);
     insta::assert_snapshot!(stderr, @r###"
-    error: a value is required for '<NAMES>...' but none was supplied
+    Error: Invalid branch name "feat..oops": branch names must not contain `..`
+    Hint: Branch names must be valid Git ref names to be exported and pushed. Use --allow-nonstandard to create it anyway.
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "create", "with space"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid branch name "with space": branch names must not contain spaces
+    Hint: Branch names must be valid Git ref names to be exported and pushed. Use --allow-nonstandard to create it anyway.
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "create", "foo/"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid branch name "foo/": branch names must not start or end with `/`
+    Hint: Branch names must be valid Git ref names to be exported and pushed. Use --allow-nonstandard to create it anyway.
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "create", "foo@{1}"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid branch name "foo@{1}": branch names must not contain `@{`
+    Hint: Branch names must be valid Git ref names to be exported and pushed. Use --allow-nonstandard to create it anyway.
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "set", "--allow-new", "foo\tbar"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid branch name "foo\tbar": branch names must not contain control characters
+    Hint: Branch names must be valid Git ref names to be exported and pushed. Use --allow-nonstandard to create it anyway.
+    "###);
 
-    For more information, try '--help'.
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "create", "--allow-nonstandard", "with space"],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "branch",
+            "set",
+            "--allow-new",
+            "--allow-nonstandard",
+            "feat..oops",
+        ],
+    );
+    let errors = [
+        "foo~1", "foo:bar", "glob*", "foo//bar", "foo.", ".hidden", "foo.lock", "@",
+    ]
+    .map(|name| {
+        let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "create", name]);
+        stderr.lines().next().unwrap().to_owned()
+    });
+    insta::assert_snapshot!(errors.join("\n"), @r###"
+    Error: Invalid branch name "foo~1": branch names must not contain any of `~^:?*[\`
+    Error: Invalid branch name "foo:bar": branch names must not contain any of `~^:?*[\`
+    Error: Invalid branch name "glob*": branch names must not contain any of `~^:?*[\`
+    Error: Invalid branch name "foo//bar": branch names must not contain `//`
+    Error: Invalid branch name "foo.": branch names must not end with `.`
+    Error: Invalid branch name ".hidden": branch names must not have a component starting with `.` or ending with `.lock`
+    Error: Invalid branch name "foo.lock": branch names must not have a component starting with `.` or ending with `.lock`
+    Error: Invalid branch name "@": branch names must not be `@`
+    "###);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "create", "--allow-invalid-name", "foo:bar"],
+    );
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  feat..oops foo:bar with space 230dd059e1b0
+    ◉   000000000000
     "###);
 }
 
 #[test]
-fn test_branch_forget_glob() {
+fn test_branch_create_warns_existing_branches() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "foo-1"]);
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "bar-2"]);
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "foo-3"]);
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "foo-4"]);
-
-    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    @  bar-2 foo-1 foo-3 foo-4 230dd059e1b0
-    ◉   000000000000
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 branch.
     "###);
-    let (stdout, stderr) =
-        test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "--glob", "foo-[1-3]"]);
-    insta::assert_snapshot!(stdout, @"");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "create", "bar"]);
     insta::assert_snapshot!(stderr, @r###"
-    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
-    Forgot 2 branches.
+    warning: Target commit already has branch: foo
+    Created 1 branch.
     "###);
-    test_env.jj_cmd_ok(&repo_path, &["undo"]);
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "glob:foo-[1-3]"]);
-    insta::assert_snapshot!(stdout, @"");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "create", "baz"]);
     insta::assert_snapshot!(stderr, @r###"
-    Forgot 2 branches.
+    warning: Target commit already has branches: bar, foo
+    Created 1 branch.
+    "###);
+
+    // Branches pointing to other commits aren't reported
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "create", "-r", "root()", "qux"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 branch.
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    @  bar-2 foo-4 230dd059e1b0
-    ◉   000000000000
+    @  bar baz foo 230dd059e1b0
+    ◉  qux 000000000000
     "###);
+}
+
+#[test]
+fn test_branch_create_per_branch_revisions() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "existing"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
 
-    // Forgetting a branch via both explicit name and glob pattern, or with
-    // multiple glob patterns, shouldn't produce an error.
     let (stdout, stderr) = test_env.jj_cmd_ok(
         &repo_path,
-        &["branch", "forget", "foo-4", "--glob", "foo-*", "glob:foo-*"],
+        &["branch", "create", "foo", "bar", "baz", "-r@", "-r@-", "-rroot()"],
     );
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
+    warning: Creating multiple branches (3): foo, bar, baz
+    warning: Target commit 230dd059e1b0 already has branch: existing
+    Created 3 branches.
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    @  bar-2 230dd059e1b0
-    ◉   000000000000
+    @  foo 4db490c88528
+    ◉  bar existing 230dd059e1b0
+    ◉  baz 000000000000
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-l1", "-Tdescription"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    create branch foo pointing to commit 4db490c88528133d579540b6900b8098f0c17701, branch bar pointing to commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22, branch baz pointing to commit 0000000000000000000000000000000000000000
     "###);
 
-    // Malformed glob
-    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "forget", "glob:foo-[1-3"]);
+    // Each branch must not exist yet
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["branch", "create", "qux", "foo", "-r@", "-r@-"],
+    );
     insta::assert_snapshot!(stderr, @r###"
-    error: invalid value 'glob:foo-[1-3' for '[NAMES]...': Pattern syntax error near position 4: invalid range pattern
-
-    For more information, try '--help'.
+    Error: Branch already exists: foo
+    Hint: Use `jj branch set` to update it.
     "###);
-
-    // We get an error if none of the globs match anything
+    // The revisions must be paired with the names
     let stderr = test_env.jj_cmd_failure(
         &repo_path,
-        &["branch", "forget", "glob:bar*", "glob:baz*", "--glob=boom*"],
+        &["branch", "create", "qux", "quux", "quuz", "-r@", "-r@-"],
     );
     insta::assert_snapshot!(stderr, @r###"
-    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
-    Error: No matching branches for patterns: baz*, boom*
+    Error: Got 2 revisions for 3 branches
+    Hint: Give a single --revision for all branches, or one for each branch in the same order.
+    "###);
+
+    // A branch name can contain `=`
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "create", "release=v1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    warning: Target commit already has branch: foo
+    Created 1 branch.
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "release=v1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    release=v1: kkmpptxz 4db490c8 (empty) (no description set)
     "###);
 }
 
 #[test]
-fn test_branch_delete_glob() {
-    // Set up a git repo with a branch and a jj repo that has it as a remote.
+fn test_branch_create_if_not_exists() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
-    let git_repo_path = test_env.env_root().join("git-repo");
-    let git_repo = git2::Repository::init_bare(git_repo_path).unwrap();
-    let mut tree_builder = git_repo.treebuilder(None).unwrap();
-    let file_oid = git_repo.blob(b"content").unwrap();
-    tree_builder
-        .insert("file", file_oid, git2::FileMode::Blob.into())
-        .unwrap();
-    test_env.jj_cmd_ok(
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "-rroot()", "foo"]);
+
+    // Existing branches are skipped and left alone
+    let (stdout, stderr) = test_env.jj_cmd_ok(
         &repo_path,
-        &["git", "remote", "add", "origin", "../git-repo"],
+        &["branch", "create", "--if-not-exists", "foo", "bar"],
     );
-
-    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=commit"]);
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "foo-1"]);
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "bar-2"]);
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "foo-3"]);
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "foo-4"]);
-    // Push to create remote-tracking branches
-    test_env.jj_cmd_ok(&repo_path, &["git", "push", "--all"]);
-
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 branch.
+    "###);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    @  bar-2 foo-1 foo-3 foo-4 6fbf398c2d59
-    ◉   000000000000
+    @  bar 230dd059e1b0
+    ◉  foo 000000000000
     "###);
-    let (stdout, stderr) =
-        test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "--glob", "foo-[1-3]"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "create", "--if-not-exists", "foo", "bar"],
+    );
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
-    Deleted 2 branches.
+    Nothing changed.
     "###);
-    test_env.jj_cmd_ok(&repo_path, &["undo"]);
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "glob:foo-[1-3]"]);
+}
+
+#[test]
+fn test_branch_create_resolve_at_op() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "abandoned"]);
+    test_env.jj_cmd_ok(&repo_path, &["abandon"]);
+
+    // The abandoned commit can't be resolved at the current operation
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["branch", "create", "-r", "description(abandoned)", "foo"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revset "description(abandoned)" didn't resolve to any revisions
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "branch",
+            "create",
+            "--resolve-at-op",
+            "@-",
+            "-r",
+            "description(abandoned)",
+            "foo",
+        ],
+    );
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    Deleted 2 branches.
+    Created 1 branch.
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    @  bar-2 foo-1@origin foo-3@origin foo-4 6fbf398c2d59
+    @   fcdbbd731496
+    │ ◉  foo 8651d52283ef
+    ├─╯
     ◉   000000000000
     "###);
-
-    // We get an error if none of the globs match live branches. Unlike `jj branch
-    // forget`, it's not allowed to delete already deleted branches.
-    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "delete", "--glob=foo-[1-3]"]);
+    // The revision must exist at the given operation
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "branch",
+            "create",
+            "--resolve-at-op",
+            "@---",
+            "-r",
+            "description(abandoned)",
+            "bar",
+        ],
+    );
     insta::assert_snapshot!(stderr, @r###"
-    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
-    Error: No matching branches for patterns: foo-[1-3]
+    Error: Revset "description(abandoned)" didn't resolve to any revisions
+    "###);
+    // The branch is created on top of the current operation
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-Tdescription ++ \"\\n\""],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    create branch foo pointing to commit 8651d52283efc7c88d89107015011da3f572f3a4
+    abandon commit 8651d52283efc7c88d89107015011da3f572f3a4
+    describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    add workspace 'default'
+    initialize repo
     "###);
+}
 
-    // Deleting a branch via both explicit name and glob pattern, or with
-    // multiple glob patterns, shouldn't produce an error.
-    let (stdout, stderr) = test_env.jj_cmd_ok(
+#[test]
+fn test_branch_set_per_branch_revisions() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(
         &repo_path,
-        &["branch", "delete", "foo-4", "--glob", "foo-*", "glob:foo-*"],
+        &["branch", "create", "-rroot()", "foo", "bar", "baz"],
     );
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "set", "foo=@-", "bar", "baz=@"]);
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
+    warning: Updating multiple branches (3): foo, bar, baz
+    Updated 3 branches.
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    @  bar-2 foo-1@origin foo-3@origin foo-4@origin 6fbf398c2d59
+    @  bar baz 4db490c88528
+    ◉  foo 230dd059e1b0
     ◉   000000000000
     "###);
-
-    // The deleted branches are still there
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    bar-2: qpvuntsm 6fbf398c (empty) commit
-      @origin: qpvuntsm 6fbf398c (empty) commit
-    foo-1 (deleted)
-      @origin: qpvuntsm 6fbf398c (empty) commit
-      (this branch will be *deleted permanently* on the remote on the
-       next `jj git push`. Use `jj branch forget` to prevent this)
-    foo-3 (deleted)
-      @origin: qpvuntsm 6fbf398c (empty) commit
-      (this branch will be *deleted permanently* on the remote on the
-       next `jj git push`. Use `jj branch forget` to prevent this)
-    foo-4 (deleted)
-      @origin: qpvuntsm 6fbf398c (empty) commit
-      (this branch will be *deleted permanently* on the remote on the
-       next `jj git push`. Use `jj branch forget` to prevent this)
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-l1", "-Tdescription"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    point branch foo to commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22, branch bar to commit 4db490c88528133d579540b6900b8098f0c17701, branch baz to commit 4db490c88528133d579540b6900b8098f0c17701
     "###);
 
-    // Malformed glob
-    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "delete", "glob:foo-[1-3"]);
+    // The fast-forward check applies to each branch
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "set", "foo=@", "bar=@-"]);
     insta::assert_snapshot!(stderr, @r###"
-    error: invalid value 'glob:foo-[1-3' for '[NAMES]...': Pattern syntax error near position 4: invalid range pattern
-
-    For more information, try '--help'.
+    warning: Updating multiple branches (2): foo, bar
+    Error: Refusing to move branch backwards or sideways:
+      bar: 4db490c88528 -> 230dd059e1b0
+    Hint: Use --allow-backwards to allow it.
+    "###);
+    // Also when moving to the same revision. Each offending branch is listed.
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["branch", "set", "-r@-", "foo", "bar", "baz"]);
+    insta::assert_snapshot!(stderr, @r###"
+    warning: Updating multiple branches (3): foo, bar, baz
+    Error: Refusing to move branch backwards or sideways:
+      bar: 4db490c88528 -> 230dd059e1b0
+      baz: 4db490c88528 -> 230dd059e1b0
+    Hint: Use --allow-backwards to allow it.
     "###);
 
-    // Unknown pattern kind
-    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "forget", "whatever:branch"]);
+    // Branches already pointing to the target are left alone
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "set", "bar", "baz"]);
+    insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
-    error: invalid value 'whatever:branch' for '[NAMES]...': Invalid string pattern kind "whatever"
-
-    For more information, try '--help'.
+    warning: Updating multiple branches (2): bar, baz
+    Nothing changed.
+    "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "set", "foo", "bar"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    warning: Updating multiple branches (2): foo, bar
+    Updated 1 branch.
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-l1", "-Tdescription"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    point branch foo to commit 4db490c88528133d579540b6900b8098f0c17701
     "###);
 }
 
 #[test]
-fn test_branch_delete_export() {
+fn test_branch_set_allow_new() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
-
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo"]);
     test_env.jj_cmd_ok(&repo_path, &["new"]);
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "foo"]);
-    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
 
-    test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "foo"]);
-    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--all"]);
-    insta::assert_snapshot!(stdout, @r###"
-    foo (deleted)
-      @git: rlvkpnrz 65b6b74e (empty) (no description set)
-      (this branch will be deleted from the underlying Git repo on the next `jj git export`)
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "set", "foo", "bar"]);
+    insta::assert_snapshot!(stderr, @r###"
+    warning: Updating multiple branches (2): foo, bar
+    Error: No such branch: bar
+    Hint: Use `jj branch create` or --allow-new to create it.
     "###);
 
-    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
-    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--all"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "--allow-new", "foo", "bar"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  bar foo 4db490c88528
+    ◉   230dd059e1b0
+    ◉   000000000000
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-l1", "-Tdescription"],
+    );
     insta::assert_snapshot!(stdout, @r###"
+    create branch bar pointing to commit 4db490c88528133d579540b6900b8098f0c17701, point branch foo to commit 4db490c88528133d579540b6900b8098f0c17701
     "###);
 }
 
 #[test]
-fn test_branch_forget_export() {
-    let test_env = TestEnvironment::default();
+fn test_branch_describe() {
+    let mut test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
+    let edit_script = test_env.set_up_fake_editor();
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo", "bar"]);
 
-    test_env.jj_cmd_ok(&repo_path, &["new"]);
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "foo"]);
-    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--all"]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "describe", "baz", "-m=x"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such branch: baz
+    "###);
+
+    // Set a description with --message
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "describe", "foo", "-m=Release prep"],
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list"]);
     insta::assert_snapshot!(stdout, @r###"
-    foo: rlvkpnrz 65b6b74e (empty) (no description set)
+    bar: qpvuntsm 230dd059 (empty) (no description set)
+    foo: qpvuntsm 230dd059 (empty) (no description set)
+      Release prep
     "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-l1", "-Tdescription"],
+    );
+    insta::assert_snapshot!(stdout, @"describe branch foo");
 
-    // Exporting the branch to git creates a local-git tracking branch
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
-    insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @"");
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "foo"]);
-    insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @"");
-    // Forgetting a branch deletes local and remote-tracking branches including
-    // the corresponding git-tracking branch.
-    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--all"]);
-    insta::assert_snapshot!(stdout, @"");
-    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-r=foo", "--no-graph"]);
+    // The editor is initialized with the current description
+    std::fs::write(&edit_script, "dump editor0").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "describe", "foo"]);
     insta::assert_snapshot!(stderr, @r###"
-    Error: Revision "foo" doesn't exist
+    Nothing changed.
     "###);
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor0")).unwrap(), @r###"
+    Release prep
 
-    // `jj git export` will delete the branch from git. In a colocated repo,
-    // this will happen automatically immediately after a `jj branch forget`.
-    // This is demonstrated in `test_git_colocated_branch_forget` in
-    // test_git_colocated.rs
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
-    insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @"");
-    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--all"]);
-    insta::assert_snapshot!(stdout, @"");
+    JJ: Lines starting with "JJ: " (like this one) will be removed.
+    "###);
+
+    // Set a multi-line description in the editor
+    std::fs::write(&edit_script, "write\nfirst line\nsecond line\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["branch", "describe", "bar"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    bar: qpvuntsm 230dd059 (empty) (no description set)
+      first line
+      second line
+    foo: qpvuntsm 230dd059 (empty) (no description set)
+      Release prep
+    "###);
+
+    // An empty description removes it
+    test_env.jj_cmd_ok(&repo_path, &["branch", "describe", "foo", "-m", ""]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    bar: qpvuntsm 230dd059 (empty) (no description set)
+      first line
+      second line
+    foo: qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+
+    // The description is removed along with the branch
+    test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "bar"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "bar"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    bar: qpvuntsm 230dd059 (empty) (no description set)
+    foo: qpvuntsm 230dd059 (empty) (no description set)
+    "###);
 }
 
 #[test]
-fn test_branch_forget_fetched_branch() {
-    // Much of this test is borrowed from `test_git_fetch_remote_only_branch` in
-    // test_git_fetch.rs
-
-    // Set up a git repo with a branch and a jj repo that has it as a remote.
+fn test_branch_set_allow_new_backwards() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
-    let git_repo_path = test_env.env_root().join("git-repo");
-    let git_repo = git2::Repository::init_bare(git_repo_path).unwrap();
-    let signature =
-        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
-    let mut tree_builder = git_repo.treebuilder(None).unwrap();
-    let file_oid = git_repo.blob(b"content").unwrap();
-    tree_builder
-        .insert("file", file_oid, git2::FileMode::Blob.into())
-        .unwrap();
-    let tree_oid = tree_builder.write().unwrap();
-    let tree = git_repo.find_tree(tree_oid).unwrap();
-    test_env.jj_cmd_ok(
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=second"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo"]);
+
+    // Only the existing branch is refused to move backwards
+    let stderr = test_env.jj_cmd_failure(
         &repo_path,
-        &["git", "remote", "add", "origin", "../git-repo"],
+        &["branch", "set", "--allow-new", "foo", "bar", "-r@-"],
     );
-    // Create a commit and a branch in the git repo
-    let first_git_repo_commit = git_repo
-        .commit(
-            Some("refs/heads/feature1"),
-            &signature,
-            &signature,
-            "message",
-            &tree,
-            &[],
-        )
-        .unwrap();
+    insta::assert_snapshot!(stderr, @r###"
+    warning: Updating multiple branches (2): foo, bar
+    Error: Refusing to move branch backwards or sideways:
+      foo: bc28fa9df1e5 -> 69542c1984c1
+    Hint: Use --allow-backwards to allow it.
+    "###);
 
-    // Fetch normally
-    test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: mzyxwzks 9f01a0e0 message
-      @origin: mzyxwzks 9f01a0e0 message
+    // A new branch can be created at any revision
+    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "--allow-new", "bar", "-r@-"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  foo bc28fa9df1e5
+    ◉  bar 69542c1984c1
+    ◉   000000000000
     "###);
+}
 
-    // TEST 1: with export-import
-    // Forget the branch
-    test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
+#[test]
+fn test_branch_set_to_remote() {
+    let test_env = TestEnvironment::default();
 
-    // At this point `jj git export && jj git import` does *not* recreate the
-    // branch. This behavior is important in colocated repos, as otherwise a
-    // forgotten branch would be immediately resurrected.
-    //
-    // Technically, this is because `jj branch forget` preserved
-    // the ref in jj view's `git_refs` tracking the local git repo's remote-tracking
-    // branch.
-    // TODO: Show that jj git push is also a no-op
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
-    insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @"");
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
-    insta::assert_snapshot!(stdout, @"");
+    // Initialize remote refs
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "remote", "--git"]);
+    let remote_path = test_env.env_root().join("remote");
+    test_env.jj_cmd_ok(&remote_path, &["describe", "-m", "foo"]);
+    test_env.jj_cmd_ok(&remote_path, &["branch", "create", "foo"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    let mut remote_git_path = remote_path;
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+    );
+    let local_path = test_env.env_root().join("local");
+    test_env.jj_cmd_ok(&local_path, &["new", "foo", "-m", "bad"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "set", "foo"]);
+
+    // Resetting to the remote is usually a backwards move
+    let stderr =
+        test_env.jj_cmd_failure(&local_path, &["branch", "set", "foo", "--to-remote=origin"]);
     insta::assert_snapshot!(stderr, @r###"
-    Nothing changed.
+    Error: Refusing to move branch backwards or sideways:
+      foo: 68f36af0fae3 -> b87601efe913
+    Hint: Use --allow-backwards to allow it.
+    "###);
+    test_env.jj_cmd_ok(
+        &local_path,
+        &["branch", "set", "foo", "--to-remote=origin", "-B"],
+    );
+    insta::assert_snapshot!(get_branch_output(&test_env, &local_path), @r###"
+    foo: zonklpmq b87601ef (empty) foo
+      @origin (in sync): zonklpmq b87601ef (empty) foo
     "###);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
 
-    // We can fetch feature1 again.
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
-    insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @"");
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: mzyxwzks 9f01a0e0 message
-      @origin: mzyxwzks 9f01a0e0 message
+    // The remote target can also be specified as a revision
+    test_env.jj_cmd_ok(&local_path, &["branch", "set", "foo", "-r@"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "set", "foo", "-rfoo@origin", "-B"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &local_path), @r###"
+    foo: zonklpmq b87601ef (empty) foo
+      @origin (in sync): zonklpmq b87601ef (empty) foo
+    "###);
+    let stderr = test_env.jj_cmd_failure(&local_path, &["branch", "set", "foo", "-rfoo@upstream"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revision "foo@upstream" doesn't exist
+    Hint: Did you mean "foo", "foo@origin"?
     "###);
 
-    // TEST 2: No export/import (otherwise the same as test 1)
-    test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
-    // Fetch works even without the export-import
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
-    insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @"");
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: mzyxwzks 9f01a0e0 message
-      @origin: mzyxwzks 9f01a0e0 message
+    let stderr = test_env.jj_cmd_failure(
+        &local_path,
+        &["branch", "set", "bar", "--to-remote=origin", "--allow-new"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such remote branch: bar@origin
+    Hint: Use `jj git fetch` to update the remote branches.
     "###);
 
-    // TEST 3: fetch branch that was moved & forgotten
+    let stderr = test_env.jj_cmd_cli_error(
+        &local_path,
+        &["branch", "set", "foo", "--to-remote=origin", "-r@"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--to-remote <REMOTE>' cannot be used with '--revision <REVISION>'
 
-    // Move the branch in the git repo.
-    git_repo
-        .commit(
-            Some("refs/heads/feature1"),
-            &signature,
-            &signature,
-            "another message",
-            &tree,
-            &[&git_repo.find_commit(first_git_repo_commit).unwrap()],
-        )
-        .unwrap();
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
-    insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @"");
+    Usage: jj branch set --to-remote <REMOTE> <NAMES>...
 
-    // Fetching a moved branch does not create a conflict
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
-    insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @"");
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: ooosovrs 38aefb17 (empty) another message
-      @origin: ooosovrs 38aefb17 (empty) another message
+    For more information, try '--help'.
     "###);
 }
 
 #[test]
-fn test_branch_forget_deleted_or_nonexistent_branch() {
-    // Much of this test is borrowed from `test_git_fetch_remote_only_branch` in
-    // test_git_fetch.rs
+fn test_branch_set_diverged_from_remote() {
+    let test_env = TestEnvironment::default();
 
-    // ======== Beginning of test setup ========
-    // Set up a git repo with a branch and a jj repo that has it as a remote.
+    // Initialize remote refs
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "remote", "--git"]);
+    let remote_path = test_env.env_root().join("remote");
+    test_env.jj_cmd_ok(&remote_path, &["describe", "-m", "foo"]);
+    test_env.jj_cmd_ok(&remote_path, &["branch", "create", "foo"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    let mut remote_git_path = remote_path;
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+    );
+    let local_path = test_env.env_root().join("local");
+
+    // Moving forward doesn't diverge from the remote
+    test_env.jj_cmd_ok(&local_path, &["new", "foo", "-m", "forward"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["branch", "set", "foo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Updated 1 branch.
+    "###);
+
+    // Moving sideways does
+    test_env.jj_cmd_ok(&local_path, &["new", "root()", "-m", "sideways"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["branch", "set", "foo", "-B"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Updated 1 branch.
+    Hint: Branch foo has diverged from foo@origin. The next `jj git push` will force-push it.
+    "###);
+
+    // So does moving backwards
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&local_path, &["branch", "set", "foo", "-rroot()", "-B"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Updated 1 branch.
+    Hint: Branch foo has diverged from foo@origin. The next `jj git push` will force-push it.
+    "###);
+
+    // Untracked remote branches are ignored
+    test_env.jj_cmd_ok(&local_path, &["branch", "untrack", "foo@origin"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["branch", "set", "foo", "-B"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Updated 1 branch.
+    "###);
+}
+
+#[test]
+fn test_branch_set_to_parent() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
-    let git_repo_path = test_env.env_root().join("git-repo");
-    let git_repo = git2::Repository::init_bare(git_repo_path).unwrap();
-    let signature =
-        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
-    let mut tree_builder = git_repo.treebuilder(None).unwrap();
-    let file_oid = git_repo.blob(b"content").unwrap();
-    tree_builder
-        .insert("file", file_oid, git2::FileMode::Blob.into())
-        .unwrap();
-    let tree_oid = tree_builder.write().unwrap();
-    let tree = git_repo.find_tree(tree_oid).unwrap();
-    test_env.jj_cmd_ok(
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo", "bar"]);
+
+    // Moving to the parent doesn't require --allow-backwards
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "set", "--to-parent", "foo"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Updated 1 branch.
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  bar bc28fa9df1e5
+    ◉  foo 69542c1984c1
+    ◉   000000000000
+    "###);
+
+    // Other branches can still be given their own target
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
         &repo_path,
-        &["git", "remote", "add", "origin", "../git-repo"],
+        &["branch", "set", "--to-parent", "foo", "bar=@"],
     );
-    // Create a commit and a branch in the git repo
-    git_repo
-        .commit(
-            Some("refs/heads/feature1"),
-            &signature,
-            &signature,
-            "message",
-            &tree,
-            &[],
-        )
-        .unwrap();
-
-    // Fetch and then delete the branch
-    test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
-    test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "feature1"]);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1 (deleted)
-      @origin: mzyxwzks 9f01a0e0 message
-      (this branch will be *deleted permanently* on the remote on the
-       next `jj git push`. Use `jj branch forget` to prevent this)
+    insta::assert_snapshot!(stderr, @r###"
+    warning: Updating multiple branches (2): foo, bar
+    Updated 1 branch.
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  bar bc28fa9df1e5
+    ◉   69542c1984c1
+    ◉  foo 000000000000
     "###);
 
-    // ============ End of test setup ============
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "set", "--to-parent", "foo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Branch foo points to the root commit, which has no parent
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "set", "--to-parent", "baz"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such branch: baz
+    "###);
 
-    // We can forget a deleted branch
-    test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
+    // A merge commit has no single parent to move to
+    test_env.jj_cmd_ok(&repo_path, &["new", "@", "@-", "-m", "merge"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "bar"]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "set", "--to-parent", "bar"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Branch bar points to a merge commit with 2 parents
+    Hint: Use `jj branch set bar -r REVISION` to choose one of them.
+    "###);
 
-    // Can't forget a non-existent branch
-    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "forget", "i_do_not_exist"]);
+    let stderr =
+        test_env.jj_cmd_cli_error(&repo_path, &["branch", "set", "--to-parent", "-r@", "bar"]);
     insta::assert_snapshot!(stderr, @r###"
-    Error: No such branch: i_do_not_exist
+    error: the argument '--to-parent' cannot be used with '--revision <REVISION>'
+
+    Usage: jj branch set --to-parent <NAMES>...
+
+    For more information, try '--help'.
     "###);
 }
 
 #[test]
-fn test_branch_track_untrack() {
+fn test_branch_set_conflicted() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    // Set up remote
-    let git_repo_path = test_env.env_root().join("git-repo");
-    let git_repo = git2::Repository::init(git_repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "left"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "right"]);
     test_env.jj_cmd_ok(
         &repo_path,
-        &["git", "remote", "add", "origin", "../git-repo"],
+        &["branch", "create", "foo", "-rdescription(left)"],
     );
-    let create_remote_commit = |message: &str, data: &[u8], ref_names: &[&str]| {
-        let signature =
-            git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0))
-                .unwrap();
-        let mut tree_builder = git_repo.treebuilder(None).unwrap();
-        let file_oid = git_repo.blob(data).unwrap();
-        tree_builder
-            .insert("file", file_oid, git2::FileMode::Blob.into())
-            .unwrap();
-        let tree_oid = tree_builder.write().unwrap();
-        let tree = git_repo.find_tree(tree_oid).unwrap();
-        // Create commit and branches in the remote
-        let git_commit_oid = git_repo
-            .commit(None, &signature, &signature, message, &tree, &[])
-            .unwrap();
-        for name in ref_names {
-            git_repo.reference(name, git_commit_oid, true, "").unwrap();
-        }
-    };
-
-    // Fetch new commit without auto tracking. No local branches should be
-    // created.
-    create_remote_commit(
-        "commit 1",
-        b"content 1",
+    test_env.jj_cmd_ok(
+        &repo_path,
         &[
-            "refs/heads/main",
-            "refs/heads/feature1",
-            "refs/heads/feature2",
+            "branch",
+            "create",
+            "foo",
+            "-rdescription(right)",
+            "--at-op=@-",
         ],
     );
-    test_env.add_config("git.auto-local-branch = false");
-    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
-    insta::assert_snapshot!(stderr, @"");
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1@origin: sptzoqmo 7b33f629 commit 1
-    feature2@origin: sptzoqmo 7b33f629 commit 1
-    main@origin: sptzoqmo 7b33f629 commit 1
+    // Resolve the concurrent operations
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(left)", "-m", "child"]);
+
+    // Moving to a descendant of only one of the adds isn't a fast-forward
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "set", "foo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Refusing to move branch backwards or sideways:
+      foo: conflicted (444b77e99d43, 1694f2ddf8ec) -> 72aed1e2132c
+    Hint: Use --allow-backwards to allow it.
     "###);
-    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    ◉  feature1@origin feature2@origin main@origin 7b33f6295eda
-    │ @   230dd059e1b0
-    ├─╯
-    ◉   000000000000
+
+    // The template lists all the targets of the conflicted branch
+    let template = r#"name ++ ": " ++ target.map(|c| c.description().first_line())"#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    foo: left right
     "###);
 
-    // Track new branch. Local branch should be created.
+    // Moving to a descendant of all the adds is
     test_env.jj_cmd_ok(
         &repo_path,
-        &["branch", "track", "feature1@origin", "main@origin"],
+        &[
+            "new",
+            "description(left)",
+            "description(right)",
+            "-m",
+            "merge",
+        ],
     );
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: sptzoqmo 7b33f629 commit 1
-      @origin: sptzoqmo 7b33f629 commit 1
-    feature2@origin: sptzoqmo 7b33f629 commit 1
-    main: sptzoqmo 7b33f629 commit 1
-      @origin: sptzoqmo 7b33f629 commit 1
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "set", "foo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Updated 1 branch.
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @    foo 9ee46f50a667
+    ├─╮
+    │ ◉   1694f2ddf8ec
+    │ │ ◉   72aed1e2132c
+    ├───╯
+    ◉ │   444b77e99d43
+    ├─╯
+    ◉   000000000000
     "###);
+}
 
-    // Track existing branch. Local branch should result in conflict.
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "feature2"]);
-    test_env.jj_cmd_ok(&repo_path, &["branch", "track", "feature2@origin"]);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: sptzoqmo 7b33f629 commit 1
-      @origin: sptzoqmo 7b33f629 commit 1
-    feature2 (conflicted):
-      + qpvuntsm 230dd059 (empty) (no description set)
-      + sptzoqmo 7b33f629 commit 1
-      @origin (behind by 1 commits): sptzoqmo 7b33f629 commit 1
-    main: sptzoqmo 7b33f629 commit 1
-      @origin: sptzoqmo 7b33f629 commit 1
+#[test]
+fn test_branch_move() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo", "bar"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "third"]);
+
+    // Moves to the tip of the descendants by default
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "move", "foo"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Moved branch foo to zsuskuln 90008468 foo | (empty) third
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  foo 90008468adbf
+    ◉   7e54d57844a7
+    ◉  bar 230dd059e1b0
+    ◉   000000000000
     "###);
 
-    // Untrack existing and locally-deleted branches. Branch targets should be
-    // unchanged
-    test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "feature2"]);
-    test_env.jj_cmd_ok(
-        &repo_path,
-        &["branch", "untrack", "feature1@origin", "feature2@origin"],
-    );
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: sptzoqmo 7b33f629 commit 1
-    feature1@origin: sptzoqmo 7b33f629 commit 1
-    feature2@origin: sptzoqmo 7b33f629 commit 1
-    main: sptzoqmo 7b33f629 commit 1
-      @origin: sptzoqmo 7b33f629 commit 1
+    // Can't pick a tip if the descendants have diverged
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "sibling", "@-"]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "move", "bar"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Branch bar has diverged descendants
+    Hint: Use --to to specify which revision to move it to.
     "###);
+
+    // Explicit destination
+    test_env.jj_cmd_ok(&repo_path, &["branch", "move", "bar", "--to", "@-"]);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    ◉  feature1 feature1@origin feature2@origin main 7b33f6295eda
-    │ @   230dd059e1b0
+    @   3f51ed02826f
+    │ ◉  foo 90008468adbf
     ├─╯
+    ◉  bar 7e54d57844a7
+    ◉   230dd059e1b0
     ◉   000000000000
     "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "move", "foo", "--to", "@"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Refusing to move branch backwards or sideways:
+      foo: 90008468adbf -> 3f51ed02826f
+    Hint: Use --allow-backwards to allow it.
+    "###);
 
-    // Fetch new commit. Only tracking branch "main" should be merged.
-    create_remote_commit(
-        "commit 2",
-        b"content 2",
-        &[
-            "refs/heads/main",
-            "refs/heads/feature1",
-            "refs/heads/feature2",
-        ],
-    );
-    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
-    insta::assert_snapshot!(stderr, @"");
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: sptzoqmo 7b33f629 commit 1
-    feature1@origin: mmqqkyyt 40dabdaf commit 2
-    feature2@origin: mmqqkyyt 40dabdaf commit 2
-    main: mmqqkyyt 40dabdaf commit 2
-      @origin: mmqqkyyt 40dabdaf commit 2
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "move", "baz"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such branch: baz
+    "###);
+}
+
+#[test]
+fn test_branch_move_from() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo", "bar"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "baz"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "third"]);
+
+    // Branches pointing to @- are moved by default
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "move"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Moved branch baz to mzvwutvl db4ea0c5 baz | (empty) third
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "move"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No branches point to commit 7e54d57844a7
+    "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "move", "--from", "@--"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Moved branch bar to mzvwutvl db4ea0c5 bar baz foo | (empty) third
+    Moved branch foo to mzvwutvl db4ea0c5 bar baz foo | (empty) third
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    ◉  feature1@origin feature2@origin main 40dabdaf4abe
-    │ ◉  feature1 7b33f6295eda
-    ├─╯
-    │ @   230dd059e1b0
-    ├─╯
+    @  bar baz foo db4ea0c52112
+    ◉   7e54d57844a7
+    ◉   230dd059e1b0
     ◉   000000000000
     "###);
 
-    // Fetch new commit with auto tracking. Tracking branch "main" and new
-    // branch "feature3" should be merged.
-    create_remote_commit(
-        "commit 3",
-        b"content 3",
+    // Conflicted branches aren't moved unless requested
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "set", "foo", "-r@-", "--allow-backwards"],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
         &[
-            "refs/heads/main",
-            "refs/heads/feature1",
-            "refs/heads/feature2",
-            "refs/heads/feature3",
+            "branch",
+            "set",
+            "foo",
+            "-r@--",
+            "--allow-backwards",
+            "--at-op=@-",
         ],
     );
-    test_env.add_config("git.auto-local-branch = true");
-    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "move", "foo"]);
     insta::assert_snapshot!(stderr, @r###"
-    Abandoned 1 commits that are no longer reachable.
+    Concurrent modification detected, resolving automatically.
+    Error: Branch foo is conflicted
+    Hint: Use --include-conflicted to move it anyway.
     "###);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: sptzoqmo 7b33f629 commit 1
-    feature1@origin: wwnpyzpo 3f0f86fa commit 3
-    feature2@origin: wwnpyzpo 3f0f86fa commit 3
-    feature3: wwnpyzpo 3f0f86fa commit 3
-      @origin: wwnpyzpo 3f0f86fa commit 3
-    main: wwnpyzpo 3f0f86fa commit 3
-      @origin: wwnpyzpo 3f0f86fa commit 3
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "move", "--from", "@--", "--include-conflicted"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Moved branch foo to mzvwutvl db4ea0c5 bar baz foo | (empty) third
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
-    ◉  feature1@origin feature2@origin feature3 main 3f0f86fa0e57
-    │ ◉  feature1 7b33f6295eda
-    ├─╯
-    │ @   230dd059e1b0
-    ├─╯
+    @  bar baz foo db4ea0c52112
+    ◉   7e54d57844a7
+    ◉   230dd059e1b0
     ◉   000000000000
     "###);
 }
 
 #[test]
-fn test_branch_track_untrack_patterns() {
+fn test_branch_at_root() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    // Set up remote
-    let git_repo_path = test_env.env_root().join("git-repo");
-    let git_repo = git2::Repository::init(git_repo_path).unwrap();
-    test_env.jj_cmd_ok(
-        &repo_path,
-        &["git", "remote", "add", "origin", "../git-repo"],
-    );
-
-    // Create remote commit
-    let signature =
-        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
-    let mut tree_builder = git_repo.treebuilder(None).unwrap();
-    let file_oid = git_repo.blob(b"content").unwrap();
-    tree_builder
-        .insert("file", file_oid, git2::FileMode::Blob.into())
-        .unwrap();
-    let tree_oid = tree_builder.write().unwrap();
-    let tree = git_repo.find_tree(tree_oid).unwrap();
-    // Create commit and branches in the remote
-    let git_commit_oid = git_repo
-        .commit(None, &signature, &signature, "commit", &tree, &[])
-        .unwrap();
-    for name in ["refs/heads/feature1", "refs/heads/feature2"] {
-        git_repo.reference(name, git_commit_oid, true, "").unwrap();
-    }
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "create", "fred", "-r=root()"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 branch.
+    "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    Failed to export some branches:
+      fred
+    "###);
+}
 
-    // Fetch new commit without auto tracking
-    test_env.add_config("git.auto-local-branch = false");
-    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
-    insta::assert_snapshot!(stderr, @"");
+#[test]
+fn test_branch_empty_name() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
 
-    // Track local branch
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "main"]);
-    insta::assert_snapshot!(
-        test_env.jj_cmd_cli_error(&repo_path, &["branch", "track", "main"]), @r###"
-    error: invalid value 'main' for '<NAMES>...': remote branch must be specified in branch@remote form
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "create", ""]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: a value is required for '<NAMES>...' but none was supplied
 
     For more information, try '--help'.
     "###);
+}
 
-    // Track/untrack unknown branch
-    insta::assert_snapshot!(
-        test_env.jj_cmd_failure(&repo_path, &["branch", "track", "main@origin"]), @r###"
-    Error: No such remote branch: main@origin
+#[test]
+fn test_branch_forget_glob() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo-1"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "bar-2"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo-3"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo-4"]);
+
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  bar-2 foo-1 foo-3 foo-4 230dd059e1b0
+    ◉   000000000000
     "###);
-    insta::assert_snapshot!(
-        test_env.jj_cmd_failure(&repo_path, &["branch", "untrack", "main@origin"]), @r###"
-    Error: No such remote branch: main@origin
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "--glob", "foo-[1-3]"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
+    Forgot 2 branches.
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "forget", r"regex:^foo-[1-3]$"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 2 branches.
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+
+    // Dry run lists the matching branches without forgetting them
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "forget", "--dry-run", "glob:foo-[1-3]"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    foo-1: qpvuntsm 230dd059 bar-2 foo-1 foo-3 foo-4 | (empty) (no description set) -> (absent)
+    foo-3: qpvuntsm 230dd059 bar-2 foo-1 foo-3 foo-4 | (empty) (no description set) -> (absent)
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["branch", "forget", "--dry-run", "glob:baz*"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No matching branches for patterns: baz*
+    "###);
+
+    // Globs starting with `!` exclude the matching branches
+    let (stdout, _stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "forget", "--dry-run", "glob:foo-*", "glob:!foo-3"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    foo-1: qpvuntsm 230dd059 bar-2 foo-1 foo-3 foo-4 | (empty) (no description set) -> (absent)
+    foo-4: qpvuntsm 230dd059 bar-2 foo-1 foo-3 foo-4 | (empty) (no description set) -> (absent)
+    "###);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["branch", "forget", "glob:foo-[34]", "glob:!foo-*"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No branches to forget: all of the matching branches are excluded
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "forget", "--glob=!foo-3"]);
+    insta::assert_snapshot!(stderr, @r###"
+    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
+    Error: No branches to forget: all of the patterns are exclusions
+    Hint: Add patterns to select branches to forget, e.g. `'glob:wip/*' 'glob:!wip/keep-me'`.
+    "###);
+    // A branch starting with `!` can be forgotten by its exact name
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "!foo"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "!foo"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 1 branch.
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "glob:foo-[1-3]"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 2 branches.
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  bar-2 foo-4 230dd059e1b0
+    ◉   000000000000
+    "###);
+
+    // Forgetting a branch via both explicit name and glob pattern, or with
+    // multiple glob patterns, shouldn't produce an error.
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "forget", "foo-4", "--glob", "foo-*", "glob:foo-*"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
+    Forgot 1 branch.
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  bar-2 230dd059e1b0
+    ◉   000000000000
+    "###);
+
+    // Malformed glob
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "forget", "glob:foo-[1-3"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value 'glob:foo-[1-3' for '[NAMES]...': Pattern syntax error near position 4: invalid range pattern
+
+    For more information, try '--help'.
+    "###);
+
+    // Malformed regex
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "forget", "regex:foo-(1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value 'regex:foo-(1' for '[NAMES]...': regex parse error:
+        foo-(1
+            ^
+    error: unclosed group
+
+    For more information, try '--help'.
+    "###);
+
+    // We get an error if none of the globs match anything
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["branch", "forget", "glob:bar*", "glob:baz*", "--glob=boom*"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
+    Error: No matching branches for patterns: baz*, boom*
+    "###);
+}
+
+#[test]
+fn test_branch_dry_run() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo", "bar"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    let setup_log = get_log_output(&test_env, &repo_path);
+
+    // None of the dry runs change the repo
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "create", "--dry-run", "baz", "qux"]);
+    insta::assert_snapshot!(stdout, @r###"
+    baz: (absent) -> zsuskuln 8348d73c (empty) second
+    qux: (absent) -> zsuskuln 8348d73c (empty) second
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    warning: Creating multiple branches (2): baz, qux
+    "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "set", "--dry-run", "foo"]);
+    insta::assert_snapshot!(stdout, @r###"
+    foo: qpvuntsm 69542c19 bar foo | (empty) first -> zsuskuln 8348d73c (empty) second
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "set", "--dry-run", "--allow-new", "foo", "new=@-"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    foo: qpvuntsm 69542c19 bar foo | (empty) first -> zsuskuln 8348d73c (empty) second
+    new: (absent) -> qpvuntsm 69542c19 bar foo | (empty) first
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    warning: Updating multiple branches (2): foo, new
+    "###);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "--dry-run", "glob:*"]);
+    insta::assert_snapshot!(stdout, @r###"
+    bar: qpvuntsm 69542c19 bar foo | (empty) first -> (absent)
+    foo: qpvuntsm 69542c19 bar foo | (empty) first -> (absent)
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+    assert_eq!(get_log_output(&test_env, &repo_path), setup_log);
+
+    // Errors are still reported
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "create", "--dry-run", "foo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Branch already exists: foo
+    Hint: Use `jj branch set` to update it.
+    "###);
+}
+
+#[test]
+fn test_branch_delete_glob() {
+    // Set up a git repo with a branch and a jj repo that has it as a remote.
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git2::Repository::init_bare(git_repo_path).unwrap();
+    let mut tree_builder = git_repo.treebuilder(None).unwrap();
+    let file_oid = git_repo.blob(b"content").unwrap();
+    tree_builder
+        .insert("file", file_oid, git2::FileMode::Blob.into())
+        .unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=commit"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo-1"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "bar-2"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo-3"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo-4"]);
+    // Push to create remote-tracking branches
+    test_env.jj_cmd_ok(&repo_path, &["git", "push", "--all"]);
+
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  bar-2 foo-1 foo-3 foo-4 6fbf398c2d59
+    ◉   000000000000
+    "###);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "--glob", "foo-[1-3]"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
+    Deleted 2 branches.
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    // Deleting many branches requires confirmation
+    test_env.add_config("branches.confirm-threshold = 1");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "delete", "glob:foo-[1-3]"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Refusing to delete 2 branches without confirmation
+    Hint: Use --yes to confirm the change non-interactively.
+    "###);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "--yes", "glob:foo-[1-3]"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Deleted 2 branches.
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  bar-2 foo-1@origin foo-3@origin foo-4 6fbf398c2d59
+    ◉   000000000000
+    "###);
+
+    // We get an error if none of the globs match live branches. Unlike `jj branch
+    // forget`, it's not allowed to delete already deleted branches.
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "delete", "--glob=foo-[1-3]"]);
+    insta::assert_snapshot!(stderr, @r###"
+    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
+    Error: No matching branches for patterns: foo-[1-3]
+    "###);
+
+    // Deleting a branch via both explicit name and glob pattern, or with
+    // multiple glob patterns, shouldn't produce an error.
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "delete", "foo-4", "--glob", "foo-*", "glob:foo-*"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    --glob has been deprecated. Please prefix the pattern with `glob:` instead.
+    Deleted 1 branch.
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  bar-2 foo-1@origin foo-3@origin foo-4@origin 6fbf398c2d59
+    ◉   000000000000
+    "###);
+
+    // The deleted branches are still there
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    bar-2: qpvuntsm 6fbf398c (empty) commit
+      @origin (in sync): qpvuntsm 6fbf398c (empty) commit
+    foo-1* (deleted)
+      @origin: qpvuntsm 6fbf398c (empty) commit
+      (this branch will be *deleted permanently* on the remote on the
+       next `jj git push`. Use `jj branch forget` to prevent this)
+    foo-3* (deleted)
+      @origin: qpvuntsm 6fbf398c (empty) commit
+      (this branch will be *deleted permanently* on the remote on the
+       next `jj git push`. Use `jj branch forget` to prevent this)
+    foo-4* (deleted)
+      @origin: qpvuntsm 6fbf398c (empty) commit
+      (this branch will be *deleted permanently* on the remote on the
+       next `jj git push`. Use `jj branch forget` to prevent this)
+    "###);
+
+    // Malformed glob
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "delete", "glob:foo-[1-3"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value 'glob:foo-[1-3' for '[NAMES]...': Pattern syntax error near position 4: invalid range pattern
+
+    For more information, try '--help'.
+    "###);
+
+    // Unknown pattern kind
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "forget", "whatever:branch"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value 'whatever:branch' for '[NAMES]...': Invalid string pattern kind "whatever"
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_branch_delete_retain_remote() {
+    let test_env = TestEnvironment::default();
+
+    // Initialize remote refs
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "remote", "--git"]);
+    let remote_path = test_env.env_root().join("remote");
+    for branch in ["foo", "bar"] {
+        test_env.jj_cmd_ok(&remote_path, &["new", "root()", "-m", branch]);
+        test_env.jj_cmd_ok(&remote_path, &["branch", "create", branch]);
+    }
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    let mut remote_git_path = remote_path;
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+    );
+    let local_path = test_env.env_root().join("local");
+
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&local_path, &["branch", "delete", "--retain-remote", "foo"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Deleted 1 branch.
+    "###);
+    test_env.jj_cmd_ok(&local_path, &["branch", "delete", "bar"]);
+    // The remote branch of foo is kept, but no longer tracked
+    insta::assert_snapshot!(get_branch_output(&test_env, &local_path), @r###"
+    bar* (deleted)
+      @origin: kywzptru b0955bb1 (empty) bar
+      (this branch will be *deleted permanently* on the remote on the
+       next `jj git push`. Use `jj branch forget` to prevent this)
+    foo@origin: vzunsxnz 280394c7 (empty) foo
+    "###);
+
+    // Only the remote branch of bar is deleted on push
+    let (stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["git", "push", "--deleted"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Delete branch bar from b0955bb186c8
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &local_path), @r###"
+    foo@origin: vzunsxnz 280394c7 (empty) foo
+    "###);
+}
+
+#[test]
+fn test_branch_delete_remote() {
+    let test_env = TestEnvironment::default();
+
+    // Initialize remote refs
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "remote", "--git"]);
+    let remote_path = test_env.env_root().join("remote");
+    for branch in ["foo", "bar"] {
+        test_env.jj_cmd_ok(&remote_path, &["new", "root()", "-m", branch]);
+        test_env.jj_cmd_ok(&remote_path, &["branch", "create", branch]);
+    }
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    let mut remote_git_path = remote_path;
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+    );
+    let local_path = test_env.env_root().join("local");
+    test_env.jj_cmd_ok(
+        &local_path,
+        &[
+            "git",
+            "remote",
+            "add",
+            "upstream",
+            remote_git_path.to_str().unwrap(),
+        ],
+    );
+    test_env.jj_cmd_ok(&local_path, &["git", "fetch", "--remote", "upstream"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "track", "foo@upstream"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "untrack", "bar@upstream"]);
+
+    // The remote branch must exist and be tracked
+    let stderr = test_env.jj_cmd_failure(&local_path, &["branch", "delete", "baz@origin"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such remote branch: baz@origin
+    "###);
+    let stderr = test_env.jj_cmd_failure(&local_path, &["branch", "delete", "bar@upstream"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Cannot delete non-tracking remote branch bar@upstream
+    Hint: Run `jj branch track bar@upstream` to track it first.
+    "###);
+    let stderr = test_env.jj_cmd_failure(&local_path, &["branch", "delete", "foo@git"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Git-tracking branch cannot be deleted separately: foo@git
+    "###);
+    let stderr = test_env.jj_cmd_failure(
+        &local_path,
+        &["branch", "delete", "--retain-remote", "foo@origin"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: --retain-remote cannot be used with remote branch foo@origin
+    "###);
+
+    // Local and remote branches can be deleted together
+    let stdout = test_env.jj_cmd_success(
+        &local_path,
+        &["branch", "delete", "--dry-run", "foo@origin", "bar"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    bar: kywzptru b0955bb1 bar bar@upstream | (empty) bar -> (absent)
+    foo@origin: vzunsxnz 280394c7 foo | (empty) foo -> (absent)
+    "###);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&local_path, &["branch", "delete", "foo@origin", "bar"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Deleted 2 branches.
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &local_path,
+        &["op", "log", "--no-graph", "-l1", "-T", "description"],
+    );
+    insta::assert_snapshot!(stdout, @"delete branches bar, foo@origin");
+    // The local branch of foo is kept
+    insta::assert_snapshot!(get_branch_output(&test_env, &local_path), @r###"
+    bar* (deleted)
+      @origin: kywzptru b0955bb1 (empty) bar
+      (this branch will be *deleted permanently* on the remote on the
+       next `jj git push`. Use `jj branch forget` to prevent this)
+    bar@upstream: kywzptru b0955bb1 (empty) bar
+    foo*: vzunsxnz 280394c7 (empty) foo
+      @origin (to be deleted): vzunsxnz 280394c7 (empty) foo
+      @upstream (in sync): vzunsxnz 280394c7 (empty) foo
+    "###);
+
+    // The remote branch is deleted only on the specified remote
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&local_path, &["git", "push", "--remote", "upstream"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    No branches point to the specified revisions.
+    Nothing changed.
+    "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["git", "push", "--remote", "origin"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Delete branch foo from 280394c74e61
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &local_path), @r###"
+    bar* (deleted)
+      @origin: kywzptru b0955bb1 (empty) bar
+      (this branch will be *deleted permanently* on the remote on the
+       next `jj git push`. Use `jj branch forget` to prevent this)
+    bar@upstream: kywzptru b0955bb1 (empty) bar
+    foo: vzunsxnz 280394c7 (empty) foo
+      @upstream (in sync): vzunsxnz 280394c7 (empty) foo
+    "###);
+}
+
+#[test]
+fn test_branch_delete_export() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo"]);
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+
+    test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "foo"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--all"]);
+    insta::assert_snapshot!(stdout, @r###"
+    foo (deleted)
+      @git: rlvkpnrz 65b6b74e (empty) (no description set)
+      (this branch will be deleted from the underlying Git repo on the next `jj git export`)
+    "###);
+
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--all"]);
+    insta::assert_snapshot!(stdout, @r###"
+    "###);
+}
+
+#[test]
+fn test_branch_forget_export() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--all"]);
+    insta::assert_snapshot!(stdout, @r###"
+    foo: rlvkpnrz 65b6b74e (empty) (no description set)
+    "###);
+
+    // Exporting the branch to git creates a local-git tracking branch
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "foo"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 1 branch.
+    "###);
+    // Forgetting a branch deletes local and remote-tracking branches including
+    // the corresponding git-tracking branch.
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--all"]);
+    insta::assert_snapshot!(stdout, @"");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-r=foo", "--no-graph"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revision "foo" doesn't exist
+    "###);
+
+    // `jj git export` will delete the branch from git. In a colocated repo,
+    // this will happen automatically immediately after a `jj branch forget`.
+    // This is demonstrated in `test_git_colocated_branch_forget` in
+    // test_git_colocated.rs
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--all"]);
+    insta::assert_snapshot!(stdout, @"");
+}
+
+#[test]
+fn test_branch_forget_fetched_branch() {
+    // Much of this test is borrowed from `test_git_fetch_remote_only_branch` in
+    // test_git_fetch.rs
+
+    // Set up a git repo with a branch and a jj repo that has it as a remote.
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git2::Repository::init_bare(git_repo_path).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let mut tree_builder = git_repo.treebuilder(None).unwrap();
+    let file_oid = git_repo.blob(b"content").unwrap();
+    tree_builder
+        .insert("file", file_oid, git2::FileMode::Blob.into())
+        .unwrap();
+    let tree_oid = tree_builder.write().unwrap();
+    let tree = git_repo.find_tree(tree_oid).unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+    // Create a commit and a branch in the git repo
+    let first_git_repo_commit = git_repo
+        .commit(
+            Some("refs/heads/feature1"),
+            &signature,
+            &signature,
+            "message",
+            &tree,
+            &[],
+        )
+        .unwrap();
+
+    // Fetch normally
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: mzyxwzks 9f01a0e0 message
+      @origin (in sync): mzyxwzks 9f01a0e0 message
+    "###);
+
+    // TEST 1: with export-import
+    // Forget the branch
+    test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
+
+    // At this point `jj git export && jj git import` does *not* recreate the
+    // branch. This behavior is important in colocated repos, as otherwise a
+    // forgotten branch would be immediately resurrected.
+    //
+    // Technically, this is because `jj branch forget` preserved
+    // the ref in jj view's `git_refs` tracking the local git repo's remote-tracking
+    // branch.
+    // TODO: Show that jj git push is also a no-op
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
+
+    // We can fetch feature1 again.
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: mzyxwzks 9f01a0e0 message
+      @origin (in sync): mzyxwzks 9f01a0e0 message
+    "###);
+
+    // TEST 2: No export/import (otherwise the same as test 1)
+    test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
+    // Fetch works even without the export-import
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: mzyxwzks 9f01a0e0 message
+      @origin (in sync): mzyxwzks 9f01a0e0 message
+    "###);
+
+    // TEST 3: fetch branch that was moved & forgotten
+
+    // Move the branch in the git repo.
+    git_repo
+        .commit(
+            Some("refs/heads/feature1"),
+            &signature,
+            &signature,
+            "another message",
+            &tree,
+            &[&git_repo.find_commit(first_git_repo_commit).unwrap()],
+        )
+        .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 1 branch.
+    "###);
+
+    // Fetching a moved branch does not create a conflict
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: ooosovrs 38aefb17 (empty) another message
+      @origin (in sync): ooosovrs 38aefb17 (empty) another message
+    "###);
+}
+
+#[test]
+fn test_branch_forget_keep_remotes() {
+    let test_env = TestEnvironment::default();
+
+    // Initialize remote refs
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "remote", "--git"]);
+    let remote_path = test_env.env_root().join("remote");
+    test_env.jj_cmd_ok(&remote_path, &["describe", "-m", "foo"]);
+    test_env.jj_cmd_ok(&remote_path, &["branch", "create", "foo"]);
+    test_env.jj_cmd_ok(&remote_path, &["new"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    let mut remote_git_path = remote_path;
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+    );
+    let local_path = test_env.env_root().join("local");
+
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&local_path, &["branch", "forget", "--keep-remotes", "foo"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 1 branch.
+    "###);
+    // The remote branch is kept, but no longer tracked
+    insta::assert_snapshot!(get_branch_output(&test_env, &local_path), @r###"
+    foo@origin: zonklpmq b87601ef (empty) foo
+    "###);
+
+    // The remote branch isn't deleted on push
+    let (stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["git", "push", "--deleted"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+
+    // It can be tracked again to restore the local branch
+    test_env.jj_cmd_ok(&local_path, &["branch", "track", "foo@origin"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &local_path), @r###"
+    foo: zonklpmq b87601ef (empty) foo
+      @origin (in sync): zonklpmq b87601ef (empty) foo
+    "###);
+}
+
+#[test]
+fn test_branch_forget_include_remotes() {
+    let test_env = TestEnvironment::default();
+
+    // Initialize remote refs
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "remote", "--git"]);
+    let remote_path = test_env.env_root().join("remote");
+    test_env.jj_cmd_ok(&remote_path, &["describe", "-m", "foo"]);
+    test_env.jj_cmd_ok(&remote_path, &["branch", "create", "foo", "bar"]);
+    test_env.jj_cmd_ok(&remote_path, &["new"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    let mut remote_git_path = remote_path;
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+    );
+    let local_path = test_env.env_root().join("local");
+    test_env.jj_cmd_ok(&local_path, &["branch", "create", "baz"]);
+
+    // Remote branches aren't matched by default
+    let stderr = test_env.jj_cmd_failure(&local_path, &["branch", "forget", "glob:*@origin"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No matching branches for patterns: *@origin
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &local_path,
+        &[
+            "branch",
+            "forget",
+            "--include-remotes",
+            "--dry-run",
+            "glob:*@origin",
+            "glob:!bar@*",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    foo@origin: zonklpmq b87601ef bar foo | (empty) foo -> (absent)
+    "###);
+
+    // The local branches are kept
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &local_path,
+        &["branch", "forget", "--include-remotes", "glob:*@origin"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 2 branches.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &local_path), @r###"
+    bar: zonklpmq b87601ef (empty) foo
+    baz: royxmykx f37b4afd (empty) (no description set)
+    foo: zonklpmq b87601ef (empty) foo
+    "###);
+
+    // A remote branch isn't forgotten twice with its local branch
+    test_env.jj_cmd_ok(&local_path, &["undo"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &local_path,
+        &["branch", "forget", "--include-remotes", "glob:foo*"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 1 branch.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &local_path), @r###"
+    bar: zonklpmq b87601ef (empty) foo
+      @origin (in sync): zonklpmq b87601ef (empty) foo
+    baz*: royxmykx f37b4afd (empty) (no description set)
+    "###);
+}
+
+#[test]
+fn test_branch_forget_at_revision() {
+    let test_env = TestEnvironment::default();
+
+    // Initialize remote refs
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "remote", "--git"]);
+    let remote_path = test_env.env_root().join("remote");
+    test_env.jj_cmd_ok(&remote_path, &["describe", "-m", "foo"]);
+    test_env.jj_cmd_ok(&remote_path, &["branch", "create", "foo"]);
+    test_env.jj_cmd_ok(&remote_path, &["new"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    let mut remote_git_path = remote_path;
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+    );
+    let local_path = test_env.env_root().join("local");
+    // Only the remote branch foo@origin points to the commit
+    test_env.jj_cmd_ok(&local_path, &["branch", "forget", "--keep-remotes", "foo"]);
+    test_env.jj_cmd_ok(
+        &local_path,
+        &["branch", "create", "-rdescription(foo)", "bar", "baz"],
+    );
+    test_env.jj_cmd_ok(&local_path, &["branch", "create", "-rroot()", "qux"]);
+
+    // No branches point to the working-copy commit
+    let stderr = test_env.jj_cmd_failure(&local_path, &["branch", "forget", "--at", "@"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No branches point to commit f37b4afd0407
+    "###);
+    // The patterns can still match branches elsewhere
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &local_path,
+        &["branch", "forget", "--dry-run", "--at", "@", "qux"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    qux: zzzzzzzz 00000000 qux | (empty) (no description set) -> (absent)
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+
+    // The branches at the revision can be combined with patterns and exclusions
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &local_path,
+        &[
+            "branch",
+            "forget",
+            "--at",
+            "description(foo)",
+            "qux",
+            "glob:!baz",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 3 branches.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &local_path), @r###"
+    baz: zonklpmq b87601ef (empty) foo
+    "###);
+}
+
+#[test]
+fn test_branch_forget_deleted_or_nonexistent_branch() {
+    // Much of this test is borrowed from `test_git_fetch_remote_only_branch` in
+    // test_git_fetch.rs
+
+    // ======== Beginning of test setup ========
+    // Set up a git repo with a branch and a jj repo that has it as a remote.
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git2::Repository::init_bare(git_repo_path).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let mut tree_builder = git_repo.treebuilder(None).unwrap();
+    let file_oid = git_repo.blob(b"content").unwrap();
+    tree_builder
+        .insert("file", file_oid, git2::FileMode::Blob.into())
+        .unwrap();
+    let tree_oid = tree_builder.write().unwrap();
+    let tree = git_repo.find_tree(tree_oid).unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+    // Create a commit and a branch in the git repo
+    git_repo
+        .commit(
+            Some("refs/heads/feature1"),
+            &signature,
+            &signature,
+            "message",
+            &tree,
+            &[],
+        )
+        .unwrap();
+
+    // Fetch and then delete the branch
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "feature1"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1* (deleted)
+      @origin: mzyxwzks 9f01a0e0 message
+      (this branch will be *deleted permanently* on the remote on the
+       next `jj git push`. Use `jj branch forget` to prevent this)
+    "###);
+
+    // ============ End of test setup ============
+
+    // We can forget a deleted branch
+    test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "feature1"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @"");
+
+    // A branch that exists only on the remote is forgotten by glob too
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "feature1"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "feature1@origin"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--all"]);
+    insta::assert_snapshot!(stdout, @r###"
+    feature1@origin: mzyxwzks 9f01a0e0 message
+    "###);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "forget", "glob:feat*"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 1 branch.
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--all"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    // Can't forget a non-existent branch
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "forget", "i_do_not_exist"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such branch: i_do_not_exist
+    "###);
+}
+
+#[test]
+fn test_branch_track_untrack() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Set up remote
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git2::Repository::init(git_repo_path).unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+    let create_remote_commit = |message: &str, data: &[u8], ref_names: &[&str]| {
+        let signature =
+            git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0))
+                .unwrap();
+        let mut tree_builder = git_repo.treebuilder(None).unwrap();
+        let file_oid = git_repo.blob(data).unwrap();
+        tree_builder
+            .insert("file", file_oid, git2::FileMode::Blob.into())
+            .unwrap();
+        let tree_oid = tree_builder.write().unwrap();
+        let tree = git_repo.find_tree(tree_oid).unwrap();
+        // Create commit and branches in the remote
+        let git_commit_oid = git_repo
+            .commit(None, &signature, &signature, message, &tree, &[])
+            .unwrap();
+        for name in ref_names {
+            git_repo.reference(name, git_commit_oid, true, "").unwrap();
+        }
+    };
+
+    // Fetch new commit without auto tracking. No local branches should be
+    // created.
+    create_remote_commit(
+        "commit 1",
+        b"content 1",
+        &[
+            "refs/heads/main",
+            "refs/heads/feature1",
+            "refs/heads/feature2",
+        ],
+    );
+    test_env.add_config("git.auto-local-branch = false");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @"");
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1@origin: sptzoqmo 7b33f629 commit 1
+    feature2@origin: sptzoqmo 7b33f629 commit 1
+    main@origin: sptzoqmo 7b33f629 commit 1
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ◉  feature1@origin feature2@origin main@origin 7b33f6295eda
+    │ @   230dd059e1b0
+    ├─╯
+    ◉   000000000000
+    "###);
+
+    // Track new branch. Local branch should be created.
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "track", "feature1@origin", "main@origin"],
+    );
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: sptzoqmo 7b33f629 commit 1
+      @origin (in sync): sptzoqmo 7b33f629 commit 1
+    feature2@origin: sptzoqmo 7b33f629 commit 1
+    main: sptzoqmo 7b33f629 commit 1
+      @origin (in sync): sptzoqmo 7b33f629 commit 1
+    "###);
+
+    // Track existing branch. Local branch should result in conflict.
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "feature2"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "track", "feature2@origin"]);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: sptzoqmo 7b33f629 commit 1
+      @origin (in sync): sptzoqmo 7b33f629 commit 1
+    feature2* (conflicted):
+      + qpvuntsm 230dd059 (empty) (no description set)
+      + sptzoqmo 7b33f629 commit 1
+      @origin (behind by 1 commits): sptzoqmo 7b33f629 commit 1
+    main: sptzoqmo 7b33f629 commit 1
+      @origin (in sync): sptzoqmo 7b33f629 commit 1
+    "###);
+
+    // Untrack existing and locally-deleted branches. Branch targets should be
+    // unchanged
+    test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "feature2"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "untrack", "feature1@origin", "feature2@origin"],
+    );
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1*: sptzoqmo 7b33f629 commit 1
+    feature1@origin (in sync): sptzoqmo 7b33f629 commit 1
+    feature2@origin: sptzoqmo 7b33f629 commit 1
+    main: sptzoqmo 7b33f629 commit 1
+      @origin (in sync): sptzoqmo 7b33f629 commit 1
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ◉  feature1 feature1@origin feature2@origin main 7b33f6295eda
+    │ @   230dd059e1b0
+    ├─╯
+    ◉   000000000000
+    "###);
+
+    // Fetch new commit. Only tracking branch "main" should be merged.
+    create_remote_commit(
+        "commit 2",
+        b"content 2",
+        &[
+            "refs/heads/main",
+            "refs/heads/feature1",
+            "refs/heads/feature2",
+        ],
+    );
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @"");
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1*: sptzoqmo 7b33f629 commit 1
+    feature1@origin (ahead by 1 commits, behind by 1 commits): mmqqkyyt 40dabdaf commit 2
+    feature2@origin: mmqqkyyt 40dabdaf commit 2
+    main: mmqqkyyt 40dabdaf commit 2
+      @origin (in sync): mmqqkyyt 40dabdaf commit 2
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ◉  feature1@origin feature2@origin main 40dabdaf4abe
+    │ ◉  feature1 7b33f6295eda
+    ├─╯
+    │ @   230dd059e1b0
+    ├─╯
+    ◉   000000000000
+    "###);
+
+    // Fetch new commit with auto tracking. Tracking branch "main" and new
+    // branch "feature3" should be merged.
+    create_remote_commit(
+        "commit 3",
+        b"content 3",
+        &[
+            "refs/heads/main",
+            "refs/heads/feature1",
+            "refs/heads/feature2",
+            "refs/heads/feature3",
+        ],
+    );
+    test_env.add_config("git.auto-local-branch = true");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Abandoned 1 commits that are no longer reachable.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1*: sptzoqmo 7b33f629 commit 1
+    feature1@origin (ahead by 1 commits, behind by 1 commits): wwnpyzpo 3f0f86fa commit 3
+    feature2@origin: wwnpyzpo 3f0f86fa commit 3
+    feature3: wwnpyzpo 3f0f86fa commit 3
+      @origin (in sync): wwnpyzpo 3f0f86fa commit 3
+    main: wwnpyzpo 3f0f86fa commit 3
+      @origin (in sync): wwnpyzpo 3f0f86fa commit 3
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ◉  feature1@origin feature2@origin feature3 main 3f0f86fa0e57
+    │ ◉  feature1 7b33f6295eda
+    ├─╯
+    │ @   230dd059e1b0
+    ├─╯
+    ◉   000000000000
+    "###);
+}
+
+#[test]
+fn test_branch_track_untrack_patterns() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Set up remote
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git2::Repository::init(git_repo_path).unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+
+    // Create remote commit
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let mut tree_builder = git_repo.treebuilder(None).unwrap();
+    let file_oid = git_repo.blob(b"content").unwrap();
+    tree_builder
+        .insert("file", file_oid, git2::FileMode::Blob.into())
+        .unwrap();
+    let tree_oid = tree_builder.write().unwrap();
+    let tree = git_repo.find_tree(tree_oid).unwrap();
+    // Create commit and branches in the remote
+    let git_commit_oid = git_repo
+        .commit(None, &signature, &signature, "commit", &tree, &[])
+        .unwrap();
+    for name in ["refs/heads/feature1", "refs/heads/feature2"] {
+        git_repo.reference(name, git_commit_oid, true, "").unwrap();
+    }
+
+    // Fetch new commit without auto tracking
+    test_env.add_config("git.auto-local-branch = false");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @"");
+
+    // Track local branch
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "main"]);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_cli_error(&repo_path, &["branch", "track", "main"]), @r###"
+    error: invalid value 'main' for '<NAMES>...': remote branch must be specified in branch@remote form
+
+    For more information, try '--help'.
+    "###);
+
+    // Track/untrack unknown branch
+    insta::assert_snapshot!(
+        test_env.jj_cmd_failure(&repo_path, &["branch", "track", "main@origin"]), @r###"
+    Error: No such remote branch: main@origin
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_failure(&repo_path, &["branch", "untrack", "main@origin"]), @r###"
+    Error: No such remote branch: main@origin
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_failure(&repo_path, &["branch", "track", "glob:maine@*"]), @r###"
+    Error: No matching remote branches for patterns: maine@*
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_failure(
+            &repo_path,
+            &["branch", "untrack", "main@origin", "glob:main@o*"],
+        ), @r###"
+    Error: No matching remote branches for patterns: main@origin, main@o*
+    "###);
+
+    // Track already tracked branch
+    test_env.jj_cmd_ok(&repo_path, &["branch", "track", "feature1@origin"]);
+    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "track", "feature1@origin"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Remote branch already tracked: feature1@origin
+    Nothing changed.
+    "###);
+
+    // Untrack non-tracking branch
+    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "feature2@origin"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Remote branch not tracked yet: feature2@origin
+    Nothing changed.
+    "###);
+
+    // Untrack Git-tracking branch
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "main@git"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Git-tracking branch cannot be untracked: main@git
+    Nothing changed.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: omvolwpu 1336caed commit
+      @git (in sync): omvolwpu 1336caed commit
+      @origin (in sync): omvolwpu 1336caed commit
+    feature2@origin: omvolwpu 1336caed commit
+    main*: qpvuntsm 230dd059 (empty) (no description set)
+      @git (in sync): qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+
+    // Untrack by pattern
+    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "glob:*@*"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Git-tracking branch cannot be untracked: feature1@git
+    Remote branch not tracked yet: feature2@origin
+    Git-tracking branch cannot be untracked: main@git
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1*: omvolwpu 1336caed commit
+      @git (in sync): omvolwpu 1336caed commit
+    feature1@origin (in sync): omvolwpu 1336caed commit
+    feature2@origin: omvolwpu 1336caed commit
+    main*: qpvuntsm 230dd059 (empty) (no description set)
+      @git (in sync): qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+
+    // Track by pattern
+    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "track", "glob:feature?@origin"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Started tracking 2 remote branches.
+    "###);
+    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
+    feature1: omvolwpu 1336caed commit
+      @git (in sync): omvolwpu 1336caed commit
+      @origin (in sync): omvolwpu 1336caed commit
+    feature2: omvolwpu 1336caed commit
+      @origin (in sync): omvolwpu 1336caed commit
+    main*: qpvuntsm 230dd059 (empty) (no description set)
+      @git (in sync): qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+}
+
+#[test]
+fn test_branch_list_last_op() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo", "bar"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "child"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "bar", "-r@"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--last-op"]);
+    insta::assert_snapshot!(stdout, @r###"
+    bar: kkmpptxz 41bc3705 (empty) child
+      Last changed by operation 313078141fc4 at 2001-02-03 04:05:10.000 +07:00: point branch bar to commit 41bc37053e8540cc1ddd1f8a83ccb4e07cc8fdf0
+    foo: qpvuntsm 230dd059 (empty) (no description set)
+      Last changed by operation 3857b95aa026 at 2001-02-03 04:05:08.000 +07:00: create branches foo, bar pointing to commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    "###);
+
+    // Only the text output is annotated
+    let stderr =
+        test_env.jj_cmd_cli_error(&repo_path, &["branch", "list", "--last-op", "--names-only"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--last-op' cannot be used with '--names-only'
+
+    Usage: jj branch list --last-op [NAMES]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_branch_list_base() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "base"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "main"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "feature 1"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "feature 2"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "feature"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "main", "-m", "trunk"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "main", "-r@"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--base=main"]);
+    insta::assert_snapshot!(stdout, @r###"
+    feature (2 ahead of main, 1 behind main): mzvwutvl be4b78aa (empty) feature 2
+    main (0 ahead of main, 0 behind main): yqosqzyt 91dabb72 (empty) trunk
+    "###);
+
+    // A conflicted branch is compared by all of its targets
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "create", "conflicted", "-r=description(trunk)"],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "create", "conflicted", "-r=feature", "--at-op=@-"],
+    );
+    // Resolve the concurrent operations
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--base=main"]);
+    insta::assert_snapshot!(stdout, @r###"
+    conflicted (2 ahead of main, 0 behind main) (conflicted):
+      + yqosqzyt 91dabb72 (empty) trunk
+      + mzvwutvl be4b78aa (empty) feature 2
+    feature (2 ahead of main, 1 behind main): mzvwutvl be4b78aa (empty) feature 2
+    main (0 ahead of main, 0 behind main): yqosqzyt 91dabb72 (empty) trunk
+    "###);
+
+    // The base defaults to the trunk, which is the root commit without remotes
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--base", "feature"]);
+    insta::assert_snapshot!(stdout, @r###"
+    feature (3 ahead of trunk(), 0 behind trunk()): mzvwutvl be4b78aa (empty) feature 2
+    "###);
+    test_env.add_config(r#"revsets.trunk = "feature""#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--base", "main"]);
+    insta::assert_snapshot!(stdout, @r###"
+    main (1 ahead of feature, 2 behind feature): yqosqzyt 91dabb72 (empty) trunk
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "list", "--base=none()"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Base revset "none()" didn't resolve to any revisions
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "list", "--base=unknown"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revision "unknown" doesn't exist
+    "###);
+}
+
+#[test]
+fn test_branch_list_contains() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "base"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "base"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "feature"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "feature"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "base", "-m", "trunk"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "main"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "conflicted", "-r=main"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "create", "conflicted", "-r=feature", "--at-op=@-"],
+    );
+    // Resolve the concurrent operations
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--contains=base"]);
+    insta::assert_snapshot!(stdout, @r###"
+    base: qpvuntsm de9c740f (empty) base
+    conflicted (conflicted):
+      + royxmykx d74f6ad6 (empty) trunk
+      + zsuskuln 4068635a (empty) feature
+    feature: zsuskuln 4068635a (empty) feature
+    main: royxmykx d74f6ad6 (empty) trunk
+    "###);
+    // A conflicted branch is listed if any of its targets contains the revision
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--contains=feature"]);
+    insta::assert_snapshot!(stdout, @r###"
+    conflicted (conflicted):
+      + royxmykx d74f6ad6 (empty) trunk
+      + zsuskuln 4068635a (empty) feature
+    feature: zsuskuln 4068635a (empty) feature
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--contains=@"]);
+    insta::assert_snapshot!(stdout, @r###"
+    conflicted (conflicted):
+      + royxmykx d74f6ad6 (empty) trunk
+      + zsuskuln 4068635a (empty) feature
+    main: royxmykx d74f6ad6 (empty) trunk
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "list", "--contains=all()"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revset "all()" resolved to more than one revision
+    Hint: The revset "all()" resolved to these revisions:
+    royxmykx d74f6ad6 conflicted?? main | (empty) trunk
+    zsuskuln 4068635a conflicted?? feature | (empty) feature
+    qpvuntsm de9c740f base | (empty) base
+    zzzzzzzz 00000000 (empty) (no description set)
+    "###);
+}
+
+#[test]
+fn test_branch_list_merged() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "base"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "base"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "merged"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "merged"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "base", "-m", "unmerged"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "unmerged"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "merged", "-m", "trunk"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "main"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "list", "--merged=main"]);
+    insta::assert_snapshot!(stdout, @r###"
+    base: qpvuntsm de9c740f (empty) base
+    main: vruxwmqv 57a28d18 (empty) trunk
+    merged: zsuskuln b1b63d1d (empty) merged
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["branch", "list", "--no-merged=main", "--names-only"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    unmerged
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["branch", "list", "--merged=main", "--no-merged=base"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    main: vruxwmqv 57a28d18 (empty) trunk
+    merged: zsuskuln b1b63d1d (empty) merged
+    "###);
+}
+
+#[test]
+fn test_branch_list() {
+    let test_env = TestEnvironment::default();
+
+    // Initialize remote refs
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "remote", "--git"]);
+    let remote_path = test_env.env_root().join("remote");
+    for branch in [
+        "remote-sync",
+        "remote-unsync",
+        "remote-untrack",
+        "remote-delete",
+    ] {
+        test_env.jj_cmd_ok(&remote_path, &["new", "root()", "-m", branch]);
+        test_env.jj_cmd_ok(&remote_path, &["branch", "create", branch]);
+    }
+    test_env.jj_cmd_ok(&remote_path, &["new"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    // Initialize local refs
+    let mut remote_git_path = remote_path;
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+    );
+    let local_path = test_env.env_root().join("local");
+    test_env.jj_cmd_ok(&local_path, &["new", "root()", "-m", "local-only"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "create", "local-only"]);
+
+    // Mutate refs in local repository
+    test_env.jj_cmd_ok(&local_path, &["branch", "delete", "remote-delete"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "delete", "remote-untrack"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "untrack", "remote-untrack@origin"]);
+    test_env.jj_cmd_ok(
+        &local_path,
+        &["branch", "set", "--allow-backwards", "remote-unsync"],
+    );
+
+    // Synchronized tracking remotes and non-tracking remotes aren't listed by
+    // default
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list"]), @r###"
+    local-only*: wqnwkozp 4e887f78 (empty) local-only
+    remote-delete* (deleted)
+      @origin: mnmymoky 203e60eb (empty) remote-delete
+      (this branch will be *deleted permanently* on the remote on the
+       next `jj git push`. Use `jj branch forget` to prevent this)
+    remote-sync: zwtyzrop c761c7ea (empty) remote-sync
+      @origin (in sync): zwtyzrop c761c7ea (empty) remote-sync
+    remote-unsync*: wqnwkozp 4e887f78 (empty) local-only
+      @origin (ahead by 1 commits, behind by 1 commits): qpsqxpyq 38ef8af7 (empty) remote-unsync
+    "###);
+
+    // Out-of-sync branches and ahead/behind counts have colorable labels
+    test_env.add_config(
+        r#"
+        colors.ahead = "green"
+        colors.behind = "red"
+        "#,
+    );
+    let stdout = test_env.jj_cmd_success(
+        &local_path,
+        &["branch", "list", "--color=always", "remote-unsync"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [38;5;5mremote-unsync[39m[38;5;3m*[39m: [1m[38;5;5mw[0m[38;5;8mqnwkozp[39m [1m[38;5;4m4[0m[38;5;8me887f78[39m [38;5;2m(empty)[39m local-only
+      [38;5;5m@origin[39m ([38;5;2mahead by 1 commits[39m, [38;5;1mbehind by 1 commits[39m): [1m[38;5;5mq[0m[38;5;8mpsqxpyq[39m [1m[38;5;4m3[0m[38;5;8m8ef8af7[39m [38;5;2m(empty)[39m remote-unsync
+    "###);
+
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--all"]), @r###"
+    local-only*: wqnwkozp 4e887f78 (empty) local-only
+    remote-delete* (deleted)
+      @origin: mnmymoky 203e60eb (empty) remote-delete
+      (this branch will be *deleted permanently* on the remote on the
+       next `jj git push`. Use `jj branch forget` to prevent this)
+    remote-sync: zwtyzrop c761c7ea (empty) remote-sync
+      @origin (in sync): zwtyzrop c761c7ea (empty) remote-sync
+    remote-unsync*: wqnwkozp 4e887f78 (empty) local-only
+      @origin (ahead by 1 commits, behind by 1 commits): qpsqxpyq 38ef8af7 (empty) remote-unsync
+    remote-untrack@origin: vmortlor 71a16b05 (empty) remote-untrack
+    "###);
+
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--all", "--format", "json"]), @r###"
+    [
+      {
+        "name": "local-only",
+        "local_target": "4e887f78eb623acad70de4be659dc036e118f6e5",
+        "conflicted": false,
+        "remotes": {}
+      },
+      {
+        "name": "remote-delete",
+        "local_target": null,
+        "conflicted": false,
+        "remotes": {
+          "origin": {
+            "target": "203e60eb354463395f342935a715fedb8fdb8b3b",
+            "tracking": true,
+            "ahead": null,
+            "behind": null
+          }
+        }
+      },
+      {
+        "name": "remote-sync",
+        "local_target": "c761c7ea2e2235c50fc0da3cfc2e82b05d1086c0",
+        "conflicted": false,
+        "remotes": {
+          "origin": {
+            "target": "c761c7ea2e2235c50fc0da3cfc2e82b05d1086c0",
+            "tracking": true,
+            "ahead": 0,
+            "behind": 0
+          }
+        }
+      },
+      {
+        "name": "remote-unsync",
+        "local_target": "4e887f78eb623acad70de4be659dc036e118f6e5",
+        "conflicted": false,
+        "remotes": {
+          "origin": {
+            "target": "38ef8af7134173e4682b5aea2822b14a98549e59",
+            "tracking": true,
+            "ahead": 1,
+            "behind": 1
+          }
+        }
+      },
+      {
+        "name": "remote-untrack",
+        "local_target": null,
+        "conflicted": false,
+        "remotes": {
+          "origin": {
+            "target": "71a16b0505cdf2d3c3b91975a7c739be1d761db2",
+            "tracking": false,
+            "ahead": null,
+            "behind": null
+          }
+        }
+      }
+    ]
+    "###);
+
+    let template = r#"
+      separate(" ",
+        name ++ if(remote, "@" ++ remote),
+        target.map(|c| c.commit_id().short()),
+        if(deleted, "(deleted)"),
+      )"#;
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "-T", template]), @r###"
+    local-only 4e887f78eb62
+    remote-delete (deleted)
+    remote-delete@origin 203e60eb3544
+    remote-sync c761c7ea2e22
+    remote-unsync 4e887f78eb62
+    remote-unsync@origin 38ef8af71341
+    "###);
+    let template = r#"name ++ if(remote, "@" ++ remote) ++ " [" ++ remotes.join(",") ++ "]""#;
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--all", "-T", template]), @r###"
+    local-only []
+    remote-delete [origin]
+    remote-delete@origin [origin]
+    remote-sync [origin]
+    remote-sync@origin [origin]
+    remote-unsync [origin]
+    remote-unsync@origin [origin]
+    remote-untrack@origin [origin]
+    "###);
+
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--tracked"]), @r###"
+    remote-delete* (deleted)
+      @origin: mnmymoky 203e60eb (empty) remote-delete
+      (this branch will be *deleted permanently* on the remote on the
+       next `jj git push`. Use `jj branch forget` to prevent this)
+    remote-sync: zwtyzrop c761c7ea (empty) remote-sync
+      @origin (in sync): zwtyzrop c761c7ea (empty) remote-sync
+    remote-unsync*: wqnwkozp 4e887f78 (empty) local-only
+      @origin (ahead by 1 commits, behind by 1 commits): qpsqxpyq 38ef8af7 (empty) remote-unsync
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--untracked", "--all"]), @r###"
+    local-only*: wqnwkozp 4e887f78 (empty) local-only
+    "###);
+
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--local-only"]), @r###"
+    local-only*: wqnwkozp 4e887f78 (empty) local-only
+    remote-delete* (deleted)
+      (this branch will be *deleted permanently* on the remote on the
+       next `jj git push`. Use `jj branch forget` to prevent this)
+    remote-sync: zwtyzrop c761c7ea (empty) remote-sync
+    remote-unsync*: wqnwkozp 4e887f78 (empty) local-only
+    "###);
+    let stderr =
+        test_env.jj_cmd_cli_error(&local_path, &["branch", "list", "--local-only", "--all"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--local-only' cannot be used with '--all'
+
+    Usage: jj branch list --local-only [NAMES]...
+
+    For more information, try '--help'.
+    "###);
+
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--names-only"]), @r###"
+    local-only
+    remote-delete
+    remote-sync
+    remote-unsync
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--names-only", "--all"]), @r###"
+    local-only
+    remote-delete
+    remote-sync
+    remote-unsync
+    remote-untrack
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &local_path,
+        &["branch", "list", "--names-only", "-rlocal-only"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    local-only
+    remote-unsync
+    "###);
+
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--count"]), @r###"
+    3
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--count", "--all"]), @r###"
+    3
+    4
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--count", "--tracked"]), @r###"
+    2
+    "###);
+    let stderr = test_env.jj_cmd_cli_error(&local_path, &["branch", "list", "--count", "-Tname"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--count' cannot be used with '--template <TEMPLATE>'
+
+    Usage: jj branch list --count [NAMES]...
+
+    For more information, try '--help'.
+    "###);
+
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--conflicted"]), @r###"
+    remote-unsync*: wqnwkozp 4e887f78 (empty) local-only
+      @origin (ahead by 1 commits, behind by 1 commits): qpsqxpyq 38ef8af7 (empty) remote-unsync
+    "###);
+}
+
+#[test]
+fn test_branch_list_sorted() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "old"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "b-old"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "new"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "c-new"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "a-conflicted"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "branch",
+            "create",
+            "a-conflicted",
+            "-rdescription(old)",
+            "--at-op=@-",
+        ],
+    );
+    // Resolve the concurrent operations
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["branch", "list"]), @r###"
+    a-conflicted (conflicted):
+      + zsuskuln a00b4f32 (empty) new
+      + rlvkpnrz 8b0dc2d1 (empty) old
+    b-old: rlvkpnrz 8b0dc2d1 (empty) old
+    c-new: zsuskuln a00b4f32 (empty) new
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--sort=committer-date"]), @r###"
+    b-old: rlvkpnrz 8b0dc2d1 (empty) old
+    a-conflicted (conflicted):
+      + zsuskuln a00b4f32 (empty) new
+      + rlvkpnrz 8b0dc2d1 (empty) old
+    c-new: zsuskuln a00b4f32 (empty) new
+    "###);
+    // Conflicted branches are sorted by the newest target commit
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--sort", "-committer-date"]), @r###"
+    a-conflicted (conflicted):
+      + zsuskuln a00b4f32 (empty) new
+      + rlvkpnrz 8b0dc2d1 (empty) old
+    c-new: zsuskuln a00b4f32 (empty) new
+    b-old: rlvkpnrz 8b0dc2d1 (empty) old
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--sort=-name"]), @r###"
+    c-new: zsuskuln a00b4f32 (empty) new
+    b-old: rlvkpnrz 8b0dc2d1 (empty) old
+    a-conflicted (conflicted):
+      + zsuskuln a00b4f32 (empty) new
+      + rlvkpnrz 8b0dc2d1 (empty) old
+    "###);
+    // The whole ordering is reversed, including branches with the same date
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--sort=-committer-date", "--reverse"]), @r###"
+    b-old: rlvkpnrz 8b0dc2d1 (empty) old
+    c-new: zsuskuln a00b4f32 (empty) new
+    a-conflicted (conflicted):
+      + zsuskuln a00b4f32 (empty) new
+      + rlvkpnrz 8b0dc2d1 (empty) old
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--names-only", "--reverse"]), @r###"
+    c-new
+    b-old
+    a-conflicted
+    "###);
+
+    // Deleted branches are listed last
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "b-old"]);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--sort=-name"]), @r###"
+    c-new: zsuskuln a00b4f32 (empty) new
+    a-conflicted (conflicted):
+      + zsuskuln a00b4f32 (empty) new
+      + rlvkpnrz 8b0dc2d1 (empty) old
+    b-old (deleted)
+      @git: rlvkpnrz 8b0dc2d1 (empty) old
+      (this branch will be deleted from the underlying Git repo on the next `jj git export`)
     "###);
     insta::assert_snapshot!(
-        test_env.jj_cmd_failure(&repo_path, &["branch", "track", "glob:maine@*"]), @r###"
-    Error: No matching remote branches for patterns: maine@*
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--sort=-name", "--reverse"]), @r###"
+    b-old (deleted)
+      @git: rlvkpnrz 8b0dc2d1 (empty) old
+      (this branch will be deleted from the underlying Git repo on the next `jj git export`)
+    a-conflicted (conflicted):
+      + zsuskuln a00b4f32 (empty) new
+      + rlvkpnrz 8b0dc2d1 (empty) old
+    c-new: zsuskuln a00b4f32 (empty) new
     "###);
     insta::assert_snapshot!(
-        test_env.jj_cmd_failure(
+        test_env.jj_cmd_success(
             &repo_path,
-            &["branch", "untrack", "main@origin", "glob:main@o*"],
+            &["branch", "list", "--format=json", "a-conflicted", "b-old"],
         ), @r###"
-    Error: No matching remote branches for patterns: main@origin, main@o*
+    [
+      {
+        "name": "a-conflicted",
+        "local_target": {
+          "adds": [
+            "a00b4f3234c79696e413cd6292a6be80d0301840",
+            "8b0dc2d1506e764d20c3de4b0cc240ee2e7c98bd"
+          ],
+          "removes": []
+        },
+        "conflicted": true,
+        "remotes": {}
+      },
+      {
+        "name": "b-old",
+        "local_target": null,
+        "conflicted": false,
+        "remotes": {
+          "git": {
+            "target": "8b0dc2d1506e764d20c3de4b0cc240ee2e7c98bd",
+            "tracking": true,
+            "ahead": null,
+            "behind": null
+          }
+        }
+      }
+    ]
     "###);
 
-    // Track already tracked branch
-    test_env.jj_cmd_ok(&repo_path, &["branch", "track", "feature1@origin"]);
-    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "track", "feature1@origin"]);
-    insta::assert_snapshot!(stderr, @r###"
-    Remote branch already tracked: feature1@origin
-    Nothing changed.
+    // --check exits with a distinct status if any listed branch is conflicted
+    let assert = test_env
+        .jj_cmd(&repo_path, &["branch", "list", "--check"])
+        .assert()
+        .code(4);
+    let stdout = test_env.normalize_output(&common::get_stdout_string(&assert));
+    let stderr = test_env.normalize_output(&common::get_stderr_string(&assert));
+    insta::assert_snapshot!(stdout, @r###"
+    a-conflicted (conflicted):
+      + zsuskuln a00b4f32 (empty) new
+      + rlvkpnrz 8b0dc2d1 (empty) old
+    b-old (deleted)
+      @git: rlvkpnrz 8b0dc2d1 (empty) old
+      (this branch will be deleted from the underlying Git repo on the next `jj git export`)
+    c-new: zsuskuln a00b4f32 (empty) new
     "###);
-
-    // Untrack non-tracking branch
-    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "feature2@origin"]);
     insta::assert_snapshot!(stderr, @r###"
-    Remote branch not tracked yet: feature2@origin
-    Nothing changed.
+    Error: Found conflicted branches: a-conflicted
+    "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "list", "--check", "c-new"]);
+    insta::assert_snapshot!(stdout, @r###"
+    c-new: zsuskuln a00b4f32 (empty) new
     "###);
+    insta::assert_snapshot!(stderr, @"");
 
-    // Untrack Git-tracking branch
-    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
-    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "main@git"]);
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "list", "--sort=-size"]);
     insta::assert_snapshot!(stderr, @r###"
-    Git-tracking branch cannot be untracked: main@git
-    Nothing changed.
+    error: invalid value '-size' for '--sort <SORT>': invalid variant: size
+
+    For more information, try '--help'.
     "###);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: omvolwpu 1336caed commit
-      @git: omvolwpu 1336caed commit
-      @origin: omvolwpu 1336caed commit
-    feature2@origin: omvolwpu 1336caed commit
-    main: qpvuntsm 230dd059 (empty) (no description set)
-      @git: qpvuntsm 230dd059 (empty) (no description set)
+
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--conflicted"]), @r###"
+    a-conflicted (conflicted):
+      + zsuskuln a00b4f32 (empty) new
+      + rlvkpnrz 8b0dc2d1 (empty) old
     "###);
+}
 
-    // Untrack by pattern
-    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "untrack", "glob:*@*"]);
-    insta::assert_snapshot!(stderr, @r###"
-    Git-tracking branch cannot be untracked: feature1@git
-    Remote branch not tracked yet: feature2@origin
-    Git-tracking branch cannot be untracked: main@git
+#[test]
+fn test_branch_list_stale() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    let timestamp_config =
+        |timestamp: &str| format!("--config-toml=debug.commit-timestamp='{timestamp}'");
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "old"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "old"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "new",
+            "root()",
+            "-m",
+            "recent",
+            &timestamp_config("2001-03-01T00:00:00+00:00"),
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "recent"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "deleted"]);
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "deleted"]);
+
+    let now = timestamp_config("2001-03-10T00:00:00+00:00");
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--stale=2w", &now]), @r###"
+    old: rlvkpnrz 8b0dc2d1 (empty) old
     "###);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: omvolwpu 1336caed commit
-      @git: omvolwpu 1336caed commit
-    feature1@origin: omvolwpu 1336caed commit
-    feature2@origin: omvolwpu 1336caed commit
-    main: qpvuntsm 230dd059 (empty) (no description set)
-      @git: qpvuntsm 230dd059 (empty) (no description set)
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--stale", "1d", &now]), @r###"
+    old: rlvkpnrz 8b0dc2d1 (empty) old
+    recent: zsuskuln f6ce0a12 (empty) recent
     "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--stale=10w", &now]), @"");
 
-    // Track by pattern
-    let (_, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "track", "glob:feature?@origin"]);
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "list", "--stale=30"]);
     insta::assert_snapshot!(stderr, @r###"
-    Started tracking 2 remote branches.
+    error: invalid value '30' for '--stale <DURATION>': missing unit (expected one of m, h, d, w)
+
+    For more information, try '--help'.
     "###);
-    insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: omvolwpu 1336caed commit
-      @git: omvolwpu 1336caed commit
-      @origin: omvolwpu 1336caed commit
-    feature2: omvolwpu 1336caed commit
-      @origin: omvolwpu 1336caed commit
-    main: qpvuntsm 230dd059 (empty) (no description set)
-      @git: qpvuntsm 230dd059 (empty) (no description set)
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["branch", "list", "--stale=2y"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value '2y' for '--stale <DURATION>': invalid unit `y` (expected one of m, h, d, w)
+
+    For more information, try '--help'.
     "###);
 }
 
 #[test]
-fn test_branch_list() {
+fn test_branch_list_hide_deleted() {
     let test_env = TestEnvironment::default();
 
     // Initialize remote refs
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "remote", "--git"]);
     let remote_path = test_env.env_root().join("remote");
-    for branch in [
-        "remote-sync",
-        "remote-unsync",
-        "remote-untrack",
-        "remote-delete",
-    ] {
+    for branch in ["remote-sync", "remote-untrack", "remote-delete"] {
         test_env.jj_cmd_ok(&remote_path, &["new", "root()", "-m", branch]);
-        test_env.jj_cmd_ok(&remote_path, &["branch", "set", branch]);
+        test_env.jj_cmd_ok(&remote_path, &["branch", "create", branch]);
     }
     test_env.jj_cmd_ok(&remote_path, &["new"]);
     test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
@@ -819,44 +2850,28 @@ fn test_branch_list() {
         &["git", "clone", remote_git_path.to_str().unwrap(), "local"],
     );
     let local_path = test_env.env_root().join("local");
-    test_env.jj_cmd_ok(&local_path, &["new", "root()", "-m", "local-only"]);
-    test_env.jj_cmd_ok(&local_path, &["branch", "set", "local-only"]);
-
-    // Mutate refs in local repository
     test_env.jj_cmd_ok(&local_path, &["branch", "delete", "remote-delete"]);
     test_env.jj_cmd_ok(&local_path, &["branch", "delete", "remote-untrack"]);
     test_env.jj_cmd_ok(&local_path, &["branch", "untrack", "remote-untrack@origin"]);
-    test_env.jj_cmd_ok(
-        &local_path,
-        &["branch", "set", "--allow-backwards", "remote-unsync"],
-    );
 
-    // Synchronized tracking remotes and non-tracking remotes aren't listed by
-    // default
     insta::assert_snapshot!(
-        test_env.jj_cmd_success(&local_path, &["branch", "list"]), @r###"
-    local-only: wqnwkozp 4e887f78 (empty) local-only
-    remote-delete (deleted)
-      @origin: mnmymoky 203e60eb (empty) remote-delete
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--all"]), @r###"
+    remote-delete* (deleted)
+      @origin: ruwvszzu e43ae806 (empty) remote-delete
       (this branch will be *deleted permanently* on the remote on the
        next `jj git push`. Use `jj branch forget` to prevent this)
     remote-sync: zwtyzrop c761c7ea (empty) remote-sync
-    remote-unsync: wqnwkozp 4e887f78 (empty) local-only
-      @origin (ahead by 1 commits, behind by 1 commits): qpsqxpyq 38ef8af7 (empty) remote-unsync
+      @origin (in sync): zwtyzrop c761c7ea (empty) remote-sync
+    remote-untrack@origin: wkxryrmm da6b996b (empty) remote-untrack
     "###);
 
+    // The deleted tracking branch is omitted, but the untracked remote branch
+    // is still listed
     insta::assert_snapshot!(
-        test_env.jj_cmd_success(&local_path, &["branch", "list", "--all"]), @r###"
-    local-only: wqnwkozp 4e887f78 (empty) local-only
-    remote-delete (deleted)
-      @origin: mnmymoky 203e60eb (empty) remote-delete
-      (this branch will be *deleted permanently* on the remote on the
-       next `jj git push`. Use `jj branch forget` to prevent this)
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--all", "--hide-deleted"]), @r###"
     remote-sync: zwtyzrop c761c7ea (empty) remote-sync
-      @origin: zwtyzrop c761c7ea (empty) remote-sync
-    remote-unsync: wqnwkozp 4e887f78 (empty) local-only
-      @origin (ahead by 1 commits, behind by 1 commits): qpsqxpyq 38ef8af7 (empty) remote-unsync
-    remote-untrack@origin: vmortlor 71a16b05 (empty) remote-untrack
+      @origin (in sync): zwtyzrop c761c7ea (empty) remote-sync
+    remote-untrack@origin: wkxryrmm da6b996b (empty) remote-untrack
     "###);
 }
 
@@ -870,7 +2885,7 @@ fn test_branch_list_filtered_by_revset() {
     let remote_path = test_env.env_root().join("remote");
     for branch in ["remote-keep", "remote-delete", "remote-rewrite"] {
         test_env.jj_cmd_ok(&remote_path, &["new", "root()", "-m", branch]);
-        test_env.jj_cmd_ok(&remote_path, &["branch", "set", branch]);
+        test_env.jj_cmd_ok(&remote_path, &["branch", "create", branch]);
     }
     test_env.jj_cmd_ok(&remote_path, &["new"]);
     test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
@@ -884,7 +2899,7 @@ fn test_branch_list_filtered_by_revset() {
     );
     let local_path = test_env.env_root().join("local");
     test_env.jj_cmd_ok(&local_path, &["new", "root()", "-m", "local-keep"]);
-    test_env.jj_cmd_ok(&local_path, &["branch", "set", "local-keep"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "create", "local-keep"]);
 
     // Mutate refs in local repository
     test_env.jj_cmd_ok(&local_path, &["branch", "delete", "remote-delete"]);
@@ -911,13 +2926,14 @@ fn test_branch_list_filtered_by_revset() {
 
     // All branches are listed by default.
     insta::assert_snapshot!(test_env.jj_cmd_success(&local_path, &["branch", "list"]), @r###"
-    local-keep: kpqxywon c7b4c09c (empty) local-keep
-    remote-delete (deleted)
+    local-keep*: kpqxywon c7b4c09c (empty) local-keep
+    remote-delete* (deleted)
       @origin: yxusvupt dad5f298 (empty) remote-delete
       (this branch will be *deleted permanently* on the remote on the
        next `jj git push`. Use `jj branch forget` to prevent this)
     remote-keep: nlwprzpn 911e9120 (empty) remote-keep
-    remote-rewrite: xyxluytn e31634b6 (empty) rewritten
+      @origin (in sync): nlwprzpn 911e9120 (empty) remote-keep
+    remote-rewrite*: xyxluytn e31634b6 (empty) rewritten
       @origin (ahead by 1 commits, behind by 1 commits): xyxluytn hidden 3e9a5af6 (empty) remote-rewrite
     "###);
 
@@ -928,24 +2944,26 @@ fn test_branch_list_filtered_by_revset() {
     // "all()" doesn't include deleted branches since they have no local targets.
     // So "all()" is identical to "branches()".
     insta::assert_snapshot!(query("all()"), @r###"
-    local-keep: kpqxywon c7b4c09c (empty) local-keep
+    local-keep*: kpqxywon c7b4c09c (empty) local-keep
     remote-keep: nlwprzpn 911e9120 (empty) remote-keep
-    remote-rewrite: xyxluytn e31634b6 (empty) rewritten
+      @origin (in sync): nlwprzpn 911e9120 (empty) remote-keep
+    remote-rewrite*: xyxluytn e31634b6 (empty) rewritten
       @origin (ahead by 1 commits, behind by 1 commits): xyxluytn hidden 3e9a5af6 (empty) remote-rewrite
     "###);
 
     // Exclude remote-only branches. "remote-rewrite@origin" is included since
     // local "remote-rewrite" target matches.
     insta::assert_snapshot!(query("branches()"), @r###"
-    local-keep: kpqxywon c7b4c09c (empty) local-keep
+    local-keep*: kpqxywon c7b4c09c (empty) local-keep
     remote-keep: nlwprzpn 911e9120 (empty) remote-keep
-    remote-rewrite: xyxluytn e31634b6 (empty) rewritten
+      @origin (in sync): nlwprzpn 911e9120 (empty) remote-keep
+    remote-rewrite*: xyxluytn e31634b6 (empty) rewritten
       @origin (ahead by 1 commits, behind by 1 commits): xyxluytn hidden 3e9a5af6 (empty) remote-rewrite
     "###);
 
     // Select branches by name.
     insta::assert_snapshot!(query("branches(remote-rewrite)"), @r###"
-    remote-rewrite: xyxluytn e31634b6 (empty) rewritten
+    remote-rewrite*: xyxluytn e31634b6 (empty) rewritten
       @origin (ahead by 1 commits, behind by 1 commits): xyxluytn hidden 3e9a5af6 (empty) remote-rewrite
     "###);
 
@@ -958,6 +2976,323 @@ fn test_branch_list_filtered_by_revset() {
     "###);
 }
 
+#[test]
+fn test_branch_list_filtered_by_remote() {
+    let test_env = TestEnvironment::default();
+
+    // Initialize remote refs
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "remote", "--git"]);
+    let remote_path = test_env.env_root().join("remote");
+    test_env.jj_cmd_ok(&remote_path, &["describe", "-m", "foo"]);
+    test_env.jj_cmd_ok(&remote_path, &["branch", "create", "foo"]);
+    test_env.jj_cmd_ok(&remote_path, &["new"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    // Initialize local refs with two remotes
+    let mut remote_git_path = remote_path.clone();
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+    );
+    let local_path = test_env.env_root().join("local");
+    test_env.jj_cmd_ok(
+        &local_path,
+        &[
+            "git",
+            "remote",
+            "add",
+            "upstream",
+            remote_git_path.to_str().unwrap(),
+        ],
+    );
+    test_env.jj_cmd_ok(&local_path, &["git", "fetch", "--remote", "upstream"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "track", "foo@upstream"]);
+
+    // Make the remotes diverge from the local branch
+    test_env.jj_cmd_ok(&remote_path, &["describe", "-m", "foo 2", "foo"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+    test_env.jj_cmd_ok(&local_path, &["git", "fetch", "--remote", "upstream"]);
+    test_env.jj_cmd_ok(&local_path, &["new", "foo", "-m", "local"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "set", "foo"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "create", "bar"]);
+    test_env.jj_cmd_ok(
+        &local_path,
+        &["git", "push", "--remote", "upstream", "--branch", "bar"],
+    );
+
+    insta::assert_snapshot!(test_env.jj_cmd_success(&local_path, &["branch", "list"]), @r###"
+    bar: wqnwkozp afa201b1 (empty) local
+      @upstream (in sync): wqnwkozp afa201b1 (empty) local
+    foo*: wqnwkozp afa201b1 (empty) local
+      @origin (ahead by 1 commits, behind by 2 commits): zonklpmq hidden b87601ef (empty) foo
+      @upstream (behind by 1 commits): rtwkwtpt dd07b58a (empty) foo 2
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--remote", "upstream"]), @r###"
+    bar: wqnwkozp afa201b1 (empty) local
+      @upstream (in sync): wqnwkozp afa201b1 (empty) local
+    foo*: wqnwkozp afa201b1 (empty) local
+      @upstream (behind by 1 commits): rtwkwtpt dd07b58a (empty) foo 2
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&local_path, &["branch", "list", "--all", "--remote", "origin"]), @r###"
+    foo*: wqnwkozp afa201b1 (empty) local
+      @origin (ahead by 1 commits, behind by 2 commits): zonklpmq hidden b87601ef (empty) foo
+    "###);
+
+    // Ahead/behind counts are capped, but a count equal to the limit is exact
+    test_env.add_config("branches.max-count = 2");
+    insta::assert_snapshot!(test_env.jj_cmd_success(&local_path, &["branch", "list"]), @r###"
+    bar: wqnwkozp afa201b1 (empty) local
+      @upstream (in sync): wqnwkozp afa201b1 (empty) local
+    foo*: wqnwkozp afa201b1 (empty) local
+      @origin (ahead by 1 commits, behind by 2 commits): zonklpmq hidden b87601ef (empty) foo
+      @upstream (behind by 1 commits): rtwkwtpt dd07b58a (empty) foo 2
+    "###);
+    test_env.add_config("branches.max-count = 1");
+    insta::assert_snapshot!(test_env.jj_cmd_success(&local_path, &["branch", "list"]), @r###"
+    bar: wqnwkozp afa201b1 (empty) local
+      @upstream (in sync): wqnwkozp afa201b1 (empty) local
+    foo*: wqnwkozp afa201b1 (empty) local
+      @origin (ahead by 1 commits, behind by 1+ commits): zonklpmq hidden b87601ef (empty) foo
+      @upstream (behind by 1 commits): rtwkwtpt dd07b58a (empty) foo 2
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&local_path, &["branch", "list", "--remote", "fork"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such remote: fork
+    Hint: Known remotes are: origin, upstream
+    "###);
+}
+
+#[test]
+fn test_branch_list_filtered_by_name() {
+    let test_env = TestEnvironment::default();
+
+    // Initialize remote refs
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "remote", "--git"]);
+    let remote_path = test_env.env_root().join("remote");
+    for branch in ["release/1", "hotfix/2"] {
+        test_env.jj_cmd_ok(&remote_path, &["new", "root()", "-m", branch]);
+        test_env.jj_cmd_ok(&remote_path, &["branch", "create", branch]);
+    }
+    test_env.jj_cmd_ok(&remote_path, &["new"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    // Initialize local refs
+    let mut remote_git_path = remote_path;
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+    );
+    let local_path = test_env.env_root().join("local");
+    test_env.jj_cmd_ok(&local_path, &["new", "root()", "-m", "local"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "create", "release/3", "feature"]);
+    test_env.jj_cmd_ok(&local_path, &["branch", "delete", "hotfix/2"]);
+
+    let query =
+        |args: &[&str]| test_env.jj_cmd_success(&local_path, &[&["branch", "list"], args].concat());
+
+    // Exact name
+    insta::assert_snapshot!(query(&["feature"]), @r###"
+    feature*: yostqsxw edcd60f7 (empty) local
+    "###);
+
+    // Multiple patterns. Remote-only branches are matched by bare name.
+    insta::assert_snapshot!(query(&["glob:release/*", "glob:hotfix/*"]), @r###"
+    hotfix/2* (deleted)
+      @origin: qvknznun 17f999c0 (empty) hotfix/2
+      (this branch will be *deleted permanently* on the remote on the
+       next `jj git push`. Use `jj branch forget` to prevent this)
+    release/1: nwuuxqtz c275a509 (empty) release/1
+      @origin (in sync): nwuuxqtz c275a509 (empty) release/1
+    release/3*: yostqsxw edcd60f7 (empty) local
+    "###);
+
+    // Names and revisions are unioned
+    insta::assert_snapshot!(query(&["feature", "-r", "release/1"]), @r###"
+    feature*: yostqsxw edcd60f7 (empty) local
+    release/1: nwuuxqtz c275a509 (empty) release/1
+      @origin (in sync): nwuuxqtz c275a509 (empty) release/1
+    "###);
+
+    // Malformed pattern
+    let stderr = test_env.jj_cmd_cli_error(&local_path, &["branch", "list", "glob:[a"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value 'glob:[a' for '[NAMES]...': Pattern syntax error near position 0: invalid range pattern
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_branch_describe_and_diff_help() {
+    let test_env = TestEnvironment::default();
+
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["branch", "describe", "-h"]);
+    insta::assert_snapshot!(stdout.lines().next().unwrap(), @"Set the description of a branch");
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["branch", "diff", "--help"]);
+    insta::assert_snapshot!(stdout.lines().take(4).collect::<Vec<_>>().join("\n"), @r###"
+    Show the commits that are in one branch but not in another
+
+    This lists the commits reachable from the local target of branch `A` but not from the one of branch
+    `B`, like `jj log -r 'B..A'`.
+    "###);
+}
+
+#[test]
+fn test_branch_diff() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "base"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "a1"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "a2"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(base)", "-m", "b1"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "b"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "diff", "a", "b"]);
+    insta::assert_snapshot!(stdout, @r###"
+    zsuskuln 7c03b5d7 a | (empty) a2
+    kkmpptxz 394bc530 (empty) a1
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "diff", "a", "b", "--both"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Commits in a but not in b:
+      zsuskuln 7c03b5d7 a | (empty) a2
+      kkmpptxz 394bc530 (empty) a1
+    Commits in b but not in a:
+      royxmykx 1c487fb6 b | (empty) b1
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["branch", "diff", "a", "a"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["branch", "diff", "a", "c"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such branch: c
+    "###);
+}
+
+#[test]
+fn test_branch_print_op_id() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    let get_head_op_id = || {
+        test_env.jj_cmd_success(
+            &repo_path,
+            &["op", "log", "--no-graph", "--limit=1", "-T=id ++ \"\\n\""],
+        )
+    };
+
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["branch", "create", "--print-op-id", "foo"]);
+    assert_eq!(stdout, get_head_op_id());
+    assert_eq!(stdout.trim().len(), 128);
+    insta::assert_snapshot!(stderr, @r###"
+    Created 1 branch.
+    "###);
+
+    let (stdout, _stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "describe", "--print-op-id", "foo", "-m=bar"],
+    );
+    assert_eq!(stdout, get_head_op_id());
+
+    // Nothing is printed if no operation was created
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "describe", "--print-op-id", "foo", "-m=bar"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+
+    // Nothing is printed without the flag
+    let (stdout, _stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "foo"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    // The operation id is logged with --verbose
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "create", "-v", "baz"]);
+    insta::assert_snapshot!(stdout, @"");
+    let op_id = get_head_op_id();
+    assert!(stderr
+        .lines()
+        .any(|line| line.contains("created operation") && line.contains(op_id.trim())));
+}
+
+#[test]
+fn test_branch_exists() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "exists", "foo"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    test_env
+        .jj_cmd(&repo_path, &["branch", "exists", "bar"])
+        .assert()
+        .code(4)
+        .stdout("")
+        .stderr("");
+
+    // Remote branches are checked separately from the local branch
+    test_env
+        .jj_cmd(&repo_path, &["branch", "exists", "foo", "--remote=git"])
+        .assert()
+        .code(4);
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "exists", "foo", "--remote=git"]);
+    test_env
+        .jj_cmd(&repo_path, &["branch", "exists", "foo", "--remote=origin"])
+        .assert()
+        .code(4)
+        .stdout("")
+        .stderr("");
+
+    test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "foo"]);
+    test_env
+        .jj_cmd(&repo_path, &["branch", "exists", "foo"])
+        .assert()
+        .code(4)
+        .stdout("")
+        .stderr("");
+}
+
+#[test]
+fn test_branch_list_color() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "foo"]);
+    test_env.add_config(r#"ui.color = "always""#);
+
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list"]), @r###"
+    [38;5;5mfoo[39m: [1m[38;5;5mq[0m[38;5;8mpvuntsm[39m [1m[38;5;4m2[0m[38;5;8m30dd059[39m [38;5;2m(empty)[39m [38;5;2m(no description set)[39m
+    "###);
+    // The --color option of the subcommand overrides the config
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--color=never"]), @r###"
+    foo: qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+    // Output isn't colored when not written to a terminal
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["branch", "list", "--color=auto"]), @r###"
+    foo: qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+    // Other commands still use the config
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tbranches"]);
+    insta::assert_snapshot!(stdout, @"[38;5;5mfoo[39m");
+}
+
 fn get_log_output(test_env: &TestEnvironment, cwd: &Path) -> String {
     let template = r#"branches ++ " " ++ commit_id.short()"#;
     test_env.jj_cmd_success(cwd, &["log", "-T", template])
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use common::TestEnvironment;
+use std::io::Write as _;
+use std::path::Path;
+
+use common::{get_stdout_string, TestEnvironment};
 use itertools::Itertools;
 
 pub mod common;
@@ -32,20 +35,16 @@ fn test_diff_basic() {
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
     insta::assert_snapshot!(stdout, @r###"
-    Removed regular file file1:
-       1     : foo
     Modified regular file file2:
        1    1: foo
             2: bar
-    Added regular file file3:
-            1: foo
+    Renamed regular file file1 to file3:
     "###);
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s"]);
     insta::assert_snapshot!(stdout, @r###"
-    R file1
     M file2
-    A file3
+    R {file1 => file3}
     "###);
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--types"]);
@@ -57,55 +56,34 @@ fn test_diff_basic() {
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
     insta::assert_snapshot!(stdout, @r###"
-    diff --git a/file1 b/file1
-    deleted file mode 100644
-    index 257cc5642c..0000000000
-    --- a/file1
-    +++ /dev/null
-    @@ -1,1 +1,0 @@
-    -foo
     diff --git a/file2 b/file2
-    index 257cc5642c...3bd1f0e297 100644
+    index 257cc5642c..3bd1f0e297 100644
     --- a/file2
     +++ b/file2
     @@ -1,1 +1,2 @@
      foo
     +bar
-    diff --git a/file3 b/file3
-    new file mode 100644
-    index 0000000000..257cc5642c
-    --- /dev/null
-    +++ b/file3
-    @@ -1,0 +1,1 @@
-    +foo
+    diff --git a/file1 b/file3
+    similarity index 100%
+    rename from file1
+    rename to file3
     "###);
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s", "--git"]);
     insta::assert_snapshot!(stdout, @r###"
-    R file1
     M file2
-    A file3
-    diff --git a/file1 b/file1
-    deleted file mode 100644
-    index 257cc5642c..0000000000
-    --- a/file1
-    +++ /dev/null
-    @@ -1,1 +1,0 @@
-    -foo
+    R {file1 => file3}
     diff --git a/file2 b/file2
-    index 257cc5642c...3bd1f0e297 100644
+    index 257cc5642c..3bd1f0e297 100644
     --- a/file2
     +++ b/file2
     @@ -1,1 +1,2 @@
      foo
     +bar
-    diff --git a/file3 b/file3
-    new file mode 100644
-    index 0000000000..257cc5642c
-    --- /dev/null
-    +++ b/file3
-    @@ -1,0 +1,1 @@
-    +foo
+    diff --git a/file1 b/file3
+    similarity index 100%
+    rename from file1
+    rename to file3
     "###);
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--stat"]);
@@ -118,523 +96,2113 @@ fn test_diff_basic() {
 }
 
 #[test]
-fn test_diff_empty() {
+fn test_diff_renames() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    std::fs::write(repo_path.join("file1"), "").unwrap();
-    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    std::fs::write(repo_path.join("exact"), "exact\n").unwrap();
+    std::fs::write(repo_path.join("similar"), "1\n2\n3\n4\n5\n6\n7\n8\n9\n").unwrap();
+    std::fs::write(repo_path.join("different"), "a\nb\nc\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::rename(repo_path.join("exact"), repo_path.join("exact-renamed")).unwrap();
+    std::fs::remove_file(repo_path.join("similar")).unwrap();
+    std::fs::write(
+        repo_path.join("similar-renamed"),
+        "1\n2\n3\n4\n5\n6\n7\nEIGHT\n9\n",
+    )
+    .unwrap();
+    std::fs::remove_file(repo_path.join("different")).unwrap();
+    std::fs::write(repo_path.join("different-renamed"), "a\nB\nC\n").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s"]);
     insta::assert_snapshot!(stdout, @r###"
-    Added regular file file1:
-        (empty)
+    R different
+    A different-renamed
+    R {exact => exact-renamed}
+    R {similar => similar-renamed}
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/different b/different
+    deleted file mode 100644
+    index de980441c3..0000000000
+    --- a/different
+    +++ /dev/null
+    @@ -1,3 +0,0 @@
+    -a
+    -b
+    -c
+    diff --git a/different-renamed b/different-renamed
+    new file mode 100644
+    index 0000000000..e642ff076b
+    --- /dev/null
+    +++ b/different-renamed
+    @@ -0,0 +1,3 @@
+    +a
+    +B
+    +C
+    diff --git a/exact b/exact-renamed
+    similarity index 100%
+    rename from exact
+    rename to exact-renamed
+    diff --git a/similar b/similar-renamed
+    similarity index 72%
+    rename from similar
+    rename to similar-renamed
+    index 0719398930..0967481208 100644
+    --- a/similar
+    +++ b/similar-renamed
+    @@ -5,5 +5,5 @@
+     5
+     6
+     7
+    -8
+    +EIGHT
+     9
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--color-words"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Removed regular file different:
+       1     : a
+       2     : b
+       3     : c
+    Added regular file different-renamed:
+            1: a
+            2: B
+            3: C
+    Renamed regular file exact to exact-renamed:
+    Renamed regular file similar to similar-renamed:
+        ...
+       5    5: 5
+       6    6: 6
+       7    7: 7
+       8    8: 8EIGHT
+       9    9: 9
     "###);
 
-    test_env.jj_cmd_ok(&repo_path, &["new"]);
-    std::fs::remove_file(repo_path.join("file1")).unwrap();
-    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    // The threshold is configurable
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["diff", "-s", "--config-toml=ui.diff.rename-threshold=95"],
+    );
     insta::assert_snapshot!(stdout, @r###"
-    Removed regular file file1:
-        (empty)
+    R different
+    A different-renamed
+    R {exact => exact-renamed}
+    R similar
+    A similar-renamed
+    "###);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["diff", "-s", "--config-toml=ui.diff.rename-threshold=101"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: invalid rename threshold: 101 (expected 0 to 100)
+    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
     "###);
 
-    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--stat"]);
+    // Renames can be disabled
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s", "--no-renames"]);
     insta::assert_snapshot!(stdout, @r###"
-    file1 | 0
-    1 file changed, 0 insertions(+), 0 deletions(-)
+    R different
+    A different-renamed
+    R exact
+    A exact-renamed
+    R similar
+    A similar-renamed
     "###);
 }
 
 #[test]
-fn test_diff_types() {
+fn test_diff_binary() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    let file_path = repo_path.join("foo");
+    // The file flips between binary and text
+    std::fs::write(repo_path.join("file"), b"\0\x01\x02\x03").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file"), "text\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file"), b"\0\x04").unwrap();
 
-    // Missing
-    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=missing"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r@--", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file b/file
+    new file mode 100644
+    index 0000000000..eaf36c1dac
+    Binary files /dev/null and b/file differ
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r@-", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file b/file
+    index eaf36c1dac..8e27be7d61 100644
+    Binary files a/file and b/file differ
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r@", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file b/file
+    index 8e27be7d61..d6db588e88 100644
+    Binary files a/file and b/file differ
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r@-", "--color-words"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Modified regular file file:
+        (binary) 4 -> 5 bytes
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r@", "--color-words"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Modified regular file file:
+        (binary) 5 -> 2 bytes
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r@", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M file
+    "###);
 
-    // Normal file
-    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=file"]);
-    std::fs::write(&file_path, "foo").unwrap();
+    // --binary emits patches that `git apply` accepts
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r@--", "--git", "--binary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file b/file
+    new file mode 100644
+    index 0000000000000000000000000000000000000000..eaf36c1daccfdf325514461cd1a2ffbc139b5464
+    GIT binary patch
+    literal 4
+    Lc${NkWMT#Y01f~L
 
-    // Conflict (add/add)
-    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=conflict"]);
-    std::fs::write(&file_path, "foo").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["new", "root()"]);
-    std::fs::write(&file_path, "bar").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["move", r#"--to=description("conflict")"#]);
+    literal 0
+    Hc$@<O00001
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r@-", "--git", "--binary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file b/file
+    index eaf36c1daccfdf325514461cd1a2ffbc139b5464..8e27be7d6154a1f68ea9160ef0e18691d20560dc 100644
+    GIT binary patch
+    literal 5
+    Mc$_OqttjCF00uV!&;S4c
+
+    literal 4
+    Lc${NkWMT#Y01f~L
+    "###);
+}
 
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        use std::path::PathBuf;
+#[test]
+fn test_diff_git_apply() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    let checkout_path = test_env.env_root().join("checkout");
+    std::fs::create_dir(&checkout_path).unwrap();
+
+    let base_files = [
+        ("modified", "a\nb\n"),
+        ("no-newline", "a"),
+        ("add-newline", "a"),
+        ("remove-newline", "a\n"),
+        ("deleted", "deleted\n"),
+        ("deleted-empty", ""),
+        ("exec", "a\n"),
+        ("exec-modified", "a\n"),
+        ("renamed", "a\nb\nc\nd\n"),
+    ];
+    for (path, content) in base_files {
+        std::fs::write(repo_path.join(path), content).unwrap();
+        std::fs::write(checkout_path.join(path), content).unwrap();
+    }
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("modified"), "a\nc\n").unwrap();
+    std::fs::write(repo_path.join("no-newline"), "b").unwrap();
+    std::fs::write(repo_path.join("add-newline"), "a\n").unwrap();
+    std::fs::write(repo_path.join("remove-newline"), "a").unwrap();
+    std::fs::remove_file(repo_path.join("deleted")).unwrap();
+    std::fs::remove_file(repo_path.join("deleted-empty")).unwrap();
+    std::fs::write(repo_path.join("added"), "added\n").unwrap();
+    std::fs::write(repo_path.join("added-empty"), "").unwrap();
+    std::fs::write(repo_path.join("added-no-newline"), "a").unwrap();
+    std::fs::write(repo_path.join("exec-modified"), "b\n").unwrap();
+    std::fs::remove_file(repo_path.join("renamed")).unwrap();
+    std::fs::write(repo_path.join("renamed-to"), "a\nb\nc\ne\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["chmod", "x", "exec", "exec-modified"]);
+
+    let patch = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
+    insta::assert_snapshot!(patch, @r###"
+    diff --git a/add-newline b/add-newline
+    index 2e65efe2a1..7898192261 100644
+    --- a/add-newline
+    +++ b/add-newline
+    @@ -1,1 +1,1 @@
+    -a
+    \ No newline at end of file
+    +a
+    diff --git a/added b/added
+    new file mode 100644
+    index 0000000000..d5f7fc3f74
+    --- /dev/null
+    +++ b/added
+    @@ -0,0 +1,1 @@
+    +added
+    diff --git a/added-empty b/added-empty
+    new file mode 100644
+    index 0000000000..e69de29bb2
+    diff --git a/added-no-newline b/added-no-newline
+    new file mode 100644
+    index 0000000000..2e65efe2a1
+    --- /dev/null
+    +++ b/added-no-newline
+    @@ -0,0 +1,1 @@
+    +a
+    \ No newline at end of file
+    diff --git a/deleted b/deleted
+    deleted file mode 100644
+    index 71779d2cab..0000000000
+    --- a/deleted
+    +++ /dev/null
+    @@ -1,1 +0,0 @@
+    -deleted
+    diff --git a/deleted-empty b/deleted-empty
+    deleted file mode 100644
+    index e69de29bb2..0000000000
+    diff --git a/exec b/exec
+    old mode 100644
+    new mode 100755
+    diff --git a/exec-modified b/exec-modified
+    old mode 100644
+    new mode 100755
+    index 7898192261..6178079822
+    --- a/exec-modified
+    +++ b/exec-modified
+    @@ -1,1 +1,1 @@
+    -a
+    +b
+    diff --git a/modified b/modified
+    index 422c2b7ab3..0f7bc76605 100644
+    --- a/modified
+    +++ b/modified
+    @@ -1,2 +1,2 @@
+     a
+    -b
+    +c
+    diff --git a/no-newline b/no-newline
+    index 2e65efe2a1..63d8dbd40c 100644
+    --- a/no-newline
+    +++ b/no-newline
+    @@ -1,1 +1,1 @@
+    -a
+    \ No newline at end of file
+    +b
+    \ No newline at end of file
+    diff --git a/remove-newline b/remove-newline
+    index 7898192261..2e65efe2a1 100644
+    --- a/remove-newline
+    +++ b/remove-newline
+    @@ -1,1 +1,1 @@
+    -a
+    +a
+    \ No newline at end of file
+    diff --git a/renamed b/renamed-to
+    similarity index 75%
+    rename from renamed
+    rename to renamed-to
+    index d68dd4031d..13847f6851 100644
+    --- a/renamed
+    +++ b/renamed-to
+    @@ -1,4 +1,4 @@
+     a
+     b
+     c
+    -d
+    +e
+    "###);
 
-        // Executable
-        test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=executable"]);
-        std::fs::write(&file_path, "foo").unwrap();
-        std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    // The patch applies cleanly to a checkout of the parent commit
+    let git_apply = |args: &[&str]| {
+        let mut child = std::process::Command::new("git")
+            .arg("apply")
+            .args(args)
+            .current_dir(&checkout_path)
+            .env("GIT_CEILING_DIRECTORIES", test_env.env_root())
+            .stdin(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        stdin.write_all(patch.as_bytes()).unwrap();
+        drop(stdin);
+        assert!(child.wait().unwrap().success());
+    };
+    git_apply(&["--check"]);
+    git_apply(&[]);
+    let read_files = |dir: &Path| {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.file_name().unwrap() != ".jj")
+            .map(|path| {
+                let content = std::fs::read(&path).unwrap();
+                (path.file_name().unwrap().to_owned(), content)
+            })
+            .sorted()
+            .collect_vec()
+    };
+    assert_eq!(read_files(&checkout_path), read_files(&repo_path));
+}
 
-        // Symlink
-        test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=symlink"]);
-        std::os::unix::fs::symlink(PathBuf::from("."), &file_path).unwrap();
+#[test]
+fn test_diff_mode_changes() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("exec"), "a\n").unwrap();
+    std::fs::write(repo_path.join("exec-modified"), "a\n").unwrap();
+    std::fs::write(repo_path.join("modified"), "a\n").unwrap();
+    std::fs::write(repo_path.join("symlink"), "target\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("exec-modified"), "b\n").unwrap();
+    std::fs::write(repo_path.join("modified"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["chmod", "x", "exec", "exec-modified"]);
+    #[cfg(unix)]
+    {
+        std::fs::remove_file(repo_path.join("symlink")).unwrap();
+        std::os::unix::fs::symlink("target", repo_path.join("symlink")).unwrap();
     }
 
-    let diff = |from: &str, to: &str| {
-        test_env.jj_cmd_success(
-            &repo_path,
-            &[
-                "diff",
-                "--types",
-                &format!(r#"--from=description("{}")"#, from),
-                &format!(r#"--to=description("{}")"#, to),
-            ],
-        )
-    };
-    insta::assert_snapshot!(diff("missing", "file"), @r###"
-    -F foo
-    "###);
-    insta::assert_snapshot!(diff("file", "conflict"), @r###"
-    FC foo
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    #[cfg(unix)]
+    insta::assert_snapshot!(stdout, @r###"
+    M exec (mode 100644 => 100755)
+    M exec-modified (mode 100644 => 100755)
+    M modified
+    T symlink (mode 100644 => 120000)
     "###);
-    insta::assert_snapshot!(diff("conflict", "missing"), @r###"
-    C- foo
+    #[cfg(not(unix))]
+    insta::assert_snapshot!(stdout, @r###"
+    M exec (mode 100644 => 100755)
+    M exec-modified (mode 100644 => 100755)
+    M modified
     "###);
 
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--stat"]);
     #[cfg(unix)]
-    {
-        insta::assert_snapshot!(diff("symlink", "file"), @r###"
-        LF foo
-        "###);
-        insta::assert_snapshot!(diff("missing", "executable"), @r###"
-        -F foo
-        "###);
-    }
+    insta::assert_snapshot!(stdout, @r###"
+    exec          | 0
+    exec-modified | 2 +-
+    modified      | 2 +-
+    symlink       | 2 +-
+    4 files changed, 3 insertions(+), 3 deletions(-)
+     mode change 100644 => 100755 exec
+     mode change 100644 => 100755 exec-modified
+     mode change 100644 => 120000 symlink
+    "###);
+    #[cfg(not(unix))]
+    insta::assert_snapshot!(stdout, @r###"
+    exec          | 0
+    exec-modified | 2 +-
+    modified      | 2 +-
+    3 files changed, 2 insertions(+), 2 deletions(-)
+     mode change 100644 => 100755 exec
+     mode change 100644 => 100755 exec-modified
+    "###);
+
+    // Mode-only changes have no content hunk
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "exec"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/exec b/exec
+    old mode 100644
+    new mode 100755
+    "###);
 }
 
 #[test]
-fn test_diff_bad_args() {
+fn test_diff_name_only() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["diff", "-s", "--types"]);
-    insta::assert_snapshot!(stderr, @r###"
-    error: the argument '--summary' cannot be used with '--types'
+    std::fs::write(repo_path.join("deleted"), "foo\n").unwrap();
+    std::fs::write(repo_path.join("modified"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::remove_file(repo_path.join("deleted")).unwrap();
+    std::fs::write(repo_path.join("modified"), "bar\n").unwrap();
+    std::fs::create_dir(repo_path.join("sub")).unwrap();
+    std::fs::write(repo_path.join("sub").join("with space"), "foo\n").unwrap();
 
-    Usage: jj diff --summary [PATHS]...
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--name-only"]);
+    insta::assert_snapshot!(stdout, @r###"
+    deleted
+    modified
+    sub/with space
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path.join("sub"), &["show", "--name-only"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Commit ID: 960629ce1e4d34e56702439c8f03ced933bc43c9
+    Change ID: rlvkpnrzqnoowoytxnquwvuryrwnrmlp
+    Author: Test User <test.user@example.com> (2001-02-03 04:05:08.000 +07:00)
+    Committer: Test User <test.user@example.com> (2001-02-03 04:05:09.000 +07:00)
 
-    For more information, try '--help'.
+        (no description set)
+
+    ../deleted
+    ../modified
+    with space
     "###);
 
-    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["diff", "--color-words", "--git"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--name-only", "-z"]);
+    insta::assert_snapshot!(stdout.replace('\0', "<NUL>"), @r###"
+    deleted<NUL>modified<NUL>sub/with space<NUL>
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["diff", "-z"]);
     insta::assert_snapshot!(stderr, @r###"
-    error: the argument '--color-words' cannot be used with '--git'
+    error: the following required arguments were not provided:
+      --name-only
 
-    Usage: jj diff --color-words [PATHS]...
+    Usage: jj diff --name-only -z [PATHS]...
 
     For more information, try '--help'.
     "###);
 }
 
 #[test]
-fn test_diff_relative_paths() {
+fn test_diff_path_globs() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    std::fs::create_dir_all(repo_path.join("dir1").join("subdir1")).unwrap();
-    std::fs::create_dir(repo_path.join("dir2")).unwrap();
-    std::fs::write(repo_path.join("file1"), "foo1\n").unwrap();
-    std::fs::write(repo_path.join("dir1").join("file2"), "foo2\n").unwrap();
-    std::fs::write(
-        repo_path.join("dir1").join("subdir1").join("file3"),
-        "foo3\n",
-    )
-    .unwrap();
-    std::fs::write(repo_path.join("dir2").join("file4"), "foo4\n").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["new"]);
-    std::fs::write(repo_path.join("file1"), "bar1\n").unwrap();
-    std::fs::write(repo_path.join("dir1").join("file2"), "bar2\n").unwrap();
-    std::fs::write(
-        repo_path.join("dir1").join("subdir1").join("file3"),
-        "bar3\n",
-    )
-    .unwrap();
-    std::fs::write(repo_path.join("dir2").join("file4"), "bar4\n").unwrap();
+    std::fs::create_dir_all(repo_path.join("sub").join("deep")).unwrap();
+    for path in [
+        "a.rs",
+        "b.txt",
+        "lit*",
+        "lit1",
+        "sub/c.rs",
+        "sub/d.txt",
+        "sub/deep/e.rs",
+    ] {
+        std::fs::write(repo_path.join(path), "foo\n").unwrap();
+    }
 
-    let stdout = test_env.jj_cmd_success(&repo_path.join("dir1"), &["diff"]);
-    #[cfg(unix)]
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s", "**/*.rs"]);
     insta::assert_snapshot!(stdout, @r###"
-    Modified regular file file2:
-       1    1: foo2bar2
-    Modified regular file subdir1/file3:
-       1    1: foo3bar3
-    Modified regular file ../dir2/file4:
-       1    1: foo4bar4
-    Modified regular file ../file1:
-       1    1: foo1bar1
+    A a.rs
+    A sub/c.rs
+    A sub/deep/e.rs
     "###);
-    #[cfg(windows)]
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s", "sub/*.rs"]);
     insta::assert_snapshot!(stdout, @r###"
-    Modified regular file file2:
-       1    1: foo2bar2
-    Modified regular file subdir1\file3:
-       1    1: foo3bar3
-    Modified regular file ..\dir2\file4:
-       1    1: foo4bar4
-    Modified regular file ..\file1:
-       1    1: foo1bar1
+    A sub/c.rs
     "###);
-
-    let stdout = test_env.jj_cmd_success(&repo_path.join("dir1"), &["diff", "-s"]);
-    #[cfg(unix)]
+    // Patterns are relative to the current directory
+    let stdout = test_env.jj_cmd_success(&repo_path.join("sub"), &["diff", "-s", "*.txt"]);
     insta::assert_snapshot!(stdout, @r###"
-    M file2
-    M subdir1/file3
-    M ../dir2/file4
-    M ../file1
+    A d.txt
     "###);
-    #[cfg(windows)]
+    // Globs and literal paths can be mixed
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s", "sub/deep", "*.txt"]);
     insta::assert_snapshot!(stdout, @r###"
-    M file2
-    M subdir1\file3
-    M ..\dir2\file4
-    M ..\file1
+    A b.txt
+    A sub/deep/e.rs
+    "###);
+
+    // Paths containing glob metacharacters can be matched literally
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s", "lit*"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A lit*
+    A lit1
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s", "--no-glob", "lit*"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A lit*
+    "###);
+    // Globs match files, not directory prefixes
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s", "--glob", "sub", "a.rs"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A a.rs
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s", "*.RS"]);
+    insta::assert_snapshot!(stdout, @"");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "diff",
+            "-s",
+            "*.RS",
+            "--config-toml=ui.path-glob-case-sensitive=false",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    A a.rs
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["diff", "-s", "sub/[a"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid glob pattern `sub/[a`: Pattern syntax error near position 0: invalid range pattern
+    "###);
+}
+
+#[test]
+fn test_diff_from_path_to_path() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::create_dir(repo_path.join("dir")).unwrap();
+    std::fs::write(repo_path.join("dir").join("old"), "a\nb\nc\n").unwrap();
+    std::fs::write(repo_path.join("other"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::remove_file(repo_path.join("dir").join("old")).unwrap();
+    std::fs::write(repo_path.join("new"), "x\ny\nc\n").unwrap();
+    std::fs::write(repo_path.join("other"), "bar\n").unwrap();
+    std::fs::write(repo_path.join("noise"), "noise\n").unwrap();
+
+    // The files are paired even if they aren't similar
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["diff", "--from-path", "dir/old", "--to-path", "new"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Renamed regular file dir/old to new:
+       1    1: ax
+       2    2: by
+       3    3: c
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "diff",
+            "--from-path=dir/old",
+            "--to-path=new",
+            "-s",
+            "--git",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    R {dir/old => new}
+    diff --git a/dir/old b/new
+    similarity index 33%
+    rename from dir/old
+    rename to new
+    index de980441c3..4ba248f281 100644
+    --- a/dir/old
+    +++ b/new
+    @@ -1,3 +1,3 @@
+    -a
+    -b
+    +x
+    +y
+     c
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "diff",
+            "--from-path=dir/old",
+            "--to-path=new",
+            "--no-renames",
+            "-s",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    R {dir/old => new}
+    "###);
+    // The same file can be compared between two revisions
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "diff",
+            "--from=@-",
+            "--from-path=other",
+            "--to-path=other",
+            "-s",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    M other
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["diff", "--from-path=new", "--to-path=new"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Path new doesn't exist in the parents of revision @
+    "###);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "diff",
+            "--from=@-",
+            "--to=@-",
+            "--from-path=dir",
+            "--to-path=other",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Path dir is a directory in revision @-
+    "###);
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["diff", "--from-path=new"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the following required arguments were not provided:
+      --to-path <PATH>
+
+    Usage: jj diff --from-path <PATH> --to-path <PATH> [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["diff", "--from-path=new", "--to-path=new", "other"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--from-path <PATH>' cannot be used with '[PATHS]...'
+
+    Usage: jj diff --from-path <PATH> --to-path <PATH> [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+    // The glob flags don't apply to --from-path and --to-path
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["diff", "--from-path=new", "--to-path=new", "--glob"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--from-path <PATH>' cannot be used with '--glob'
+
+    Usage: jj diff --from-path <PATH> --to-path <PATH> [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["diff", "--from-path=new", "--to-path=new", "--no-glob"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--from-path <PATH>' cannot be used with '--no-glob'
+
+    Usage: jj diff --from-path <PATH> --to-path <PATH> [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_diff_from_path_to_path_formats() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("old"), "a\nb\nc\n").unwrap();
+    std::fs::write(repo_path.join("empty-old"), "").unwrap();
+    std::fs::write(repo_path.join("link-old"), "target\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::remove_file(repo_path.join("old")).unwrap();
+    std::fs::write(repo_path.join("new"), "x\nb\nc\n").unwrap();
+    std::fs::remove_file(repo_path.join("empty-old")).unwrap();
+    std::fs::write(repo_path.join("empty-new"), "").unwrap();
+    std::fs::remove_file(repo_path.join("link-old")).unwrap();
+
+    // Only the two files are shown in any format
+    let diff = |args: &[&str]| {
+        let mut all_args = vec!["diff", "--from-path=old", "--to-path=new"];
+        all_args.extend(args);
+        test_env.jj_cmd_success(&repo_path, &all_args)
+    };
+    insta::assert_snapshot!(diff(&["--stat"]), @r###"
+    new | 2 +-
+    1 file changed, 1 insertion(+), 1 deletion(-)
+    "###);
+    insta::assert_snapshot!(diff(&["--types"]), @r###"
+    FF new
+    "###);
+    insta::assert_snapshot!(diff(&["--name-only"]), @r###"
+    new
+    "###);
+    insta::assert_snapshot!(diff(&["--no-renames", "--git"]), @r###"
+    diff --git a/old b/new
+    similarity index 66%
+    rename from old
+    rename to new
+    index de980441c3..de109a6390 100644
+    --- a/old
+    +++ b/new
+    @@ -1,3 +1,3 @@
+    -a
+    +x
+     b
+     c
+    "###);
+
+    // Empty files are compared too
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "diff",
+            "--from-path=empty-old",
+            "--to-path=empty-new",
+            "-s",
+            "--git",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    R {empty-old => empty-new}
+    diff --git a/empty-old b/empty-new
+    similarity index 100%
+    rename from empty-old
+    rename to empty-new
+    "###);
+
+    // A file can be compared with a symlink
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink("target", repo_path.join("link-new")).unwrap();
+        let stdout = test_env.jj_cmd_success(
+            &repo_path,
+            &[
+                "diff",
+                "--from-path=link-old",
+                "--to-path=link-new",
+                "-s",
+                "--git",
+            ],
+        );
+        insta::assert_snapshot!(stdout, @r###"
+        R {link-old => link-new} (mode 100644 => 120000)
+        diff --git a/link-old b/link-new
+        old mode 100644
+        new mode 120000
+        similarity index 0%
+        rename from link-old
+        rename to link-new
+        index eb5a316cbd..1de565933b
+        --- a/link-old
+        +++ b/link-new
+        @@ -1,1 +1,1 @@
+        -target
+        +target
+        \ No newline at end of file
+        "###);
+    }
+}
+
+#[test]
+fn test_diff_empty() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Added regular file file1:
+        (empty)
+    "###);
+
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::remove_file(repo_path.join("file1")).unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Removed regular file file1:
+        (empty)
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--stat"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file1 | 0
+    1 file changed, 0 insertions(+), 0 deletions(-)
+    "###);
+}
+
+#[test]
+fn test_diff_conflicts() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "line 1\nline 2\nline 3\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=base"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=side1"]);
+    std::fs::write(repo_path.join("file"), "line 1\nline 2 a\nline 3\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(base)", "-m=side2"]);
+    std::fs::write(repo_path.join("file"), "line 1\nline 2 b\nline 3\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "description(side1)", "description(side2)"],
+    );
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["diff", "--from=description(base)", "--summary"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    C file
+    "###);
+
+    // Each side of the conflict is diffed against the base
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--from=description(base)"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Created conflict in file:
+      Changes from base to side #1:
+       1    1: line 1
+       2    2: line 2 a
+       3    3: line 3
+      Changes from base to side #2:
+       1    1: line 1
+       2    2: line 2 b
+       3    3: line 3
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["diff", "--from=description(base)", "--color=always"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [38;5;3mCreated conflict in file:[39m
+    [38;5;1m  Changes from base to side #1:[39m
+    [38;5;1m   1[39m [38;5;2m   1[39m: line 1
+    [38;5;1m   2[39m [38;5;2m   2[39m: line 2[38;5;2m a[39m
+    [38;5;1m   3[39m [38;5;2m   3[39m: line 3
+    [38;5;1m  Changes from base to side #2:[39m
+    [38;5;1m   1[39m [38;5;2m   1[39m: line 1
+    [38;5;1m   2[39m [38;5;2m   2[39m: line 2[38;5;2m b[39m
+    [38;5;1m   3[39m [38;5;2m   3[39m: line 3
+    "###);
+
+    // Resolving the conflict is diffed against its materialized form
+    std::fs::write(repo_path.join("file"), "line 1\nline 2 ab\nline 3\n").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M file
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Resolved conflict in file:
+       1    1: line 1
+       2     : <<<<<<<
+       3     : %%%%%%%
+       4    2: -line 2
+       5    2: +line 2 aab
+       6     : +++++++
+       7     : line 2 b
+       8     : >>>>>>>
+       9    3: line 3
+    "###);
+}
+
+#[test]
+fn test_diff_types() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let file_path = repo_path.join("foo");
+
+    // Missing
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=missing"]);
+
+    // Normal file
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=file"]);
+    std::fs::write(&file_path, "foo").unwrap();
+
+    // Conflict (add/add)
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=conflict"]);
+    std::fs::write(&file_path, "foo").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()"]);
+    std::fs::write(&file_path, "bar").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["move", r#"--to=description("conflict")"#]);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        use std::path::PathBuf;
+
+        // Executable
+        test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=executable"]);
+        std::fs::write(&file_path, "foo").unwrap();
+        std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        // Symlink
+        test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m=symlink"]);
+        std::os::unix::fs::symlink(PathBuf::from("."), &file_path).unwrap();
+    }
+
+    let diff = |from: &str, to: &str| {
+        test_env.jj_cmd_success(
+            &repo_path,
+            &[
+                "diff",
+                "--types",
+                &format!(r#"--from=description("{}")"#, from),
+                &format!(r#"--to=description("{}")"#, to),
+            ],
+        )
+    };
+    insta::assert_snapshot!(diff("missing", "file"), @r###"
+    -F foo
+    "###);
+    insta::assert_snapshot!(diff("file", "conflict"), @r###"
+    FC foo
+    "###);
+    insta::assert_snapshot!(diff("conflict", "missing"), @r###"
+    C- foo
     "###);
 
-    let stdout = test_env.jj_cmd_success(&repo_path.join("dir1"), &["diff", "--types"]);
     #[cfg(unix)]
+    {
+        insta::assert_snapshot!(diff("symlink", "file"), @r###"
+        LF foo
+        "###);
+        insta::assert_snapshot!(diff("missing", "executable"), @r###"
+        -F foo
+        "###);
+    }
+}
+
+#[test]
+fn test_diff_bad_args() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["diff", "-s", "--types"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--summary' cannot be used with '--types'
+
+    Usage: jj diff --summary [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["diff", "--color-words", "--git"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--color-words' cannot be used with '--git'
+
+    Usage: jj diff --color-words [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_diff_relative_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::create_dir_all(repo_path.join("dir1").join("subdir1")).unwrap();
+    std::fs::create_dir(repo_path.join("dir2")).unwrap();
+    std::fs::write(repo_path.join("file1"), "foo1\n").unwrap();
+    std::fs::write(repo_path.join("dir1").join("file2"), "foo2\n").unwrap();
+    std::fs::write(
+        repo_path.join("dir1").join("subdir1").join("file3"),
+        "foo3\n",
+    )
+    .unwrap();
+    std::fs::write(repo_path.join("dir2").join("file4"), "foo4\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "bar1\n").unwrap();
+    std::fs::write(repo_path.join("dir1").join("file2"), "bar2\n").unwrap();
+    std::fs::write(
+        repo_path.join("dir1").join("subdir1").join("file3"),
+        "bar3\n",
+    )
+    .unwrap();
+    std::fs::write(repo_path.join("dir2").join("file4"), "bar4\n").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path.join("dir1"), &["diff"]);
+    #[cfg(unix)]
+    insta::assert_snapshot!(stdout, @r###"
+    Modified regular file file2:
+       1    1: foo2bar2
+    Modified regular file subdir1/file3:
+       1    1: foo3bar3
+    Modified regular file ../dir2/file4:
+       1    1: foo4bar4
+    Modified regular file ../file1:
+       1    1: foo1bar1
+    "###);
+    #[cfg(windows)]
+    insta::assert_snapshot!(stdout, @r###"
+    Modified regular file file2:
+       1    1: foo2bar2
+    Modified regular file subdir1\file3:
+       1    1: foo3bar3
+    Modified regular file ..\dir2\file4:
+       1    1: foo4bar4
+    Modified regular file ..\file1:
+       1    1: foo1bar1
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&repo_path.join("dir1"), &["diff", "-s"]);
+    #[cfg(unix)]
+    insta::assert_snapshot!(stdout, @r###"
+    M file2
+    M subdir1/file3
+    M ../dir2/file4
+    M ../file1
+    "###);
+    #[cfg(windows)]
+    insta::assert_snapshot!(stdout, @r###"
+    M file2
+    M subdir1\file3
+    M ..\dir2\file4
+    M ..\file1
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&repo_path.join("dir1"), &["diff", "--types"]);
+    #[cfg(unix)]
+    insta::assert_snapshot!(stdout, @r###"
+    FF file2
+    FF subdir1/file3
+    FF ../dir2/file4
+    FF ../file1
+    "###);
+    #[cfg(windows)]
+    insta::assert_snapshot!(stdout, @r###"
+    FF file2
+    FF subdir1\file3
+    FF ..\dir2\file4
+    FF ..\file1
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&repo_path.join("dir1"), &["diff", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/dir1/file2 b/dir1/file2
+    index 54b060eee9..1fe912cdd8 100644
+    --- a/dir1/file2
+    +++ b/dir1/file2
+    @@ -1,1 +1,1 @@
+    -foo2
+    +bar2
+    diff --git a/dir1/subdir1/file3 b/dir1/subdir1/file3
+    index c1ec6c6f12..f3c8b75ec6 100644
+    --- a/dir1/subdir1/file3
+    +++ b/dir1/subdir1/file3
+    @@ -1,1 +1,1 @@
+    -foo3
+    +bar3
+    diff --git a/dir2/file4 b/dir2/file4
+    index a0016dbc4c..17375f7a12 100644
+    --- a/dir2/file4
+    +++ b/dir2/file4
+    @@ -1,1 +1,1 @@
+    -foo4
+    +bar4
+    diff --git a/file1 b/file1
+    index 1715acd6a5..05c4fe6772 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,1 +1,1 @@
+    -foo1
+    +bar1
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&repo_path.join("dir1"), &["diff", "--stat"]);
+    #[cfg(unix)]
+    insta::assert_snapshot!(stdout, @r###"
+    file2         | 2 +-
+    subdir1/file3 | 2 +-
+    ../dir2/file4 | 2 +-
+    ../file1      | 2 +-
+    4 files changed, 4 insertions(+), 4 deletions(-)
+    "###);
+    #[cfg(windows)]
+    insta::assert_snapshot!(stdout, @r###"
+    file2         | 2 +-
+    subdir1\file3 | 2 +-
+    ..\dir2\file4 | 2 +-
+    ..\file1      | 2 +-
+    4 files changed, 4 insertions(+), 4 deletions(-)
+    "###);
+}
+
+#[test]
+fn test_diff_missing_newline() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo").unwrap();
+    std::fs::write(repo_path.join("file2"), "foo\nbar").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "foo\nbar").unwrap();
+    std::fs::write(repo_path.join("file2"), "foo").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Modified regular file file1:
+       1    1: foo
+            2: bar
+    Modified regular file file2:
+       1    1: foo
+       2     : bar
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    index 1910281566..a907ec3f43 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,1 +1,2 @@
+    -foo
+    \ No newline at end of file
+    +foo
+    +bar
+    \ No newline at end of file
+    diff --git a/file2 b/file2
+    index a907ec3f43..1910281566 100644
+    --- a/file2
+    +++ b/file2
+    @@ -1,2 +1,1 @@
+    -foo
+    -bar
+    \ No newline at end of file
+    +foo
+    \ No newline at end of file
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--stat"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file1 | 3 ++-
+    file2 | 3 +--
+    2 files changed, 3 insertions(+), 3 deletions(-)
+    "###);
+}
+
+#[test]
+fn test_color_words_diff_missing_newline() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Empty"]);
+    std::fs::write(repo_path.join("file1"), "a\nb\nc\nd\ne\nf\ng\nh\ni").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Add no newline"]);
+    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\ne\nf\ng\nh\ni").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Modify first line"]);
+    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\nE\nf\ng\nh\ni").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Modify middle line"]);
+    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\nE\nf\ng\nh\nI").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Modify last line"]);
+    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\nE\nf\ng\nh\nI\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Append newline"]);
+    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\nE\nf\ng\nh\nI").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Remove newline"]);
+    std::fs::write(repo_path.join("file1"), "").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Empty"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-Tdescription",
+            "-pr::@-",
+            "--no-graph",
+            "--reversed",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    === Empty
+    Added regular file file1:
+        (empty)
+    === Add no newline
+    Modified regular file file1:
+            1: a
+            2: b
+            3: c
+            4: d
+            5: e
+            6: f
+            7: g
+            8: h
+            9: i
+    === Modify first line
+    Modified regular file file1:
+       1    1: aA
+       2    2: b
+       3    3: c
+       4    4: d
+        ...
+    === Modify middle line
+    Modified regular file file1:
+       1    1: A
+       2    2: b
+       3    3: c
+       4    4: d
+       5    5: eE
+       6    6: f
+       7    7: g
+       8    8: h
+       9    9: i
+    === Modify last line
+    Modified regular file file1:
+        ...
+       6    6: f
+       7    7: g
+       8    8: h
+       9    9: iI
+    === Append newline
+    Modified regular file file1:
+        ...
+       6    6: f
+       7    7: g
+       8    8: h
+       9    9: I
+    === Remove newline
+    Modified regular file file1:
+        ...
+       6    6: f
+       7    7: g
+       8    8: h
+       9    9: I
+    === Empty
+    Modified regular file file1:
+       1     : A
+       2     : b
+       3     : c
+       4     : d
+       5     : E
+       6     : f
+       7     : g
+       8     : h
+       9     : I
+    "###);
+}
+
+#[test]
+fn test_diff_skipped_context() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\nb\nc\nd\ne\nf\ng\nh\ni\nj").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "=== Left side of diffs"]);
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "@", "-m", "=== Must skip 2 lines"]);
+    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\ne\nf\ng\nh\ni\nJ").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "@-", "-m", "=== Don't skip 1 line"]);
+    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\ne\nf\ng\nh\nI\nj").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "@-", "-m", "=== No gap to skip"]);
+    std::fs::write(repo_path.join("file1"), "a\nB\nc\nd\ne\nf\ng\nh\nI\nj").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "@-", "-m", "=== No gap to skip"]);
+    std::fs::write(repo_path.join("file1"), "a\nb\nC\nd\ne\nf\ng\nh\nI\nj").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "@-", "-m", "=== 1 line at start"]);
+    std::fs::write(repo_path.join("file1"), "a\nb\nc\nd\nE\nf\ng\nh\ni\nj").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "@-", "-m", "=== 1 line at end"]);
+    std::fs::write(repo_path.join("file1"), "a\nb\nc\nd\ne\nF\ng\nh\ni\nj").unwrap();
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-Tdescription", "-p", "--no-graph", "--reversed"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    === Left side of diffs
+    Added regular file file1:
+            1: a
+            2: b
+            3: c
+            4: d
+            5: e
+            6: f
+            7: g
+            8: h
+            9: i
+           10: j
+    === Must skip 2 lines
+    Modified regular file file1:
+       1    1: aA
+       2    2: b
+       3    3: c
+       4    4: d
+        ...
+       7    7: g
+       8    8: h
+       9    9: i
+      10   10: jJ
+    === Don't skip 1 line
+    Modified regular file file1:
+       1    1: aA
+       2    2: b
+       3    3: c
+       4    4: d
+       5    5: e
+       6    6: f
+       7    7: g
+       8    8: h
+       9    9: iI
+      10   10: j
+    === No gap to skip
+    Modified regular file file1:
+       1    1: a
+       2    2: bB
+       3    3: c
+       4    4: d
+       5    5: e
+       6    6: f
+       7    7: g
+       8    8: h
+       9    9: iI
+      10   10: j
+    === No gap to skip
+    Modified regular file file1:
+       1    1: a
+       2    2: b
+       3    3: cC
+       4    4: d
+       5    5: e
+       6    6: f
+       7    7: g
+       8    8: h
+       9    9: iI
+      10   10: j
+    === 1 line at start
+    Modified regular file file1:
+       1    1: a
+       2    2: b
+       3    3: c
+       4    4: d
+       5    5: eE
+       6    6: f
+       7    7: g
+       8    8: h
+        ...
+    === 1 line at end
+    Modified regular file file1:
+        ...
+       3    3: c
+       4    4: d
+       5    5: e
+       6    6: fF
+       7    7: g
+       8    8: h
+       9    9: i
+      10   10: j
+    "###);
+}
+
+#[test]
+fn test_diff_word_diff() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(
+        repo_path.join("file1"),
+        "first line\nThe quick brown fox.\nunchanged\nold line\n",
+    )
+    .unwrap();
+    std::fs::write(repo_path.join("file2"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(
+        repo_path.join("file1"),
+        "first line\nThe slow brown fox!\nunchanged\nnew line\nadded line\n",
+    )
+    .unwrap();
+    std::fs::remove_file(repo_path.join("file2")).unwrap();
+    std::fs::write(repo_path.join("file3"), "bar").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--word-diff"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    index 23eda4fd7e..ac6abf114a 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,4 +1,5 @@
+    first line
+    The [-quick-]{+slow+} brown fox[-.-]{+!+}
+    unchanged
+    [-old-]{+new+} line
+    {+added line+}
+    diff --git a/file2 b/file2
+    deleted file mode 100644
+    index 257cc5642c..0000000000
+    --- a/file2
+    +++ /dev/null
+    @@ -1,1 +0,0 @@
+    [-foo-]
+    diff --git a/file3 b/file3
+    new file mode 100644
+    index 0000000000..ba0e162e1c
+    --- /dev/null
+    +++ b/file3
+    @@ -0,0 +1,1 @@
+    {+bar+}
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--word-diff", "--color=always"]);
+    insta::assert_snapshot!(stdout, @r###"
+    [1mdiff --git a/file1 b/file1[0m
+    [1mindex 23eda4fd7e..ac6abf114a 100644[0m
+    [1m--- a/file1[0m
+    [1m+++ b/file1[0m
+    [38;5;6m@@ -1,4 +1,5 @@[39m
+    first line
+    The [38;5;1m[-quick-][38;5;2m{+slow+}[39m brown fox[38;5;1m[-.-][38;5;2m{+!+}[39m
+    unchanged
+    [38;5;1m[-old-][38;5;2m{+new+}[39m line
+    [38;5;2m{+added line+}[39m
+    [1mdiff --git a/file2 b/file2[0m
+    [1mdeleted file mode 100644[0m
+    [1mindex 257cc5642c..0000000000[0m
+    [1m--- a/file2[0m
+    [1m+++ /dev/null[0m
+    [38;5;6m@@ -1,1 +0,0 @@[39m
+    [38;5;1m[-foo-][39m
+    [1mdiff --git a/file3 b/file3[0m
+    [1mnew file mode 100644[0m
+    [1mindex 0000000000..ba0e162e1c[0m
+    [1m--- /dev/null[0m
+    [1m+++ b/file3[0m
+    [38;5;6m@@ -0,0 +1,1 @@[39m
+    [38;5;2m{+bar+}[39m
+    "###);
+}
+
+#[test]
+fn test_color_words_diff_chars() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(
+        repo_path.join("file1"),
+        "let targets = compute_branch_target();\nfoo\n",
+    )
+    .unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(
+        repo_path.join("file1"),
+        "let targets = compute_branch_targets();\nbar\n",
+    )
+    .unwrap();
+
+    // Only the changed characters of similar words are highlighted. Words with
+    // nothing in common are highlighted as a whole.
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--color=always"]);
     insta::assert_snapshot!(stdout, @r###"
-    FF file2
-    FF subdir1/file3
-    FF ../dir2/file4
-    FF ../file1
+    [38;5;3mModified regular file file1:[39m
+    [38;5;1m   1[39m [38;5;2m   1[39m: let targets = [38;5;1mcompute_branch_target[38;5;2mcompute_branch_target[4ms[24m[39m();
+    [38;5;1m   2[39m [38;5;2m   2[39m: [38;5;1mfoo[38;5;2mbar[39m
     "###);
-    #[cfg(windows)]
+}
+
+#[test]
+fn test_diff_ignore_whitespace() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo {\n    bar;\n}\nbaz {}\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "a b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(
+        repo_path.join("file1"),
+        "\
+foo {
+        bar;
+}
+baz {  }
+new
+",
+    )
+    .unwrap();
+    std::fs::write(repo_path.join("file2"), "a  b \n").unwrap();
+
+    // Git format
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "--ignore-all-space"]);
     insta::assert_snapshot!(stdout, @r###"
-    FF file2
-    FF subdir1\file3
-    FF ..\dir2\file4
-    FF ..\file1
+    diff --git a/file1 b/file1
+    index f532aa68ad..2b275f26d8 100644
+    --- a/file1
+    +++ b/file1
+    @@ -2,3 +2,4 @@
+             bar;
+     }
+     baz {  }
+    +new
+    diff --git a/file2 b/file2
+    index b2901ea97c..56ecf9fed9 100644
+    --- a/file2
+    +++ b/file2
     "###);
-
-    let stdout = test_env.jj_cmd_success(&repo_path.join("dir1"), &["diff", "--git"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "--ignore-space-change"]);
     insta::assert_snapshot!(stdout, @r###"
-    diff --git a/dir1/file2 b/dir1/file2
-    index 54b060eee9...1fe912cdd8 100644
-    --- a/dir1/file2
-    +++ b/dir1/file2
-    @@ -1,1 +1,1 @@
-    -foo2
-    +bar2
-    diff --git a/dir1/subdir1/file3 b/dir1/subdir1/file3
-    index c1ec6c6f12...f3c8b75ec6 100644
-    --- a/dir1/subdir1/file3
-    +++ b/dir1/subdir1/file3
-    @@ -1,1 +1,1 @@
-    -foo3
-    +bar3
-    diff --git a/dir2/file4 b/dir2/file4
-    index a0016dbc4c...17375f7a12 100644
-    --- a/dir2/file4
-    +++ b/dir2/file4
-    @@ -1,1 +1,1 @@
-    -foo4
-    +bar4
     diff --git a/file1 b/file1
-    index 1715acd6a5...05c4fe6772 100644
+    index f532aa68ad..2b275f26d8 100644
     --- a/file1
     +++ b/file1
-    @@ -1,1 +1,1 @@
-    -foo1
-    +bar1
+    @@ -1,4 +1,5 @@
+     foo {
+             bar;
+     }
+    -baz {}
+    +baz {  }
+    +new
+    diff --git a/file2 b/file2
+    index b2901ea97c..56ecf9fed9 100644
+    --- a/file2
+    +++ b/file2
     "###);
 
-    let stdout = test_env.jj_cmd_success(&repo_path.join("dir1"), &["diff", "--stat"]);
-    #[cfg(unix)]
+    // Color-words format
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--ignore-all-space"]);
     insta::assert_snapshot!(stdout, @r###"
-    file2         | 2 +-
-    subdir1/file3 | 2 +-
-    ../dir2/file4 | 2 +-
-    ../file1      | 2 +-
-    4 files changed, 4 insertions(+), 4 deletions(-)
+    Modified regular file file1:
+       1    1: foo {
+       2    2:         bar;
+       3    3: }
+       4    4: baz {  }
+            5: new
+    Modified regular file file2:
     "###);
-    #[cfg(windows)]
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--ignore-space-change"]);
     insta::assert_snapshot!(stdout, @r###"
-    file2         | 2 +-
-    subdir1\file3 | 2 +-
-    ..\dir2\file4 | 2 +-
-    ..\file1      | 2 +-
-    4 files changed, 4 insertions(+), 4 deletions(-)
+    Modified regular file file1:
+       1    1: foo {
+       2    2:         bar;
+       3    3: }
+       4    4: baz {  }
+            5: new
+    Modified regular file file2:
+    "###);
+
+    // The summary is unaffected
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary", "--ignore-all-space"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M file1
+    M file2
     "###);
 }
 
 #[test]
-fn test_diff_missing_newline() {
+fn test_diff_color_moved() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    std::fs::write(repo_path.join("file1"), "foo").unwrap();
-    std::fs::write(repo_path.join("file2"), "foo\nbar").unwrap();
+    std::fs::write(
+        repo_path.join("file1"),
+        "fn foo() {\n    call_the_first_function();\n    call_the_second_function();\n}\n\nfn \
+         bar() {}\nx = 1\n",
+    )
+    .unwrap();
     test_env.jj_cmd_ok(&repo_path, &["new"]);
-    std::fs::write(repo_path.join("file1"), "foo\nbar").unwrap();
-    std::fs::write(repo_path.join("file2"), "foo").unwrap();
+    // foo() is moved to another file, and the short line isn't considered moved
+    std::fs::write(repo_path.join("file1"), "fn bar() {}\n").unwrap();
+    std::fs::write(
+        repo_path.join("file2"),
+        "x = 1\nfn foo() {\n    call_the_first_function();\n    call_the_second_function();\n}\n",
+    )
+    .unwrap();
 
-    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    // Moved lines aren't detected by default
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "--color=always"]);
+    insta::assert_snapshot!(stdout, @r###"
+    [1mdiff --git a/file1 b/file1[0m
+    [1mindex 990032578e..a16f090854 100644[0m
+    [1m--- a/file1[0m
+    [1m+++ b/file1[0m
+    [38;5;6m@@ -1,7 +1,1 @@[39m
+    [38;5;1m-fn foo() {[39m
+    [38;5;1m-    call_the_first_function();[39m
+    [38;5;1m-    call_the_second_function();[39m
+    [38;5;1m-}[39m
+    [38;5;1m-[39m
+     fn bar() {}
+    [38;5;1m-x = 1[39m
+    [1mdiff --git a/file2 b/file2[0m
+    [1mnew file mode 100644[0m
+    [1mindex 0000000000..b3751fe78b[0m
+    [1m--- /dev/null[0m
+    [1m+++ b/file2[0m
+    [38;5;6m@@ -0,0 +1,5 @@[39m
+    [38;5;2m+x = 1[39m
+    [38;5;2m+fn foo() {[39m
+    [38;5;2m+    call_the_first_function();[39m
+    [38;5;2m+    call_the_second_function();[39m
+    [38;5;2m+}[39m
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["diff", "--git", "--color=always", "--color-moved"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [1mdiff --git a/file1 b/file1[0m
+    [1mindex 990032578e..a16f090854 100644[0m
+    [1m--- a/file1[0m
+    [1m+++ b/file1[0m
+    [38;5;6m@@ -1,7 +1,1 @@[39m
+    [1m[38;5;5m-fn foo() {[0m
+    [1m[38;5;5m-    call_the_first_function();[0m
+    [1m[38;5;5m-    call_the_second_function();[0m
+    [1m[38;5;5m-}[0m
+    [38;5;1m-[39m
+     fn bar() {}
+    [38;5;1m-x = 1[39m
+    [1mdiff --git a/file2 b/file2[0m
+    [1mnew file mode 100644[0m
+    [1mindex 0000000000..b3751fe78b[0m
+    [1m--- /dev/null[0m
+    [1m+++ b/file2[0m
+    [38;5;6m@@ -0,0 +1,5 @@[39m
+    [38;5;2m+x = 1[39m
+    [1m[38;5;6m+fn foo() {[0m
+    [1m[38;5;6m+    call_the_first_function();[0m
+    [1m[38;5;6m+    call_the_second_function();[0m
+    [1m[38;5;6m+}[0m
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["diff", "--color-words", "--color=always", "--color-moved"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [38;5;3mModified regular file file1:[39m
+    [1m[38;5;5m   1     : fn foo() {[0m
+    [1m[38;5;5m   2     :     call_the_first_function();[0m
+    [1m[38;5;5m   3     :     call_the_second_function();[0m
+    [1m[38;5;5m   4     : }[0m
+    [38;5;1m   5[39m     : [38;5;1m[39m
+    [38;5;1m   6[39m [38;5;2m   1[39m: fn bar() {}
+    [38;5;1m   7[39m     : [38;5;1mx = 1[39m
+    [38;5;3mAdded regular file file2:[39m
+         [38;5;2m   1[39m: [38;5;2mx = 1[39m
+    [1m[38;5;6m        2: fn foo() {[0m
+    [1m[38;5;6m        3:     call_the_first_function();[0m
+    [1m[38;5;6m        4:     call_the_second_function();[0m
+    [1m[38;5;6m        5: }[0m
+    "###);
+
+    // The output is the same without color
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "--color-moved"]);
+    assert_eq!(
+        stdout,
+        test_env.jj_cmd_success(&repo_path, &["diff", "--git"])
+    );
+}
+
+#[test]
+fn test_diff_ws_error_highlight() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a \nb\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "a \nb  \n \tc\n").unwrap();
+
+    // Whitespace errors aren't highlighted by default
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "--color=always"]);
+    insta::assert_snapshot!(stdout, @r###"
+    [1mdiff --git a/file1 b/file1[0m
+    [1mindex afa826fb50..eefae990ee 100644[0m
+    [1m--- a/file1[0m
+    [1m+++ b/file1[0m
+    [38;5;6m@@ -1,2 +1,3 @@[39m
+     a 
+    [38;5;1m-b[39m
+    [38;5;2m+b  [39m
+    [38;5;2m+ 	c[39m
+    "###);
+    // Only the added lines are highlighted, not the context
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["diff", "--git", "--color=always", "--ws-error-highlight"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [1mdiff --git a/file1 b/file1[0m
+    [1mindex afa826fb50..eefae990ee 100644[0m
+    [1m--- a/file1[0m
+    [1m+++ b/file1[0m
+    [38;5;6m@@ -1,2 +1,3 @@[39m
+     a 
+    [38;5;1m-b[39m
+    [38;5;2m+b[48;5;1m  [49m[39m
+    [38;5;2m+[48;5;1m 	[49mc[39m
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "diff",
+            "--color-words",
+            "--color=always",
+            "--config-toml=ui.diff.highlight-whitespace=true",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [38;5;3mModified regular file file1:[39m
+    [38;5;1m   1[39m [38;5;2m   1[39m: a 
+    [38;5;1m   2[39m [38;5;2m   2[39m: b[38;5;2m[48;5;1m  [39m[49m
+         [38;5;2m   3[39m: [38;5;2m[48;5;1m 	[49mc[39m
+    "###);
+}
+
+#[test]
+fn test_diff_context() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "a\nb\nc\nd\nE\nf\ng\nh\ni\nj\n").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "--context=1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    index 92dfa21641..d5a68b2a8a 100644
+    --- a/file1
+    +++ b/file1
+    @@ -4,3 +4,3 @@
+     d
+    -e
+    +E
+     f
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "--context=5"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    index 92dfa21641..d5a68b2a8a 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,10 +1,10 @@
+     a
+     b
+     c
+     d
+    -e
+    +E
+     f
+     g
+     h
+     i
+     j
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--color-words", "--context=1"]);
     insta::assert_snapshot!(stdout, @r###"
     Modified regular file file1:
-       1    1: foo
-            2: bar
-    Modified regular file file2:
-       1    1: foo
-       2     : bar
+        ...
+       4    4: d
+       5    5: eE
+       6    6: f
+        ...
+    "###);
+    // The default format also respects --context
+    test_env.add_config(r#"ui.diff.format = "git""#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--context=0"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    index 92dfa21641..d5a68b2a8a 100644
+    --- a/file1
+    +++ b/file1
+    @@ -5,1 +5,1 @@
+    -e
+    +E
     "###);
 
-    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
+    // Hunks whose context would overlap are merged
+    std::fs::write(repo_path.join("file1"), "a\nB\nc\nd\nE\nf\ng\nh\ni\nJ\n").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-U1"]);
     insta::assert_snapshot!(stdout, @r###"
     diff --git a/file1 b/file1
-    index 1910281566...a907ec3f43 100644
+    index 92dfa21641..c5cdd20f65 100644
     --- a/file1
     +++ b/file1
-    @@ -1,1 +1,2 @@
-    -foo
-    \ No newline at end of file
-    +foo
-    +bar
-    \ No newline at end of file
-    diff --git a/file2 b/file2
-    index a907ec3f43...1910281566 100644
-    --- a/file2
-    +++ b/file2
-    @@ -1,2 +1,1 @@
-    -foo
-    -bar
-    \ No newline at end of file
-    +foo
-    \ No newline at end of file
+    @@ -1,6 +1,6 @@
+     a
+    -b
+    +B
+     c
+     d
+    -e
+    +E
+     f
+    @@ -9,2 +9,2 @@
+     i
+    -j
+    +J
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-U2"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    index 92dfa21641..c5cdd20f65 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,10 +1,10 @@
+     a
+    -b
+    +B
+     c
+     d
+    -e
+    +E
+     f
+     g
+     h
+     i
+    -j
+    +J
     "###);
 
-    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--stat"]);
+    // Pure insertions and deletions without context refer to the line before
+    std::fs::write(repo_path.join("file1"), "a\nb\nnew\nc\nd\nf\ng\nh\ni\nj\n").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-U0"]);
     insta::assert_snapshot!(stdout, @r###"
-    file1 | 3 ++-
-    file2 | 3 +--
-    2 files changed, 3 insertions(+), 3 deletions(-)
+    diff --git a/file1 b/file1
+    index 92dfa21641..4b0017e71f 100644
+    --- a/file1
+    +++ b/file1
+    @@ -2,0 +3,1 @@
+    +new
+    @@ -5,1 +5,0 @@
+    -e
+    "###);
+
+    // log reuses the same diff options
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-r@", "--no-graph", "-T", r#""""#, "-p", "-U1"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    index 92dfa21641..4b0017e71f 100644
+    --- a/file1
+    +++ b/file1
+    @@ -2,5 +2,5 @@
+     b
+    +new
+     c
+     d
+    -e
+     f
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["diff", "-U1000000"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value '1000000' for '--context <CONTEXT>': must be at most 100000
+
+    For more information, try '--help'.
     "###);
 }
 
 #[test]
-fn test_color_words_diff_missing_newline() {
+fn test_diff_format_per_command() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    std::fs::write(repo_path.join("file1"), "").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Empty"]);
-    std::fs::write(repo_path.join("file1"), "a\nb\nc\nd\ne\nf\ng\nh\ni").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Add no newline"]);
-    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\ne\nf\ng\nh\ni").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Modify first line"]);
-    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\nE\nf\ng\nh\ni").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Modify middle line"]);
-    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\nE\nf\ng\nh\nI").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Modify last line"]);
-    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\nE\nf\ng\nh\nI\n").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Append newline"]);
-    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\nE\nf\ng\nh\nI").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Remove newline"]);
-    std::fs::write(repo_path.join("file1"), "").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "=== Empty"]);
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+
+    // Built-in default
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Added regular file file1:
+            1: foo
+    "###);
+
+    // Generic config
+    test_env.add_config(r#"ui.diff.format = "summary""#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A file1
+    "###);
+
+    // Per-command config overrides the generic config
+    test_env.add_config(
+        r#"
+        ui.diff.format-for.diff = "stat"
+        ui.diff.format-for.show = "git"
+        "#,
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file1 | 1 +
+    1 file changed, 1 insertion(+), 0 deletions(-)
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["show"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Commit ID: a75cc5f7570a3474e5abfe4e178452c915dd0cb2
+    Change ID: qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu
+    Author: Test User <test.user@example.com> (2001-02-03 04:05:07.000 +07:00)
+    Committer: Test User <test.user@example.com> (2001-02-03 04:05:08.000 +07:00)
+
+        (no description set)
+
+    diff --git a/file1 b/file1
+    new file mode 100644
+    index 0000000000..257cc5642c
+    --- /dev/null
+    +++ b/file1
+    @@ -0,0 +1,1 @@
+    +foo
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r@", "-T", r#""""#, "-p"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @
+    │  A file1
+    ~
+    "###);
+
+    // Command-line flags override the per-command config
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--types"]);
+    insta::assert_snapshot!(stdout, @r###"
+    -F file1
+    "###);
 
+    // Per-command config overrides the external diff tool
     let stdout = test_env.jj_cmd_success(
         &repo_path,
-        &[
-            "log",
-            "-Tdescription",
-            "-pr::@-",
-            "--no-graph",
-            "--reversed",
-        ],
+        &["diff", "--config-toml=ui.diff.tool=['false']"],
     );
     insta::assert_snapshot!(stdout, @r###"
-    === Empty
-    Added regular file file1:
-        (empty)
-    === Add no newline
-    Modified regular file file1:
-            1: a
-            2: b
-            3: c
-            4: d
-            5: e
-            6: f
-            7: g
-            8: h
-            9: i
-    === Modify first line
-    Modified regular file file1:
-       1    1: aA
-       2    2: b
-       3    3: c
-       4    4: d
-        ...
-    === Modify middle line
-    Modified regular file file1:
-       1    1: A
-       2    2: b
-       3    3: c
-       4    4: d
-       5    5: eE
-       6    6: f
-       7    7: g
-       8    8: h
-       9    9: i
-    === Modify last line
-    Modified regular file file1:
-        ...
-       6    6: f
-       7    7: g
-       8    8: h
-       9    9: iI
-    === Append newline
-    Modified regular file file1:
-        ...
-       6    6: f
-       7    7: g
-       8    8: h
-       9    9: I
-    === Remove newline
-    Modified regular file file1:
-        ...
-       6    6: f
-       7    7: g
-       8    8: h
-       9    9: I
-    === Empty
-    Modified regular file file1:
-       1     : A
-       2     : b
-       3     : c
-       4     : d
-       5     : E
-       6     : f
-       7     : g
-       8     : h
-       9     : I
+    file1 | 1 +
+    1 file changed, 1 insertion(+), 0 deletions(-)
+    "###);
+
+    // Invalid value
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["show", "--config-toml=ui.diff.format-for.show='patch'"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: invalid diff format in ui.diff.format-for.show: patch (expected one of color-words, git, stat, summary, types, word-diff)
+    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
+    "###);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["log", "-p", "--config-toml=ui.diff.format='patch'"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: invalid diff format in ui.diff.format: patch (expected one of color-words, git, stat, summary, types, word-diff)
+    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
     "###);
 }
 
 #[test]
-fn test_diff_skipped_context() {
+fn test_diff_max_file_size() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    std::fs::write(repo_path.join("file1"), "a\nb\nc\nd\ne\nf\ng\nh\ni\nj").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "=== Left side of diffs"]);
+    let large_content = (1..=20).map(|i| format!("line {i}\n")).collect::<String>();
+    std::fs::write(repo_path.join("large"), &large_content).unwrap();
+    std::fs::write(repo_path.join("small"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    let large_content = large_content.replace("line 10\n", "line ten\n");
+    std::fs::write(repo_path.join("large"), &large_content).unwrap();
+    std::fs::write(repo_path.join("small"), "b\n").unwrap();
+    std::fs::write(repo_path.join("added"), "c\n".repeat(1000)).unwrap();
+    test_env.add_config(r#"ui.diff.max-file-size = "100B""#);
 
-    test_env.jj_cmd_ok(&repo_path, &["new", "@", "-m", "=== Must skip 2 lines"]);
-    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\ne\nf\ng\nh\ni\nJ").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["new", "@-", "-m", "=== Don't skip 1 line"]);
-    std::fs::write(repo_path.join("file1"), "A\nb\nc\nd\ne\nf\ng\nh\nI\nj").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["new", "@-", "-m", "=== No gap to skip"]);
-    std::fs::write(repo_path.join("file1"), "a\nB\nc\nd\ne\nf\ng\nh\nI\nj").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["new", "@-", "-m", "=== No gap to skip"]);
-    std::fs::write(repo_path.join("file1"), "a\nb\nC\nd\ne\nf\ng\nh\nI\nj").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["new", "@-", "-m", "=== 1 line at start"]);
-    std::fs::write(repo_path.join("file1"), "a\nb\nc\nd\nE\nf\ng\nh\ni\nj").unwrap();
-    test_env.jj_cmd_ok(&repo_path, &["new", "@-", "-m", "=== 1 line at end"]);
-    std::fs::write(repo_path.join("file1"), "a\nb\nc\nd\ne\nF\ng\nh\ni\nj").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Added regular file added:
+        (file too large to diff: more than 100.0B, see ui.diff.max-file-size)
+    Modified regular file large:
+        (file too large to diff: more than 100.0B, see ui.diff.max-file-size)
+    Modified regular file small:
+       1    1: ab
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/added b/added
+    new file mode 100644
+    index 0000000000..b742950811
+    (file too large to diff: more than 100.0B, see ui.diff.max-file-size)
+    diff --git a/large b/large
+    index c4352f8b46..be8344cad5 100644
+    (file too large to diff: more than 100.0B, see ui.diff.max-file-size)
+    diff --git a/small b/small
+    index 7898192261..6178079822 100644
+    --- a/small
+    +++ b/small
+    @@ -1,1 +1,1 @@
+    -a
+    +b
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A added
+    M large
+    M small
+    "###);
+    // Large files are reported by size like binary files
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--stat"]);
+    insta::assert_snapshot!(stdout, @r###"
+    added | Bin 0 -> 2000 bytes
+    large | Bin 151 -> 152 bytes
+    small | 2 +-
+    3 files changed, 1 insertion(+), 1 deletion(-)
+    "###);
 
+    // The limit can be turned off
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["diff", "--git", "--no-size-limit", "large"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/large b/large
+    index c4352f8b46..be8344cad5 100644
+    --- a/large
+    +++ b/large
+    @@ -7,7 +7,7 @@
+     line 7
+     line 8
+     line 9
+    -line 10
+    +line ten
+     line 11
+     line 12
+     line 13
+    "###);
     let stdout = test_env.jj_cmd_success(
         &repo_path,
-        &["log", "-Tdescription", "-p", "--no-graph", "--reversed"],
+        &[
+            "diff",
+            "--git",
+            "--config-toml=ui.diff.max-file-size='0'",
+            "large",
+        ],
     );
     insta::assert_snapshot!(stdout, @r###"
-    === Left side of diffs
-    Added regular file file1:
-            1: a
-            2: b
-            3: c
-            4: d
-            5: e
-            6: f
-            7: g
-            8: h
-            9: i
-           10: j
-    === Must skip 2 lines
-    Modified regular file file1:
-       1    1: aA
-       2    2: b
-       3    3: c
-       4    4: d
-        ...
-       7    7: g
-       8    8: h
-       9    9: i
-      10   10: jJ
-    === Don't skip 1 line
-    Modified regular file file1:
-       1    1: aA
-       2    2: b
-       3    3: c
-       4    4: d
-       5    5: e
-       6    6: f
-       7    7: g
-       8    8: h
-       9    9: iI
-      10   10: j
-    === No gap to skip
-    Modified regular file file1:
-       1    1: a
-       2    2: bB
-       3    3: c
-       4    4: d
-       5    5: e
-       6    6: f
-       7    7: g
-       8    8: h
-       9    9: iI
-      10   10: j
-    === No gap to skip
-    Modified regular file file1:
-       1    1: a
-       2    2: b
-       3    3: cC
-       4    4: d
-       5    5: e
-       6    6: f
-       7    7: g
-       8    8: h
-       9    9: iI
-      10   10: j
-    === 1 line at start
-    Modified regular file file1:
-       1    1: a
-       2    2: b
-       3    3: c
-       4    4: d
-       5    5: eE
-       6    6: f
-       7    7: g
-       8    8: h
-        ...
-    === 1 line at end
-    Modified regular file file1:
-        ...
-       3    3: c
-       4    4: d
-       5    5: e
-       6    6: fF
-       7    7: g
-       8    8: h
-       9    9: i
-      10   10: j
+    diff --git a/large b/large
+    index c4352f8b46..be8344cad5 100644
+    --- a/large
+    +++ b/large
+    @@ -7,7 +7,7 @@
+     line 7
+     line 8
+     line 9
+    -line 10
+    +line ten
+     line 11
+     line 12
+     line 13
+    "###);
+
+    // The contents of large files aren't compared to detect renames, but
+    // identical files are still paired
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::rename(repo_path.join("large"), repo_path.join("large-edited")).unwrap();
+    std::fs::write(
+        repo_path.join("large-edited"),
+        large_content.replace("line 1\n", "line one\n"),
+    )
+    .unwrap();
+    std::fs::rename(repo_path.join("added"), repo_path.join("added-moved")).unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    R {added => added-moved}
+    R large
+    A large-edited
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary", "--no-size-limit"]);
+    insta::assert_snapshot!(stdout, @r###"
+    R {added => added-moved}
+    R {large => large-edited}
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["diff", "--config-toml=ui.diff.max-file-size='large'"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: must start with a number
+    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
     "###);
 }
 
@@ -783,6 +2351,120 @@ fn test_diff_stat() {
     file1 | 1 -
     1 file changed, 0 insertions(+), 1 deletion(-)
     "###);
+
+    // Binary files are reported without line counts
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "baz\n").unwrap();
+    std::fs::write(repo_path.join("file2"), b"\0binary\n").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--stat"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file1 | 2 +-
+    file2 | Bin 0 -> 8 bytes
+    2 files changed, 1 insertion(+), 1 deletion(-)
+    "###);
+}
+
+#[test]
+fn test_diff_dirstat() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    let write_lines = |path: &str, num_lines: usize| {
+        let path = repo_path.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "line\n".repeat(num_lines)).unwrap();
+    };
+    write_lines("top", 1);
+    write_lines("a/file", 1);
+    write_lines("a/b/c/d/file1", 1);
+    write_lines("a/b/c/file2", 2);
+    write_lines("x/y/file", 1);
+    std::fs::write(repo_path.join("x/binary"), b"\0binary\n").unwrap();
+
+    // Files deeper than the depth are counted towards their ancestor. (The
+    // summary is shown so the indentation of the percentages is kept in the
+    // snapshots.)
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary", "--dirstat"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A a/b/c/d/file1
+    A a/b/c/file2
+    A a/file
+    A top
+    A x/binary
+    A x/y/file
+      16.7% ./
+      16.7% a/
+      50.0% a/b/c/
+      16.6% x/y/
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary", "--dirstat=1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A a/b/c/d/file1
+    A a/b/c/file2
+    A a/file
+    A top
+    A x/binary
+    A x/y/file
+      16.7% ./
+      66.7% a/
+      16.6% x/
+    "###);
+
+    // Directories are shown relative to the current directory
+    let stdout =
+        test_env.jj_cmd_success(&repo_path.join("x"), &["diff", "--summary", "--dirstat=2"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A ../a/b/c/d/file1
+    A ../a/b/c/file2
+    A ../a/file
+    A ../top
+    A binary
+    A y/file
+      16.7% ../
+      16.7% ../a/
+      50.0% ../a/b/
+      16.6% y/
+    "###);
+
+    // Can be combined with --stat
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--stat", "--dirstat=0"]);
+    insta::assert_snapshot!(stdout, @r###"
+    a/b/c/d/file1 | 1 +
+    a/b/c/file2   | 2 ++
+    a/file        | 1 +
+    top           | 1 +
+    x/binary      | Bin 0 -> 8 bytes
+    x/y/file      | 1 +
+    6 files changed, 6 insertions(+), 0 deletions(-)
+     100.0% ./
+    "###);
+
+    // The percentages add up to 100% even if they are rounded
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    write_lines("d1/file", 1);
+    write_lines("d2/file", 1);
+    write_lines("d3/file", 1);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary", "--dirstat"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A d1/file
+    A d2/file
+    A d3/file
+      33.4% d1/
+      33.3% d2/
+      33.3% d3/
+    "###);
+    let total: f64 = stdout
+        .lines()
+        .filter(|line| line.contains('%'))
+        .map(|line| line.trim_start().split('%').next().unwrap())
+        .map(|percentage| percentage.parse::<f64>().unwrap())
+        .sum();
+    assert!((total - 100.0).abs() < 0.01, "total: {total}");
+
+    // Nothing is shown if no lines changed
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--dirstat"]);
+    insta::assert_snapshot!(stdout, @"");
 }
 
 #[test]
@@ -898,3 +2580,74 @@ fn test_diff_stat_long_name_or_stat() {
     2 files changed, 20 insertions(+), 0 deletions(-)
     "###);
 }
+
+#[test]
+fn test_diff_many_files() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Enough files that the diffs are computed in several batches
+    for i in 0..300 {
+        let content = (0..20)
+            .map(|line| format!("file {i} line {line}\n"))
+            .join("");
+        std::fs::write(repo_path.join(format!("file{i:03}")), content).unwrap();
+    }
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    for i in 0..300 {
+        let path = repo_path.join(format!("file{i:03}"));
+        match i % 4 {
+            0 => {
+                let content = std::fs::read_to_string(&path).unwrap();
+                std::fs::write(&path, content.replace(" line 7", " modified line")).unwrap();
+            }
+            1 => std::fs::remove_file(&path).unwrap(),
+            2 => std::fs::write(repo_path.join(format!("added{i:03}")), format!("{i}\n")).unwrap(),
+            _ => {}
+        }
+    }
+
+    // The output doesn't depend on the number of threads the diffs are
+    // computed by
+    let diff_with_threads = |args: &[&str], num_threads: &str| {
+        let assert = test_env
+            .jj_cmd(&repo_path, args)
+            .env("RAYON_NUM_THREADS", num_threads)
+            .assert()
+            .success();
+        get_stdout_string(&assert)
+    };
+    for args in [
+        &["diff", "--git"][..],
+        &["diff", "--git", "--color=always"],
+        &["diff", "--word-diff"],
+        &["diff", "--color-words", "--color=always"],
+        &["diff", "--stat"],
+        &["diff", "--stat", "--dirstat"],
+        &["show", "--git"],
+    ] {
+        let serial = diff_with_threads(args, "1");
+        assert_eq!(serial, diff_with_threads(args, "8"), "args: {args:?}");
+        assert_eq!(
+            serial,
+            test_env.jj_cmd_success(&repo_path, args),
+            "args: {args:?}"
+        );
+    }
+
+    // Files are listed in path order
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
+    let headers = stdout
+        .lines()
+        .filter(|line| line.starts_with("diff --git"))
+        .collect_vec();
+    assert_eq!(headers.len(), 225);
+    assert!(headers.windows(2).all(|pair| pair[0] < pair[1]));
+    insta::assert_snapshot!(headers[..4].join("\n"), @r###"
+    diff --git a/added002 b/added002
+    diff --git a/added006 b/added006
+    diff --git a/added010 b/added010
+    diff --git a/added014 b/added014
+    "###);
+}
@@ -437,10 +437,10 @@ fn test_diffedit_old_restore_interactive_tests() {
     index 7898192261..0000000000
     --- a/file1
     +++ /dev/null
-    @@ -1,1 +1,0 @@
+    @@ -1,1 +0,0 @@
     -a
     diff --git a/file2 b/file2
-    index 7898192261...6178079822 100644
+    index 7898192261..6178079822 100644
     --- a/file2
     +++ b/file2
     @@ -1,1 +1,1 @@
@@ -451,7 +451,7 @@ fn test_diffedit_old_restore_interactive_tests() {
     index 0000000000..c21c9352f7
     --- /dev/null
     +++ b/file3
-    @@ -1,0 +1,1 @@
+    @@ -0,0 +1,1 @@
     +unrelated
     "###);
 }
@@ -236,8 +236,8 @@ fn test_git_clone_colocate() {
     );
     insta::assert_snapshot!(stdout, @r###"
     main: mzyxwzks 9f01a0e0 message
-      @git: mzyxwzks 9f01a0e0 message
-      @origin: mzyxwzks 9f01a0e0 message
+      @git (in sync): mzyxwzks 9f01a0e0 message
+      @origin (in sync): mzyxwzks 9f01a0e0 message
     "###);
 
     // Subsequent fetch should just work even if the source path was relative
@@ -83,7 +83,7 @@ fn test_git_fetch_default_remote() {
     test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     origin: oputwtnw ffecd2d6 message
-      @origin: oputwtnw ffecd2d6 message
+      @origin (in sync): oputwtnw ffecd2d6 message
     "###);
 }
 
@@ -101,7 +101,7 @@ fn test_git_fetch_single_remote() {
         .stderr("Fetching from the only existing remote: rem1\n");
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     rem1: qxosxrvv 6a211027 message
-      @rem1: qxosxrvv 6a211027 message
+      @rem1 (in sync): qxosxrvv 6a211027 message
     "###);
 }
 
@@ -118,7 +118,7 @@ fn test_git_fetch_single_remote_all_remotes_flag() {
         .success();
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     rem1: qxosxrvv 6a211027 message
-      @rem1: qxosxrvv 6a211027 message
+      @rem1 (in sync): qxosxrvv 6a211027 message
     "###);
 }
 
@@ -132,7 +132,7 @@ fn test_git_fetch_single_remote_from_arg() {
     test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote", "rem1"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     rem1: qxosxrvv 6a211027 message
-      @rem1: qxosxrvv 6a211027 message
+      @rem1 (in sync): qxosxrvv 6a211027 message
     "###);
 }
 
@@ -147,7 +147,7 @@ fn test_git_fetch_single_remote_from_config() {
     test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     rem1: qxosxrvv 6a211027 message
-      @rem1: qxosxrvv 6a211027 message
+      @rem1 (in sync): qxosxrvv 6a211027 message
     "###);
 }
 
@@ -165,9 +165,9 @@ fn test_git_fetch_multiple_remotes() {
     );
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     rem1: qxosxrvv 6a211027 message
-      @rem1: qxosxrvv 6a211027 message
+      @rem1 (in sync): qxosxrvv 6a211027 message
     rem2: yszkquru 2497a8a0 message
-      @rem2: yszkquru 2497a8a0 message
+      @rem2 (in sync): yszkquru 2497a8a0 message
     "###);
 }
 
@@ -182,9 +182,9 @@ fn test_git_fetch_all_remotes() {
     test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--all-remotes"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     rem1: qxosxrvv 6a211027 message
-      @rem1: qxosxrvv 6a211027 message
+      @rem1 (in sync): qxosxrvv 6a211027 message
     rem2: yszkquru 2497a8a0 message
-      @rem2: yszkquru 2497a8a0 message
+      @rem2 (in sync): yszkquru 2497a8a0 message
     "###);
 }
 
@@ -200,9 +200,9 @@ fn test_git_fetch_multiple_remotes_from_config() {
     test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     rem1: qxosxrvv 6a211027 message
-      @rem1: qxosxrvv 6a211027 message
+      @rem1 (in sync): qxosxrvv 6a211027 message
     rem2: yszkquru 2497a8a0 message
-      @rem2: yszkquru 2497a8a0 message
+      @rem2 (in sync): yszkquru 2497a8a0 message
     "###);
 }
 
@@ -275,8 +275,8 @@ fn test_git_fetch_from_remote_named_git() {
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["branch", "list", "--all"]);
     insta::assert_snapshot!(stdout, @r###"
     git: mrylzrtu 76fc7466 message
-      @bar: mrylzrtu 76fc7466 message
-      @git: mrylzrtu 76fc7466 message
+      @bar (in sync): mrylzrtu 76fc7466 message
+      @git (in sync): mrylzrtu 76fc7466 message
     "###);
     insta::assert_snapshot!(stderr, @r###"
     Done importing changes from the underlying Git repo.
@@ -292,7 +292,7 @@ fn test_git_fetch_prune_before_updating_tips() {
     test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     origin: oputwtnw ffecd2d6 message
-      @origin: oputwtnw ffecd2d6 message
+      @origin (in sync): oputwtnw ffecd2d6 message
     "###);
 
     // Remove origin branch in git repo and create origin/subname
@@ -306,7 +306,7 @@ fn test_git_fetch_prune_before_updating_tips() {
     test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     origin/subname: oputwtnw ffecd2d6 message
-      @origin: oputwtnw ffecd2d6 message
+      @origin (in sync): oputwtnw ffecd2d6 message
     "###);
 }
 
@@ -330,7 +330,7 @@ fn test_git_fetch_conflicting_branches() {
     );
     // This should result in a CONFLICTED branch
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    rem1 (conflicted):
+    rem1* (conflicted):
       + kkmpptxz fcdbbd73 (empty) (no description set)
       + qxosxrvv 6a211027 message
       @rem1 (behind by 1 commits): qxosxrvv 6a211027 message
@@ -352,7 +352,7 @@ fn test_git_fetch_conflicting_branches_colocated() {
     test_env.jj_cmd_ok(&repo_path, &["branch", "create", "rem1"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     rem1: zsuskuln f652c321 (empty) (no description set)
-      @git: zsuskuln f652c321 (empty) (no description set)
+      @git (in sync): zsuskuln f652c321 (empty) (no description set)
     "###);
 
     test_env.jj_cmd_ok(
@@ -362,7 +362,7 @@ fn test_git_fetch_conflicting_branches_colocated() {
     // This should result in a CONFLICTED branch
     // See https://github.com/martinvonz/jj/pull/1146#discussion_r1112372340 for the bug this tests for.
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    rem1 (conflicted):
+    rem1* (conflicted):
       + zsuskuln f652c321 (empty) (no description set)
       + qxosxrvv 6a211027 message
       @git (behind by 1 commits): zsuskuln f652c321 (empty) (no description set)
@@ -440,13 +440,13 @@ fn test_git_fetch_all() {
     insta::assert_snapshot!(stderr, @"");
     insta::assert_snapshot!(get_branch_output(&test_env, &target_jj_repo_path), @r###"
     a1: nknoxmzm 359a9a02 descr_for_a1
-      @origin: nknoxmzm 359a9a02 descr_for_a1
+      @origin (in sync): nknoxmzm 359a9a02 descr_for_a1
     a2: qkvnknrk decaa396 descr_for_a2
-      @origin: qkvnknrk decaa396 descr_for_a2
+      @origin (in sync): qkvnknrk decaa396 descr_for_a2
     b: vpupmnsl c7d4bdcb descr_for_b
-      @origin: vpupmnsl c7d4bdcb descr_for_b
+      @origin (in sync): vpupmnsl c7d4bdcb descr_for_b
     trunk1: zowqyktl ff36dc55 descr_for_trunk1
-      @origin: zowqyktl ff36dc55 descr_for_trunk1
+      @origin (in sync): zowqyktl ff36dc55 descr_for_trunk1
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &target_jj_repo_path), @r###"
     ◉  c7d4bdcbc215 descr_for_b b
@@ -494,13 +494,13 @@ fn test_git_fetch_all() {
     "###);
     insta::assert_snapshot!(get_branch_output(&test_env, &target_jj_repo_path), @r###"
     a1: nknoxmzm 359a9a02 descr_for_a1
-      @origin: nknoxmzm 359a9a02 descr_for_a1
+      @origin (in sync): nknoxmzm 359a9a02 descr_for_a1
     a2: qkvnknrk decaa396 descr_for_a2
-      @origin: qkvnknrk decaa396 descr_for_a2
-    b: vpupmnsl 061eddbb new_descr_for_b_to_create_conflict
+      @origin (in sync): qkvnknrk decaa396 descr_for_a2
+    b*: vpupmnsl 061eddbb new_descr_for_b_to_create_conflict
       @origin (ahead by 1 commits, behind by 1 commits): vpupmnsl hidden c7d4bdcb descr_for_b
     trunk1: zowqyktl ff36dc55 descr_for_trunk1
-      @origin: zowqyktl ff36dc55 descr_for_trunk1
+      @origin (in sync): zowqyktl ff36dc55 descr_for_trunk1
     "###);
     let (stdout, stderr) = test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(stdout, @"");
@@ -509,18 +509,18 @@ fn test_git_fetch_all() {
     "###);
     insta::assert_snapshot!(get_branch_output(&test_env, &target_jj_repo_path), @r###"
     a1: quxllqov 0424f6df descr_for_a1
-      @origin: quxllqov 0424f6df descr_for_a1
+      @origin (in sync): quxllqov 0424f6df descr_for_a1
     a2: osusxwst 91e46b4b descr_for_a2
-      @origin: osusxwst 91e46b4b descr_for_a2
-    b (conflicted):
+      @origin (in sync): osusxwst 91e46b4b descr_for_a2
+    b* (conflicted):
       - vpupmnsl hidden c7d4bdcb descr_for_b
       + vpupmnsl 061eddbb new_descr_for_b_to_create_conflict
       + vktnwlsu babc4922 descr_for_b
       @origin (behind by 1 commits): vktnwlsu babc4922 descr_for_b
     trunk1: zowqyktl ff36dc55 descr_for_trunk1
-      @origin: zowqyktl ff36dc55 descr_for_trunk1
+      @origin (in sync): zowqyktl ff36dc55 descr_for_trunk1
     trunk2: umznmzko 8f1f14fb descr_for_trunk2
-      @origin: umznmzko 8f1f14fb descr_for_trunk2
+      @origin (in sync): umznmzko 8f1f14fb descr_for_trunk2
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &target_jj_repo_path), @r###"
     ◉  babc49226c14 descr_for_b b?? b@origin
@@ -602,7 +602,7 @@ fn test_git_fetch_some_of_many_branches() {
     // ...check what the intermediate state looks like...
     insta::assert_snapshot!(get_branch_output(&test_env, &target_jj_repo_path), @r###"
     b: vpupmnsl c7d4bdcb descr_for_b
-      @origin: vpupmnsl c7d4bdcb descr_for_b
+      @origin (in sync): vpupmnsl c7d4bdcb descr_for_b
     "###);
     // ...then fetch two others with a glob.
     let (stdout, stderr) = test_env.jj_cmd_ok(
@@ -699,10 +699,10 @@ fn test_git_fetch_some_of_many_branches() {
     // We left a2 where it was before, let's see how `jj branch list` sees this.
     insta::assert_snapshot!(get_branch_output(&test_env, &target_jj_repo_path), @r###"
     a1: ypowunwp 6df2d34c descr_for_a1
-      @origin: ypowunwp 6df2d34c descr_for_a1
+      @origin (in sync): ypowunwp 6df2d34c descr_for_a1
     a2: qkvnknrk decaa396 descr_for_a2
-      @origin: qkvnknrk decaa396 descr_for_a2
-    b (conflicted):
+      @origin (in sync): qkvnknrk decaa396 descr_for_a2
+    b* (conflicted):
       - vpupmnsl hidden c7d4bdcb descr_for_b
       + vpupmnsl 6ebd41dc new_descr_for_b_to_create_conflict
       + nxrpswuq 01d11519 descr_for_b
@@ -734,10 +734,10 @@ fn test_git_fetch_some_of_many_branches() {
     "###);
     insta::assert_snapshot!(get_branch_output(&test_env, &target_jj_repo_path), @r###"
     a1: ypowunwp 6df2d34c descr_for_a1
-      @origin: ypowunwp 6df2d34c descr_for_a1
+      @origin (in sync): ypowunwp 6df2d34c descr_for_a1
     a2: qrmzolkr 31c7d94b descr_for_a2
-      @origin: qrmzolkr 31c7d94b descr_for_a2
-    b (conflicted):
+      @origin (in sync): qrmzolkr 31c7d94b descr_for_a2
+    b* (conflicted):
       - vpupmnsl hidden c7d4bdcb descr_for_b
       + vpupmnsl 6ebd41dc new_descr_for_b_to_create_conflict
       + nxrpswuq 01d11519 descr_for_b
@@ -863,7 +863,7 @@ fn test_fetch_undo_what() {
     "###);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     b: vpupmnsl c7d4bdcb descr_for_b
-      @origin: vpupmnsl c7d4bdcb descr_for_b
+      @origin (in sync): vpupmnsl c7d4bdcb descr_for_b
     "###);
 
     // We can undo the change in the repo without moving the remote-tracking branch
@@ -874,7 +874,7 @@ fn test_fetch_undo_what() {
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @"");
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    b (deleted)
+    b* (deleted)
       @origin: vpupmnsl hidden c7d4bdcb descr_for_b
       (this branch will be *deleted permanently* on the remote on the
        next `jj git push`. Use `jj branch forget` to prevent this)
@@ -884,11 +884,11 @@ fn test_fetch_undo_what() {
     // change our local repo state...
     test_env.jj_cmd_ok(&repo_path, &["branch", "c", "newbranch"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    b (deleted)
+    b* (deleted)
       @origin: vpupmnsl hidden c7d4bdcb descr_for_b
       (this branch will be *deleted permanently* on the remote on the
        next `jj git push`. Use `jj branch forget` to prevent this)
-    newbranch: qpvuntsm 230dd059 (empty) (no description set)
+    newbranch*: qpvuntsm 230dd059 (empty) (no description set)
     "###);
     // Restoring just the remote-tracking state will not affect `newbranch`, but
     // will eliminate `b@origin`.
@@ -916,14 +916,14 @@ fn test_git_fetch_remove_fetch() {
     let repo_path = test_env.env_root().join("repo");
     add_git_remote(&test_env, &repo_path, "origin");
 
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "origin"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "origin"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     origin: qpvuntsm 230dd059 (empty) (no description set)
     "###);
 
     test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    origin (conflicted):
+    origin* (conflicted):
       + qpvuntsm 230dd059 (empty) (no description set)
       + oputwtnw ffecd2d6 message
       @origin (behind by 1 commits): oputwtnw ffecd2d6 message
@@ -943,7 +943,7 @@ fn test_git_fetch_remove_fetch() {
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @"");
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    origin (conflicted):
+    origin* (conflicted):
       + qpvuntsm 230dd059 (empty) (no description set)
       + oputwtnw ffecd2d6 message
       @origin (behind by 1 commits): oputwtnw ffecd2d6 message
@@ -957,14 +957,14 @@ fn test_git_fetch_rename_fetch() {
     let repo_path = test_env.env_root().join("repo");
     add_git_remote(&test_env, &repo_path, "origin");
 
-    test_env.jj_cmd_ok(&repo_path, &["branch", "set", "origin"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "origin"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     origin: qpvuntsm 230dd059 (empty) (no description set)
     "###);
 
     test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    origin (conflicted):
+    origin* (conflicted):
       + qpvuntsm 230dd059 (empty) (no description set)
       + oputwtnw ffecd2d6 message
       @origin (behind by 1 commits): oputwtnw ffecd2d6 message
@@ -975,7 +975,7 @@ fn test_git_fetch_rename_fetch() {
         &["git", "remote", "rename", "origin", "upstream"],
     );
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    origin (conflicted):
+    origin* (conflicted):
       + qpvuntsm 230dd059 (empty) (no description set)
       + oputwtnw ffecd2d6 message
       @upstream (behind by 1 commits): oputwtnw ffecd2d6 message
@@ -1185,7 +1185,7 @@ fn test_git_fetch_remote_only_branch() {
     test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote=origin"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     feature1: mzyxwzks 9f01a0e0 message
-      @origin: mzyxwzks 9f01a0e0 message
+      @origin (in sync): mzyxwzks 9f01a0e0 message
     "###);
 
     git_repo
@@ -1210,7 +1210,7 @@ fn test_git_fetch_remote_only_branch() {
     "###);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     feature1: mzyxwzks 9f01a0e0 message
-      @origin: mzyxwzks 9f01a0e0 message
+      @origin (in sync): mzyxwzks 9f01a0e0 message
     feature2@origin: mzyxwzks 9f01a0e0 message
     "###);
 }
@@ -152,7 +152,7 @@ fn test_git_import_undo() {
     insta::assert_snapshot!(stderr, @"");
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     a: qpvuntsm 230dd059 (empty) (no description set)
-      @git: qpvuntsm 230dd059 (empty) (no description set)
+      @git (in sync): qpvuntsm 230dd059 (empty) (no description set)
     "###);
 
     // "git import" can be undone by default.
@@ -166,7 +166,7 @@ fn test_git_import_undo() {
     insta::assert_snapshot!(stderr, @"");
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     a: qpvuntsm 230dd059 (empty) (no description set)
-      @git: qpvuntsm 230dd059 (empty) (no description set)
+      @git (in sync): qpvuntsm 230dd059 (empty) (no description set)
     "###);
 }
 
@@ -195,7 +195,7 @@ fn test_git_import_move_export_with_default_undo() {
     insta::assert_snapshot!(stderr, @"");
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     a: qpvuntsm 230dd059 (empty) (no description set)
-      @git: qpvuntsm 230dd059 (empty) (no description set)
+      @git (in sync): qpvuntsm 230dd059 (empty) (no description set)
     "###);
 
     // Move branch "a" and export to git repo
@@ -210,7 +210,7 @@ fn test_git_import_move_export_with_default_undo() {
     insta::assert_snapshot!(stderr, @"");
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     a: yqosqzyt 096dc80d (empty) (no description set)
-      @git: yqosqzyt 096dc80d (empty) (no description set)
+      @git (in sync): yqosqzyt 096dc80d (empty) (no description set)
     "###);
 
     // "git import" can be undone with the default `restore` behavior, as shown in
@@ -241,7 +241,7 @@ fn test_git_import_move_export_with_default_undo() {
     insta::assert_snapshot!(stderr, @"");
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     a: yqosqzyt 096dc80d (empty) (no description set)
-      @git: yqosqzyt 096dc80d (empty) (no description set)
+      @git (in sync): yqosqzyt 096dc80d (empty) (no description set)
     "###);
 }
 
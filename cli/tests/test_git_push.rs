@@ -75,11 +75,11 @@ fn test_git_push_current_branch() {
     // Check the setup
     let stdout = test_env.jj_cmd_success(&workspace_root, &["branch", "list", "--all"]);
     insta::assert_snapshot!(stdout, @r###"
-    branch1: lzmmnrxq 19e00bf6 (empty) modified branch1 commit
+    branch1*: lzmmnrxq 19e00bf6 (empty) modified branch1 commit
       @origin (ahead by 1 commits, behind by 1 commits): lzmmnrxq hidden 45a3aa29 (empty) description 1
-    branch2: yostqsxw 10ee3363 (empty) foo
+    branch2*: yostqsxw 10ee3363 (empty) foo
       @origin (behind by 1 commits): rlzusymt 8476341e (empty) description 2
-    my-branch: yostqsxw 10ee3363 (empty) foo
+    my-branch*: yostqsxw 10ee3363 (empty) foo
     "###);
     // First dry-run. `branch1` should not get pushed.
     let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--dry-run"]);
@@ -99,12 +99,12 @@ fn test_git_push_current_branch() {
     "###);
     let stdout = test_env.jj_cmd_success(&workspace_root, &["branch", "list", "--all"]);
     insta::assert_snapshot!(stdout, @r###"
-    branch1: lzmmnrxq 19e00bf6 (empty) modified branch1 commit
+    branch1*: lzmmnrxq 19e00bf6 (empty) modified branch1 commit
       @origin (ahead by 1 commits, behind by 1 commits): lzmmnrxq hidden 45a3aa29 (empty) description 1
     branch2: yostqsxw 10ee3363 (empty) foo
-      @origin: yostqsxw 10ee3363 (empty) foo
+      @origin (in sync): yostqsxw 10ee3363 (empty) foo
     my-branch: yostqsxw 10ee3363 (empty) foo
-      @origin: yostqsxw 10ee3363 (empty) foo
+      @origin (in sync): yostqsxw 10ee3363 (empty) foo
     "###);
 }
 
@@ -239,7 +239,7 @@ fn test_git_push_locally_created_and_rewritten() {
 
     // Push locally-created branch
     test_env.jj_cmd_ok(&workspace_root, &["new", "root()", "-mlocal 1"]);
-    test_env.jj_cmd_ok(&workspace_root, &["branch", "set", "my"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "my"]);
     let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push"]);
     insta::assert_snapshot!(stderr, @r###"
     Branch changes to push to origin:
@@ -252,10 +252,10 @@ fn test_git_push_locally_created_and_rewritten() {
     let stdout = test_env.jj_cmd_success(&workspace_root, &["branch", "list", "--all"]);
     insta::assert_snapshot!(stdout, @r###"
     branch1: lzmmnrxq 45a3aa29 (empty) description 1
-      @origin: lzmmnrxq 45a3aa29 (empty) description 1
+      @origin (in sync): lzmmnrxq 45a3aa29 (empty) description 1
     branch2: rlzusymt 8476341e (empty) description 2
-      @origin: rlzusymt 8476341e (empty) description 2
-    my: vruxwmqv bde1d2e4 (empty) local 2
+      @origin (in sync): rlzusymt 8476341e (empty) description 2
+    my*: vruxwmqv bde1d2e4 (empty) local 2
       @origin (ahead by 1 commits, behind by 1 commits): vruxwmqv hidden fcc99992 (empty) local 1
     "###);
     let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push"]);
@@ -278,13 +278,13 @@ fn test_git_push_multiple() {
     // Check the setup
     let stdout = test_env.jj_cmd_success(&workspace_root, &["branch", "list", "--all"]);
     insta::assert_snapshot!(stdout, @r###"
-    branch1 (deleted)
+    branch1* (deleted)
       @origin: lzmmnrxq 45a3aa29 (empty) description 1
       (this branch will be *deleted permanently* on the remote on the
        next `jj git push`. Use `jj branch forget` to prevent this)
-    branch2: yqosqzyt 15dcdaa4 (empty) foo
+    branch2*: yqosqzyt 15dcdaa4 (empty) foo
       @origin (ahead by 1 commits, behind by 1 commits): rlzusymt 8476341e (empty) description 2
-    my-branch: yqosqzyt 15dcdaa4 (empty) foo
+    my-branch*: yqosqzyt 15dcdaa4 (empty) foo
     "###);
     // First dry-run
     let (stdout, stderr) =
@@ -366,9 +366,9 @@ fn test_git_push_multiple() {
     let stdout = test_env.jj_cmd_success(&workspace_root, &["branch", "list", "--all"]);
     insta::assert_snapshot!(stdout, @r###"
     branch2: yqosqzyt 15dcdaa4 (empty) foo
-      @origin: yqosqzyt 15dcdaa4 (empty) foo
+      @origin (in sync): yqosqzyt 15dcdaa4 (empty) foo
     my-branch: yqosqzyt 15dcdaa4 (empty) foo
-      @origin: yqosqzyt 15dcdaa4 (empty) foo
+      @origin (in sync): yqosqzyt 15dcdaa4 (empty) foo
     "###);
     let stdout = test_env.jj_cmd_success(&workspace_root, &["log", "-rall()"]);
     insta::assert_snapshot!(stdout, @r###"
@@ -567,7 +567,7 @@ fn test_git_push_conflict() {
     test_env.jj_cmd_ok(&workspace_root, &["commit", "-m", "second"]);
     std::fs::write(workspace_root.join("file"), "third").unwrap();
     test_env.jj_cmd_ok(&workspace_root, &["rebase", "-r", "@", "-d", "@--"]);
-    test_env.jj_cmd_ok(&workspace_root, &["branch", "set", "my-branch"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "my-branch"]);
     test_env.jj_cmd_ok(&workspace_root, &["describe", "-m", "third"]);
     let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--all"]);
     insta::assert_snapshot!(stderr, @r###"
@@ -679,6 +679,45 @@ fn test_git_push_deleted() {
     "###);
 }
 
+#[test]
+fn test_git_push_deleted_remote_only() {
+    let (test_env, workspace_root) = set_up();
+
+    // A remote branch deleted by `jj branch delete NAME@REMOTE` is pushed as
+    // deleted, but the local branch is kept.
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["branch", "delete", "branch1@origin", "branch2@origin"],
+    );
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--dry-run", "--branch", "branch1"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Delete branch branch1 from 45a3aa29e907
+    Dry-run requested, not pushing.
+    "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--deleted"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Branch changes to push to origin:
+      Delete branch branch1 from 45a3aa29e907
+      Delete branch branch2 from 8476341eb395
+    "###);
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["branch", "list", "--all"]);
+    insta::assert_snapshot!(stdout, @r###"
+    branch1: lzmmnrxq 45a3aa29 (empty) description 1
+    branch2: rlzusymt 8476341e (empty) description 2
+    "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--deleted"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+}
+
 #[test]
 fn test_git_push_conflicting_branches() {
     let (test_env, workspace_root) = set_up();
@@ -696,13 +735,13 @@ fn test_git_push_conflicting_branches() {
         .unwrap();
     test_env.jj_cmd_ok(&workspace_root, &["git", "import"]);
     test_env.jj_cmd_ok(&workspace_root, &["new", "root()", "-m=description 3"]);
-    test_env.jj_cmd_ok(&workspace_root, &["branch", "set", "branch2"]);
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "branch2"]);
     test_env.jj_cmd_ok(&workspace_root, &["git", "fetch"]);
     insta::assert_snapshot!(
         test_env.jj_cmd_success(&workspace_root, &["branch", "list", "--all"]), @r###"
     branch1: lzmmnrxq 45a3aa29 (empty) description 1
-      @origin: lzmmnrxq 45a3aa29 (empty) description 1
-    branch2 (conflicted):
+      @origin (in sync): lzmmnrxq 45a3aa29 (empty) description 1
+    branch2* (conflicted):
       + yostqsxw 8e670e2d (empty) description 3
       + rlzusymt 8476341e (empty) description 2
       @origin (behind by 1 commits): rlzusymt 8476341e (empty) description 2
@@ -65,3 +65,111 @@ fn test_gitsubmodule_print_gitmodules() {
 	path:new
     "###);
 }
+
+#[test]
+fn test_gitsubmodule_diff() {
+    let test_env = TestEnvironment::default();
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git2::Repository::init(&git_repo_path).unwrap();
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let mut parents = vec![];
+    for (message, submodule_id) in [
+        ("add submodule", "1111111111111111111111111111111111111111"),
+        (
+            "update submodule",
+            "2222222222222222222222222222222222222222",
+        ),
+    ] {
+        let mut tree_builder = git_repo.treebuilder(None).unwrap();
+        let blob_id = git_repo.blob(b"content\n").unwrap();
+        tree_builder.insert("file", blob_id, 0o100644).unwrap();
+        let submodule_id = git2::Oid::from_str(submodule_id).unwrap();
+        tree_builder.insert("sub", submodule_id, 0o160000).unwrap();
+        let tree = git_repo.find_tree(tree_builder.write().unwrap()).unwrap();
+        let parent_refs = parents.iter().collect::<Vec<_>>();
+        let commit_id = git_repo
+            .commit(None, &signature, &signature, message, &tree, &parent_refs)
+            .unwrap();
+        parents = vec![git_repo.find_commit(commit_id).unwrap()];
+    }
+    git_repo.branch("main", &parents[0], false).unwrap();
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["init", "repo", "--git-repo", "git-repo"],
+    );
+    let repo_path = test_env.env_root().join("repo");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r", "main", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M sub
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r", "main", "--stat"]);
+    insta::assert_snapshot!(stdout, @r###"
+    sub | 2 +-
+    1 file changed, 1 insertion(+), 1 deletion(-)
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r", "main", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/sub b/sub
+    index 1111111111..2222222222 160000
+    --- a/sub
+    +++ b/sub
+    @@ -1,1 +1,1 @@
+    -Subproject commit 1111111111111111111111111111111111111111
+    +Subproject commit 2222222222222222222222222222222222222222
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r", "main", "--color-words"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Modified Git submodule sub:
+       1    1: Subproject commit 11111111111111111111111111111111111111112222222222222222222222222222222222222222
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r", "main-", "--git"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file b/file
+    new file mode 100644
+    index 0000000000..d95f3ad14d
+    --- /dev/null
+    +++ b/file
+    @@ -0,0 +1,1 @@
+    +content
+    diff --git a/sub b/sub
+    new file mode 160000
+    index 0000000000..1111111111
+    --- /dev/null
+    +++ b/sub
+    @@ -0,0 +1,1 @@
+    +Subproject commit 1111111111111111111111111111111111111111
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r", "main-", "--color-words"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Added regular file file:
+            1: content
+    Added Git submodule sub:
+            1: Subproject commit 1111111111111111111111111111111111111111
+    "###);
+
+    // The submodule isn't checked out
+    let (stdout, _stderr) = test_env.jj_cmd_ok(&repo_path, &["new", "main-"]);
+    insta::assert_snapshot!(stdout, @"");
+    assert!(!repo_path.join("sub").exists());
+
+    // A submodule directory created by the user is left alone by snapshot and
+    // checkout
+    std::fs::create_dir(repo_path.join("sub")).unwrap();
+    std::fs::write(repo_path.join("sub").join("file"), "in submodule\n").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["status"]);
+    insta::assert_snapshot!(stdout, @r###"
+    The working copy is clean
+    Working copy : vruxwmqv fb57c325 (empty) (no description set)
+    Parent commit: wnsozpkv 35ab61fc add submodule
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["new", "main"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["status"]);
+    insta::assert_snapshot!(stdout, @r###"
+    The working copy is clean
+    Working copy : znkkpsqq 3faa505c (empty) (no description set)
+    Parent commit: ktyrwpzq 5125b350 main | update submodule
+    "###);
+    assert!(repo_path.join("sub").join("file").exists());
+}
@@ -69,7 +69,7 @@ fn test_interdiff_basic() {
     );
     insta::assert_snapshot!(stdout, @r###"
     diff --git a/file2 b/file2
-    index 257cc5642c...3bd1f0e297 100644
+    index 257cc5642c..3bd1f0e297 100644
     --- a/file2
     +++ b/file2
     @@ -1,1 +1,2 @@
@@ -78,6 +78,58 @@ fn test_interdiff_basic() {
     "###);
 }
 
+#[test]
+fn test_interdiff_from_predecessor() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file2"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "change"]);
+    test_env.jj_cmd_ok(&repo_path, &["rebase", "-r@", "-droot()"]);
+
+    // The rebase itself isn't shown
+    let stdout = test_env.jj_cmd_success(&repo_path, &["interdiff", "--from-predecessor"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    std::fs::write(repo_path.join("file2"), "foo\nbar\n").unwrap();
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["interdiff", "--from-predecessor", "--to", "@", "--git"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file2 b/file2
+    index 257cc5642c..3bd1f0e297 100644
+    --- a/file2
+    +++ b/file2
+    @@ -1,1 +1,2 @@
+     foo
+    +bar
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["interdiff", "--from-predecessor", "--to", "root()"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Commit 000000000000 has no predecessor
+    "###);
+
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["interdiff", "--from-predecessor", "--from", "@-"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--from-predecessor' cannot be used with '--from <FROM>'
+
+    Usage: jj interdiff <--from <FROM>|--to <TO>|--from-predecessor> [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+}
+
 #[test]
 fn test_interdiff_paths() {
     let test_env = TestEnvironment::default();
@@ -151,7 +203,7 @@ fn test_interdiff_conflicting() {
     );
     insta::assert_snapshot!(stdout, @r###"
     diff --git a/file b/file
-    index 0000000000...24c5735c3e 100644
+    index 0000000000..24c5735c3e 100644
     --- a/file
     +++ b/file
     @@ -1,7 +1,1 @@
@@ -134,7 +134,7 @@ fn test_log_with_or_without_diff() {
     @  a new commit
     │  M file1
     │  diff --git a/file1 b/file1
-    │  index 257cc5642c...3bd1f0e297 100644
+    │  index 257cc5642c..3bd1f0e297 100644
     │  --- a/file1
     │  +++ b/file1
     │  @@ -1,1 +1,2 @@
@@ -147,7 +147,7 @@ fn test_log_with_or_without_diff() {
     │  index 0000000000..257cc5642c
     │  --- /dev/null
     │  +++ b/file1
-    │  @@ -1,0 +1,1 @@
+    │  @@ -0,0 +1,1 @@
     │  +foo
     ◉
     "###);
@@ -196,7 +196,7 @@ fn test_log_with_or_without_diff() {
     insta::assert_snapshot!(stdout, @r###"
     a new commit
     diff --git a/file1 b/file1
-    index 257cc5642c...3bd1f0e297 100644
+    index 257cc5642c..3bd1f0e297 100644
     --- a/file1
     +++ b/file1
     @@ -1,1 +1,2 @@
@@ -208,7 +208,7 @@ fn test_log_with_or_without_diff() {
     index 0000000000..257cc5642c
     --- /dev/null
     +++ b/file1
-    @@ -1,0 +1,1 @@
+    @@ -0,0 +1,1 @@
     +foo
     "###);
 
@@ -261,7 +261,7 @@ fn test_log_with_or_without_diff() {
     insta::assert_snapshot!(stdout, @r###"
     @  a new commit
     │  diff --git a/file1 b/file1
-    ~  index 257cc5642c...3bd1f0e297 100644
+    ~  index 257cc5642c..3bd1f0e297 100644
        --- a/file1
        +++ b/file1
        @@ -1,1 +1,2 @@
@@ -275,7 +275,7 @@ fn test_log_with_or_without_diff() {
     insta::assert_snapshot!(stdout, @r###"
     a new commit
     diff --git a/file1 b/file1
-    index 257cc5642c...3bd1f0e297 100644
+    index 257cc5642c..3bd1f0e297 100644
     --- a/file1
     +++ b/file1
     @@ -1,1 +1,2 @@
@@ -799,6 +799,49 @@ fn test_log_reversed() {
     "###);
 }
 
+#[test]
+fn test_log_reversed_merge() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "left"]);
+    std::fs::write(repo_path.join("file1"), "left\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "right"]);
+    std::fs::write(repo_path.join("file2"), "right\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "description(left)", "@", "-m", "merge"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "child"]);
+    std::fs::write(repo_path.join("file1"), "child\n").unwrap();
+
+    // The edges fan out from the root and join at the merge without crossing
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--reversed"]);
+    insta::assert_snapshot!(stdout, @r###"
+    ◉
+    ├─╮
+    │ ◉  left
+    ◉ │  right
+    ├─╯
+    ◉  merge
+    @  child
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--reversed", "-p", "file1"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ◉  left
+    ╷  Added regular file file1:
+    ╷          1: left
+    @  child
+       Modified regular file file1:
+          1    1: leftchild
+    "###);
+}
+
 #[test]
 fn test_log_filtered_by_path() {
     let test_env = TestEnvironment::default();
@@ -863,6 +906,43 @@ fn test_log_filtered_by_path() {
     "###);
 }
 
+#[test]
+fn test_log_filtered_by_path_glob() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::create_dir(repo_path.join("lib")).unwrap();
+    std::fs::write(repo_path.join("lib").join("a.proto"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    std::fs::write(repo_path.join("b.proto"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "third"]);
+    std::fs::write(repo_path.join("lib").join("c.rs"), "foo\n").unwrap();
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "-s", "lib/**/*.proto"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ◉  first
+    │  A lib/a.proto
+    ~
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "-s", "*.proto", "lib/c.rs"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @  third
+    │  A lib/c.rs
+    ◉  second
+    │  A b.proto
+    ~
+    "###);
+}
+
 #[test]
 fn test_log_limit() {
     let test_env = TestEnvironment::default();
@@ -896,6 +976,13 @@ fn test_log_limit() {
     ├─╮
     │ ◉  b
     "###);
+    // -n is an alias of --limit
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "-n2"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @    d
+    ├─╮
+    │ ◉  b
+    "###);
 
     let stdout = test_env.jj_cmd_success(
         &repo_path,
@@ -1059,7 +1146,7 @@ fn test_multiple_revsets() {
     let repo_path = test_env.env_root().join("repo");
     for name in ["foo", "bar", "baz"] {
         test_env.jj_cmd_ok(&repo_path, &["new", "-m", name]);
-        test_env.jj_cmd_ok(&repo_path, &["branch", "set", name]);
+        test_env.jj_cmd_ok(&repo_path, &["branch", "create", name]);
     }
 
     // Default revset should be overridden if one or more -r options are specified.
@@ -1236,6 +1323,151 @@ fn test_graph_styles() {
     "###);
 }
 
+#[test]
+fn test_graph_styles_invalid() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.add_config(r#"ui.graph.style = "fancy""#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Invalid `ui.graph.style`: "fancy" (valid options are: "curved", "square", "ascii", "ascii-large", "legacy")
+    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "log"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Invalid `ui.graph.style`: "fancy" (valid options are: "curved", "square", "ascii", "ascii-large", "legacy")
+    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
+    "###);
+
+    // The style isn't needed without a graph
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-T=change_id"]);
+    insta::assert_snapshot!(stdout, @r###"
+    qpvuntsmwlqtpsluzzsnyyzlmlwvmlnuzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz
+    "###);
+}
+
+#[test]
+fn test_graph_node_symbols() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "base\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "base"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "main"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "left"]);
+    std::fs::write(repo_path.join("file"), "left\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "main", "-m", "right"]);
+    std::fs::write(repo_path.join("file"), "right\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "-m", "conflict", r#"description("left")"#, "@"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "working copy"]);
+    test_env.add_config(
+        r#"
+        revset-aliases.'immutable_heads()' = 'main'
+        ui.graph.node.immutable = "+"
+        ui.graph.node.conflict = "x"
+        "#,
+    );
+
+    let render = |style: &str| {
+        test_env.jj_cmd_success(
+            &repo_path,
+            &[
+                "log",
+                "-T=description",
+                &format!("--config-toml=ui.graph.style = {style:?}"),
+            ],
+        )
+    };
+    insta::assert_snapshot!(render("curved"), @r###"
+    @  working copy
+    x    conflict
+    ├─╮
+    │ ◉  right
+    ◉ │  left
+    ├─╯
+    +  base
+    │
+    ~
+    "###);
+    insta::assert_snapshot!(render("square"), @r###"
+    @  working copy
+    x    conflict
+    ├─┐
+    │ ◉  right
+    ◉ │  left
+    ├─┘
+    +  base
+    │
+    ~
+    "###);
+    insta::assert_snapshot!(render("ascii"), @r###"
+    @  working copy
+    x    conflict
+    |\
+    | o  right
+    o |  left
+    |/
+    +  base
+    |
+    ~
+    "###);
+    insta::assert_snapshot!(render("ascii-large"), @r###"
+    @  working copy
+    x     conflict
+    |\
+    | \
+    |  o  right
+    o  |  left
+    | /
+    |/
+    +  base
+    |
+    ~
+    "###);
+    insta::assert_snapshot!(render("legacy"), @r###"
+    @ working copy
+    x   conflict
+    |\  
+    | o right
+    o | left
+    |/  
+    + base
+    ~ 
+    "###);
+
+    // The working-copy symbol takes precedence
+    test_env.add_config(r#"ui.graph.node.working-copy = "W""#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T=description", "-r=::@-"]);
+    insta::assert_snapshot!(stdout, @r###"
+    x    conflict
+    ├─╮
+    │ ◉  right
+    ◉ │  left
+    ├─╯
+    +  base
+    +
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["edit", r#"description("conflict")"#]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T=description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    x  working copy
+    W    conflict
+    ├─╮
+    │ ◉  right
+    ◉ │  left
+    ├─╯
+    +  base
+    │
+    ~
+    "###);
+}
+
 #[test]
 fn test_log_word_wrap() {
     let test_env = TestEnvironment::default();
@@ -109,7 +109,7 @@ fn test_obslog_with_or_without_diff() {
     rlvkpnrz test.user@example.com 2001-02-03 04:05:10.000 +07:00 66b42ad3
     my description
     diff --git a/file1 b/file1
-    index 0000000000...2ab19ae607 100644
+    index 0000000000..2ab19ae607 100644
     --- a/file1
     +++ b/file1
     @@ -1,7 +1,1 @@
@@ -126,7 +126,7 @@ fn test_obslog_with_or_without_diff() {
     rlvkpnrz hidden test.user@example.com 2001-02-03 04:05:09.000 +07:00 6fbba7bc
     my description
     diff --git a/file1 b/file1
-    index 257cc5642c...3bd1f0e297 100644
+    index 257cc5642c..3bd1f0e297 100644
     --- a/file1
     +++ b/file1
     @@ -1,1 +1,2 @@
@@ -137,7 +137,7 @@ fn test_obslog_with_or_without_diff() {
     index 0000000000..257cc5642c
     --- /dev/null
     +++ b/file2
-    @@ -1,0 +1,1 @@
+    @@ -0,0 +1,1 @@
     +foo
     rlvkpnrz hidden test.user@example.com 2001-02-03 04:05:08.000 +07:00 eac0d0da
     (empty) my description
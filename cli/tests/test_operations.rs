@@ -130,6 +130,25 @@ fn test_op_log_limit() {
     "###);
 }
 
+#[test]
+fn test_op_log_graph_style() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        r#"
+        ui.graph.style = "ascii"
+        ui.graph.node.working-copy = "*"
+        "#,
+    );
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "log", "-T=description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    *  add workspace 'default'
+    o  initialize repo
+    "###);
+}
+
 #[test]
 fn test_op_log_no_graph() {
     let test_env = TestEnvironment::default();
@@ -164,7 +183,9 @@ fn test_op_log_no_graph_null_terminated() {
             r#"id.short(4) ++ "\0""#,
         ],
     );
-    insta::assert_debug_snapshot!(stdout, @r###""16e6\0fb05\0d50e\023b8\0""###);
+    insta::assert_debug_snapshot!(stdout, @r###"
+    "16e6\0fb05\0d50e\023b8\0"
+    "###);
 }
 
 #[test]
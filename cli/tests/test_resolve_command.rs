@@ -205,13 +205,10 @@ conflict
     insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["diff"]), 
     @r###"
     Modified conflict in file:
-       1    1: <<<<<<<
-       2    2: %%%%%%%
-       3    3: -basesome
-       4    4: +afake
-       5    5: +++++++
-       6    6: bconflict
-       7    7: >>>>>>>
+      Changes from base to side #1:
+       1    1: somefake
+      Changes from base to side #2:
+       1    1: someconflict
     "###);
     insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), 
     @r###"
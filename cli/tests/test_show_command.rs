@@ -63,3 +63,88 @@ fn test_show_relative_timestamps() {
         (no description set)
     "###);
 }
+
+#[test]
+fn test_show_diff_formats() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    std::fs::remove_file(repo_path.join("file1")).unwrap();
+    std::fs::write(repo_path.join("file2"), "foo\nbar\n").unwrap();
+
+    // The header is the same for all formats
+    let stdout = test_env.jj_cmd_success(&repo_path, &["show", "-s"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Commit ID: 61311e3966715c9b6513a1aa0df3cd5db0fc19fc
+    Change ID: kkmpptxzrspxrzommnulwmwkkqwworpl
+    Author: Test User <test.user@example.com> (2001-02-03 04:05:09.000 +07:00)
+    Committer: Test User <test.user@example.com> (2001-02-03 04:05:10.000 +07:00)
+
+        second
+
+    R file1
+    M file2
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["show", "--summary"]);
+    let stdout = stdout.lines().skip(7).join("\n");
+    insta::assert_snapshot!(stdout, @r###"
+    R file1
+    M file2
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["show", "--stat"]);
+    let stdout = stdout.lines().skip(7).join("\n");
+    insta::assert_snapshot!(stdout, @r###"
+    file1 | 1 -
+    file2 | 1 +
+    2 files changed, 1 insertion(+), 1 deletion(-)
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["show", "--git"]);
+    let stdout = stdout.lines().skip(7).join("\n");
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    deleted file mode 100644
+    index 257cc5642c..0000000000
+    --- a/file1
+    +++ /dev/null
+    @@ -1,1 +0,0 @@
+    -foo
+    diff --git a/file2 b/file2
+    index 257cc5642c..3bd1f0e297 100644
+    --- a/file2
+    +++ b/file2
+    @@ -1,1 +1,2 @@
+     foo
+    +bar
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["show", "--color-words"]);
+    let stdout = stdout.lines().skip(7).join("\n");
+    insta::assert_snapshot!(stdout, @r###"
+    Removed regular file file1:
+       1     : foo
+    Modified regular file file2:
+       1    1: foo
+            2: bar
+    "###);
+
+    // Conflicting formats are rejected like in `jj diff`
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["show", "--summary", "--stat"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--summary' cannot be used with '--stat'
+
+    Usage: jj show --summary [REVISION]
+
+    For more information, try '--help'.
+    "###);
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["show", "--git", "--color-words"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--git' cannot be used with '--color-words'
+
+    Usage: jj show --git [REVISION]
+
+    For more information, try '--help'.
+    "###);
+}
@@ -71,7 +71,7 @@ fn test_git_push_undo() {
     //    local `main`     | BB      |   --   | --
     //    remote-tracking  | AA      |   AA   | AA
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    main: qpvuntsm 8c05de15 (empty) BB
+    main*: qpvuntsm 8c05de15 (empty) BB
       @origin (ahead by 1 commits, behind by 1 commits): qpvuntsm hidden 0cffb614 (empty) AA
     "###);
     let pre_push_opid = test_env.current_operation_id(&repo_path);
@@ -84,7 +84,7 @@ fn test_git_push_undo() {
     //    remote-tracking  | BB      |   BB   | BB
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     main: qpvuntsm 8c05de15 (empty) BB
-      @origin: qpvuntsm 8c05de15 (empty) BB
+      @origin (in sync): qpvuntsm 8c05de15 (empty) BB
     "###);
 
     // Undo the push
@@ -96,7 +96,7 @@ fn test_git_push_undo() {
     //    local  `main`    | BB      |   --   | --
     //    remote-tracking  | AA      |   AA   | BB
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    main: qpvuntsm 8c05de15 (empty) BB
+    main*: qpvuntsm 8c05de15 (empty) BB
       @origin (ahead by 1 commits, behind by 1 commits): qpvuntsm hidden 0cffb614 (empty) AA
     "###);
     test_env.advance_test_rng_seed_to_multiple_of(100_000);
@@ -111,7 +111,7 @@ fn test_git_push_undo() {
     // branches, but that also has undersired consequences: the second fetch in `jj
     // git fetch && jj undo && jj git fetch` would become a no-op.
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    main (conflicted):
+    main* (conflicted):
       - qpvuntsm hidden 0cffb614 (empty) AA
       + qpvuntsm?? 0a3e99f0 (empty) CC
       + qpvuntsm?? 8c05de15 (empty) BB
@@ -144,7 +144,7 @@ fn test_git_push_undo_with_import() {
     //    local `main`     | BB      |   --   | --
     //    remote-tracking  | AA      |   AA   | AA
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    main: qpvuntsm 8c05de15 (empty) BB
+    main*: qpvuntsm 8c05de15 (empty) BB
       @origin (ahead by 1 commits, behind by 1 commits): qpvuntsm hidden 0cffb614 (empty) AA
     "###);
     let pre_push_opid = test_env.current_operation_id(&repo_path);
@@ -157,7 +157,7 @@ fn test_git_push_undo_with_import() {
     //    remote-tracking  | BB      |   BB   | BB
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     main: qpvuntsm 8c05de15 (empty) BB
-      @origin: qpvuntsm 8c05de15 (empty) BB
+      @origin (in sync): qpvuntsm 8c05de15 (empty) BB
     "###);
 
     // Undo the push
@@ -169,7 +169,7 @@ fn test_git_push_undo_with_import() {
     //    local  `main`    | BB      |   --   | --
     //    remote-tracking  | AA      |   AA   | BB
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    main: qpvuntsm 8c05de15 (empty) BB
+    main*: qpvuntsm 8c05de15 (empty) BB
       @origin (ahead by 1 commits, behind by 1 commits): qpvuntsm hidden 0cffb614 (empty) AA
     "###);
 
@@ -185,7 +185,7 @@ fn test_git_push_undo_with_import() {
     //    remote-tracking  | BB      |   BB   | BB
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     main: qpvuntsm 8c05de15 (empty) BB
-      @origin: qpvuntsm 8c05de15 (empty) BB
+      @origin (in sync): qpvuntsm 8c05de15 (empty) BB
     "###);
     test_env.advance_test_rng_seed_to_multiple_of(100_000);
     test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "CC"]);
@@ -193,7 +193,7 @@ fn test_git_push_undo_with_import() {
     // There is not a conflict. This seems like a good outcome; undoing `git push`
     // was essentially a no-op.
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    main: qpvuntsm 0a3e99f0 (empty) CC
+    main*: qpvuntsm 0a3e99f0 (empty) CC
       @origin (ahead by 1 commits, behind by 1 commits): qpvuntsm hidden 8c05de15 (empty) BB
     "###);
 }
@@ -224,8 +224,8 @@ fn test_git_push_undo_colocated() {
     //    local `main`     | BB      |   BB   | BB
     //    remote-tracking  | AA      |   AA   | AA
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    main: qpvuntsm 8c05de15 (empty) BB
-      @git: qpvuntsm 8c05de15 (empty) BB
+    main*: qpvuntsm 8c05de15 (empty) BB
+      @git (in sync): qpvuntsm 8c05de15 (empty) BB
       @origin (ahead by 1 commits, behind by 1 commits): qpvuntsm hidden 0cffb614 (empty) AA
     "###);
     let pre_push_opid = test_env.current_operation_id(&repo_path);
@@ -238,8 +238,8 @@ fn test_git_push_undo_colocated() {
     //    remote-tracking  | BB      |   BB   | BB
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     main: qpvuntsm 8c05de15 (empty) BB
-      @git: qpvuntsm 8c05de15 (empty) BB
-      @origin: qpvuntsm 8c05de15 (empty) BB
+      @git (in sync): qpvuntsm 8c05de15 (empty) BB
+      @origin (in sync): qpvuntsm 8c05de15 (empty) BB
     "###);
 
     // Undo the push
@@ -259,8 +259,8 @@ fn test_git_push_undo_colocated() {
     //    local `main`     | BB      |   BB   | BB
     //    remote-tracking  | AA      |   AA   | AA
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    main: qpvuntsm 8c05de15 (empty) BB
-      @git: qpvuntsm 8c05de15 (empty) BB
+    main*: qpvuntsm 8c05de15 (empty) BB
+      @git (in sync): qpvuntsm 8c05de15 (empty) BB
       @origin (ahead by 1 commits, behind by 1 commits): qpvuntsm hidden 0cffb614 (empty) AA
     "###);
     test_env.advance_test_rng_seed_to_multiple_of(100_000);
@@ -269,7 +269,7 @@ fn test_git_push_undo_colocated() {
     // We have the same conflict as `test_git_push_undo`. TODO: why did we get the
     // same result in a seemingly different way?
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    main (conflicted):
+    main* (conflicted):
       - qpvuntsm hidden 0cffb614 (empty) AA
       + qpvuntsm?? 0a3e99f0 (empty) CC
       + qpvuntsm?? 8c05de15 (empty) BB
@@ -296,12 +296,12 @@ fn test_git_push_undo_repo_only() {
     test_env.jj_cmd_ok(&repo_path, &["git", "push"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     main: qpvuntsm 0cffb614 (empty) AA
-      @origin: qpvuntsm 0cffb614 (empty) AA
+      @origin (in sync): qpvuntsm 0cffb614 (empty) AA
     "###);
     test_env.advance_test_rng_seed_to_multiple_of(100_000);
     test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "BB"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    main: qpvuntsm 8c05de15 (empty) BB
+    main*: qpvuntsm 8c05de15 (empty) BB
       @origin (ahead by 1 commits, behind by 1 commits): qpvuntsm hidden 0cffb614 (empty) AA
     "###);
     let pre_push_opid = test_env.current_operation_id(&repo_path);
@@ -314,14 +314,14 @@ fn test_git_push_undo_repo_only() {
     );
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     main: qpvuntsm 8c05de15 (empty) BB
-      @origin: qpvuntsm 8c05de15 (empty) BB
+      @origin (in sync): qpvuntsm 8c05de15 (empty) BB
     "###);
     test_env.advance_test_rng_seed_to_multiple_of(100_000);
     test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "CC"]);
     test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
     // This currently gives an identical result to `test_git_push_undo_import`.
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    main: qpvuntsm 0a3e99f0 (empty) CC
+    main*: qpvuntsm 0a3e99f0 (empty) CC
       @origin (ahead by 1 commits, behind by 1 commits): qpvuntsm hidden 8c05de15 (empty) BB
     "###);
 }
@@ -341,8 +341,8 @@ fn test_branch_track_untrack_undo() {
     test_env.jj_cmd_ok(&repo_path, &["branch", "delete", "feature2"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     feature1: qpvuntsm 270721f5 (empty) commit
-      @origin: qpvuntsm 270721f5 (empty) commit
-    feature2 (deleted)
+      @origin (in sync): qpvuntsm 270721f5 (empty) commit
+    feature2* (deleted)
       @origin: qpvuntsm 270721f5 (empty) commit
       (this branch will be *deleted permanently* on the remote on the
        next `jj git push`. Use `jj branch forget` to prevent this)
@@ -354,16 +354,16 @@ fn test_branch_track_untrack_undo() {
         &["branch", "untrack", "feature1@origin", "feature2@origin"],
     );
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: qpvuntsm 270721f5 (empty) commit
-    feature1@origin: qpvuntsm 270721f5 (empty) commit
+    feature1*: qpvuntsm 270721f5 (empty) commit
+    feature1@origin (in sync): qpvuntsm 270721f5 (empty) commit
     feature2@origin: qpvuntsm 270721f5 (empty) commit
     "###);
 
     test_env.jj_cmd_ok(&repo_path, &["undo"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     feature1: qpvuntsm 270721f5 (empty) commit
-      @origin: qpvuntsm 270721f5 (empty) commit
-    feature2 (deleted)
+      @origin (in sync): qpvuntsm 270721f5 (empty) commit
+    feature2* (deleted)
       @origin: qpvuntsm 270721f5 (empty) commit
       (this branch will be *deleted permanently* on the remote on the
        next `jj git push`. Use `jj branch forget` to prevent this)
@@ -371,22 +371,22 @@ fn test_branch_track_untrack_undo() {
 
     test_env.jj_cmd_ok(&repo_path, &["undo"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: qpvuntsm 270721f5 (empty) commit
-    feature1@origin: qpvuntsm 270721f5 (empty) commit
+    feature1*: qpvuntsm 270721f5 (empty) commit
+    feature1@origin (in sync): qpvuntsm 270721f5 (empty) commit
     feature2@origin: qpvuntsm 270721f5 (empty) commit
     "###);
 
     test_env.jj_cmd_ok(&repo_path, &["branch", "track", "feature1@origin"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
     feature1: qpvuntsm 270721f5 (empty) commit
-      @origin: qpvuntsm 270721f5 (empty) commit
+      @origin (in sync): qpvuntsm 270721f5 (empty) commit
     feature2@origin: qpvuntsm 270721f5 (empty) commit
     "###);
 
     test_env.jj_cmd_ok(&repo_path, &["undo"]);
     insta::assert_snapshot!(get_branch_output(&test_env, &repo_path), @r###"
-    feature1: qpvuntsm 270721f5 (empty) commit
-    feature1@origin: qpvuntsm 270721f5 (empty) commit
+    feature1*: qpvuntsm 270721f5 (empty) commit
+    feature1@origin (in sync): qpvuntsm 270721f5 (empty) commit
     feature2@origin: qpvuntsm 270721f5 (empty) commit
     "###);
 }
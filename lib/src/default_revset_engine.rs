@@ -14,6 +14,7 @@
 
 #![allow(missing_docs)]
 
+use std::cell::RefCell;
 use std::cmp::{Ordering, Reverse};
 use std::collections::{BTreeSet, BinaryHeap, HashSet};
 use std::fmt;
@@ -30,7 +31,7 @@ use crate::default_index_store::{
 use crate::default_revset_graph_iterator::RevsetGraphIterator;
 use crate::id_prefix::{IdIndex, IdIndexSource, IdIndexSourceEntry};
 use crate::index::{HexPrefix, PrefixResolution};
-use crate::matchers::{EverythingMatcher, Matcher, PrefixMatcher, Visit};
+use crate::matchers::{EverythingMatcher, FileGlobsMatcher, Matcher, PrefixMatcher, Visit};
 use crate::repo_path::RepoPath;
 use crate::revset::{
     ChangeIdIndex, ResolvedExpression, ResolvedPredicateExpression, Revset, RevsetEvaluationError,
@@ -127,6 +128,77 @@ impl<'index> Revset<'index> for RevsetImpl<'index> {
     fn count(&self) -> usize {
         self.inner.iter().count()
     }
+
+    fn containing_fn(&self) -> Box<dyn Fn(&CommitId) -> bool + '_> {
+        let positions = PositionsAccumulator::new(self.index, self.inner.iter());
+        Box::new(move |commit_id| positions.contains(commit_id))
+    }
+}
+
+/// Incrementally consumes revset entries to test whether commits are
+/// included in the revset.
+///
+/// Entries are only consumed as far as needed to answer each query, and the
+/// consumed positions are kept so that queries can come in any order.
+struct PositionsAccumulator<'revset, 'index> {
+    index: CompositeIndex<'index>,
+    inner: RefCell<PositionsAccumulatorInner<'revset, 'index>>,
+}
+
+struct PositionsAccumulatorInner<'revset, 'index> {
+    entries_iter: Option<Box<dyn Iterator<Item = IndexEntry<'index>> + 'revset>>,
+    /// Positions consumed so far, in descending order.
+    consumed_positions: Vec<IndexPosition>,
+}
+
+impl<'revset, 'index> PositionsAccumulator<'revset, 'index> {
+    fn new(
+        index: CompositeIndex<'index>,
+        entries_iter: Box<dyn Iterator<Item = IndexEntry<'index>> + 'revset>,
+    ) -> Self {
+        let inner = PositionsAccumulatorInner {
+            entries_iter: Some(entries_iter),
+            consumed_positions: Vec::new(),
+        };
+        PositionsAccumulator {
+            index,
+            inner: RefCell::new(inner),
+        }
+    }
+
+    /// Checks whether the commit is in the revset.
+    fn contains(&self, commit_id: &CommitId) -> bool {
+        let Some(position) = self.index.commit_id_to_pos(commit_id) else {
+            return false;
+        };
+        let mut inner = self.inner.borrow_mut();
+        inner.consume_to(position);
+        inner
+            .consumed_positions
+            .binary_search_by(|p| p.cmp(&position).reverse())
+            .is_ok()
+    }
+}
+
+impl PositionsAccumulatorInner<'_, '_> {
+    /// Consumes entries until the given position is reached or passed.
+    fn consume_to(&mut self, desired_position: IndexPosition) {
+        let Some(entries_iter) = self.entries_iter.as_mut() else {
+            return;
+        };
+        let last_position = self.consumed_positions.last();
+        if last_position.is_some_and(|&pos| pos <= desired_position) {
+            return;
+        }
+        for entry in entries_iter.by_ref() {
+            let position = entry.position();
+            self.consumed_positions.push(position);
+            if position <= desired_position {
+                return;
+            }
+        }
+        self.entries_iter = None;
+    }
 }
 
 struct ChangeIdIndexImpl<'index> {
@@ -867,6 +939,10 @@ fn build_predicate_fn<'index>(
                 has_diff_from_parent(&store, index, entry, matcher.as_ref())
             })
         }
+        RevsetFilterPredicate::FileGlobs(globs) => {
+            let matcher = FileGlobsMatcher::new(globs);
+            pure_predicate_fn(move |entry| has_diff_from_parent(&store, index, entry, &matcher))
+        }
         RevsetFilterPredicate::HasConflict => pure_predicate_fn(move |entry| {
             let commit = store.get_commit(&entry.commit_id()).unwrap();
             commit.has_conflict().unwrap()
@@ -1007,4 +1083,56 @@ mod tests {
         assert!(!p(&get_entry(&id_1)));
         assert!(p(&get_entry(&id_0)));
     }
+
+    #[test]
+    fn test_positions_accumulator() {
+        let mut new_change_id = change_id_generator();
+        let mut index = MutableIndexImpl::full(3, 16);
+        let id_0 = CommitId::from_hex("000000");
+        let id_1 = CommitId::from_hex("111111");
+        let id_2 = CommitId::from_hex("222222");
+        let id_3 = CommitId::from_hex("333333");
+        let id_4 = CommitId::from_hex("444444");
+        index.add_commit_data(id_0.clone(), new_change_id(), &[]);
+        index.add_commit_data(id_1.clone(), new_change_id(), &[id_0.clone()]);
+        index.add_commit_data(id_2.clone(), new_change_id(), &[id_1.clone()]);
+        index.add_commit_data(id_3.clone(), new_change_id(), &[id_2.clone()]);
+        index.add_commit_data(id_4.clone(), new_change_id(), &[id_3.clone()]);
+
+        let get_entry = |id: &CommitId| index.as_composite().entry_by_id(id).unwrap();
+        let make_entries = |ids: &[&CommitId]| ids.iter().map(|id| get_entry(id)).collect_vec();
+        let make_set = |ids: &[&CommitId]| -> Box<dyn InternalRevset> {
+            let index_entries = make_entries(ids);
+            Box::new(EagerRevset { index_entries })
+        };
+
+        // Queries in iteration order
+        let set = make_set(&[&id_4, &id_2, &id_0]);
+        let positions = PositionsAccumulator::new(index.as_composite(), set.iter());
+        assert!(positions.contains(&id_4));
+        assert!(!positions.contains(&id_3));
+        assert!(positions.contains(&id_2));
+        assert!(!positions.contains(&id_1));
+        assert!(positions.contains(&id_0));
+
+        // Queries in arbitrary order, including already consumed entries
+        let positions = PositionsAccumulator::new(index.as_composite(), set.iter());
+        assert!(positions.contains(&id_2));
+        assert!(!positions.contains(&id_3));
+        assert!(positions.contains(&id_4));
+        assert!(positions.contains(&id_0));
+        assert!(!positions.contains(&id_1));
+        assert!(positions.contains(&id_2));
+
+        // Unknown commit
+        let positions = PositionsAccumulator::new(index.as_composite(), set.iter());
+        assert!(!positions.contains(&CommitId::from_hex("999999")));
+        assert!(positions.contains(&id_0));
+
+        // Empty set
+        let set = make_set(&[]);
+        let positions = PositionsAccumulator::new(index.as_composite(), set.iter());
+        assert!(!positions.contains(&id_4));
+        assert!(!positions.contains(&id_0));
+    }
 }
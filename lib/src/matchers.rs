@@ -17,6 +17,7 @@
 use std::collections::{HashMap, HashSet};
 use std::iter;
 
+use itertools::Itertools as _;
 use tracing::instrument;
 
 use crate::repo_path::{RepoPath, RepoPathComponent};
@@ -160,6 +161,72 @@ impl Matcher for PrefixMatcher {
     }
 }
 
+/// Glob pattern matching file paths below a base directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileGlob {
+    /// Directory the pattern is relative to.
+    pub base: RepoPath,
+    /// Pattern to match the path relative to `base` against.
+    pub pattern: glob::Pattern,
+    pub case_sensitive: bool,
+}
+
+impl FileGlob {
+    pub fn matches(&self, file: &RepoPath) -> bool {
+        if !self.base.contains(file) {
+            return false;
+        }
+        let relative_path = file.components()[self.base.components().len()..]
+            .iter()
+            .map(|component| component.as_str())
+            .join("/");
+        let options = glob::MatchOptions {
+            case_sensitive: self.case_sensitive,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        self.pattern.matches_with(&relative_path, options)
+    }
+}
+
+/// Matches files by glob patterns. Only the base directories of the patterns
+/// and their descendants are visited.
+pub struct FileGlobsMatcher {
+    tree: RepoPathTree,
+    globs: Vec<FileGlob>,
+}
+
+impl FileGlobsMatcher {
+    #[instrument]
+    pub fn new(globs: &[FileGlob]) -> Self {
+        let mut tree = RepoPathTree::new();
+        for glob in globs {
+            tree.add_dir(&glob.base);
+        }
+        FileGlobsMatcher {
+            tree,
+            globs: globs.to_vec(),
+        }
+    }
+}
+
+impl Matcher for FileGlobsMatcher {
+    fn matches(&self, file: &RepoPath) -> bool {
+        self.globs.iter().any(|glob| glob.matches(file))
+    }
+
+    fn visit(&self, dir: &RepoPath) -> Visit {
+        if self.globs.iter().any(|glob| glob.base.contains(dir)) {
+            return Visit::Specific {
+                dirs: VisitDirs::All,
+                files: VisitFiles::All,
+            };
+        }
+        // 'dir' is an ancestor of some base directories
+        self.tree.get_visit_sets(dir)
+    }
+}
+
 /// Matches paths that are matched by the first input matcher but not by the
 /// second.
 pub struct DifferenceMatcher<'input> {
@@ -259,6 +326,55 @@ impl Matcher for IntersectionMatcher<'_> {
     }
 }
 
+/// Matches paths that are matched by any of the input matchers.
+pub struct UnionMatcher<M1, M2> {
+    input1: M1,
+    input2: M2,
+}
+
+impl<M1: Matcher, M2: Matcher> UnionMatcher<M1, M2> {
+    pub fn new(input1: M1, input2: M2) -> Self {
+        Self { input1, input2 }
+    }
+}
+
+impl<M1: Matcher, M2: Matcher> Matcher for UnionMatcher<M1, M2> {
+    fn matches(&self, file: &RepoPath) -> bool {
+        self.input1.matches(file) || self.input2.matches(file)
+    }
+
+    fn visit(&self, dir: &RepoPath) -> Visit {
+        match (self.input1.visit(dir), self.input2.visit(dir)) {
+            (Visit::AllRecursively, _) | (_, Visit::AllRecursively) => Visit::AllRecursively,
+            (Visit::Nothing, visit) | (visit, Visit::Nothing) => visit,
+            (
+                Visit::Specific {
+                    dirs: dirs1,
+                    files: files1,
+                },
+                Visit::Specific {
+                    dirs: dirs2,
+                    files: files2,
+                },
+            ) => {
+                let dirs = match (dirs1, dirs2) {
+                    (VisitDirs::Set(dirs1), VisitDirs::Set(dirs2)) => {
+                        VisitDirs::Set(dirs1.union(&dirs2).cloned().collect())
+                    }
+                    _ => VisitDirs::All,
+                };
+                let files = match (files1, files2) {
+                    (VisitFiles::Set(files1), VisitFiles::Set(files2)) => {
+                        VisitFiles::Set(files1.union(&files2).cloned().collect())
+                    }
+                    _ => VisitFiles::All,
+                };
+                Visit::Specific { dirs, files }
+            }
+        }
+    }
+}
+
 /// Keeps track of which subdirectories and files of each directory need to be
 /// visited.
 #[derive(PartialEq, Eq, Debug)]
@@ -549,6 +665,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fileglobsmatcher() {
+        let new_glob = |base: &str, pattern: &str, case_sensitive| FileGlob {
+            base: RepoPath::from_internal_string(base),
+            pattern: glob::Pattern::new(pattern).unwrap(),
+            case_sensitive,
+        };
+        let m = FileGlobsMatcher::new(&[
+            new_glob("foo", "**/*.rs", true),
+            new_glob("", "*.TXT", false),
+        ]);
+
+        assert!(m.matches(&RepoPath::from_internal_string("foo/a.rs")));
+        assert!(m.matches(&RepoPath::from_internal_string("foo/bar/a.rs")));
+        assert!(!m.matches(&RepoPath::from_internal_string("a.rs")));
+        assert!(!m.matches(&RepoPath::from_internal_string("foo/a.RS")));
+        assert!(m.matches(&RepoPath::from_internal_string("a.txt")));
+        // "*" doesn't match path separators
+        assert!(!m.matches(&RepoPath::from_internal_string("bar/a.txt")));
+
+        assert_eq!(
+            m.visit(&RepoPath::root()),
+            Visit::Specific {
+                dirs: VisitDirs::All,
+                files: VisitFiles::All,
+            }
+        );
+
+        let m = FileGlobsMatcher::new(&[new_glob("foo/bar", "*.rs", true)]);
+        assert_eq!(
+            m.visit(&RepoPath::root()),
+            Visit::sets(hashset! {RepoPathComponent::from("foo")}, hashset! {})
+        );
+        assert_eq!(
+            m.visit(&RepoPath::from_internal_string("baz")),
+            Visit::Nothing
+        );
+        assert_eq!(
+            m.visit(&RepoPath::from_internal_string("foo/bar/baz")),
+            Visit::Specific {
+                dirs: VisitDirs::All,
+                files: VisitFiles::All,
+            }
+        );
+    }
+
     #[test]
     fn test_differencematcher_remove_subdir() {
         let m1 = PrefixMatcher::new(&[
@@ -715,4 +877,34 @@ mod tests {
             Visit::AllRecursively
         );
     }
+
+    #[test]
+    fn test_unionmatcher() {
+        let m1 = PrefixMatcher::new(&[RepoPath::from_internal_string("foo")]);
+        let m2 = FilesMatcher::new(&[
+            RepoPath::from_internal_string("bar/baz"),
+            RepoPath::from_internal_string("qux"),
+        ]);
+        let m = UnionMatcher::new(m1, m2);
+
+        assert!(m.matches(&RepoPath::from_internal_string("foo/bar")));
+        assert!(m.matches(&RepoPath::from_internal_string("bar/baz")));
+        assert!(!m.matches(&RepoPath::from_internal_string("bar/qux")));
+
+        assert_eq!(
+            m.visit(&RepoPath::root()),
+            Visit::sets(
+                hashset! {RepoPathComponent::from("foo"), RepoPathComponent::from("bar")},
+                hashset! {RepoPathComponent::from("foo"), RepoPathComponent::from("qux")}
+            )
+        );
+        assert_eq!(
+            m.visit(&RepoPath::from_internal_string("foo")),
+            Visit::AllRecursively
+        );
+        assert_eq!(
+            m.visit(&RepoPath::from_internal_string("bar")),
+            Visit::sets(hashset! {}, hashset! {RepoPathComponent::from("baz")})
+        );
+    }
 }
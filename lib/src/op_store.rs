@@ -14,7 +14,7 @@
 
 #![allow(missing_docs)]
 
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::{Debug, Error, Formatter};
 use std::iter;
 
@@ -259,27 +259,63 @@ pub struct BranchTarget<'a> {
     pub remote_refs: Vec<(&'a str, &'a RemoteRef)>,
 }
 
-content_hash! {
-    /// Represents the way the repo looks at a given time, just like how a Tree
-    /// object represents how the file system looks at a given time.
-    #[derive(PartialEq, Eq, Clone, Debug, Default)]
-    pub struct View {
-        /// All head commits
-        pub head_ids: HashSet<CommitId>,
-        /// Heads of the set of public commits.
-        pub public_head_ids: HashSet<CommitId>,
-        pub local_branches: BTreeMap<String, RefTarget>,
-        pub tags: BTreeMap<String, RefTarget>,
-        pub remote_views: BTreeMap<String, RemoteView>,
-        pub git_refs: BTreeMap<String, RefTarget>,
-        /// The commit the Git HEAD points to.
-        // TODO: Support multiple Git worktrees?
-        // TODO: Do we want to store the current branch name too?
-        pub git_head: RefTarget,
-        // The commit that *should be* checked out in the workspace. Note that the working copy
-        // (.jj/working_copy/) has the source of truth about which commit *is* checked out (to be
-        // precise: the commit to which we most recently completed an update to).
-        pub wc_commit_ids: HashMap<WorkspaceId, CommitId>,
+/// Represents the way the repo looks at a given time, just like how a Tree
+/// object represents how the file system looks at a given time.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct View {
+    /// All head commits
+    pub head_ids: HashSet<CommitId>,
+    /// Heads of the set of public commits.
+    pub public_head_ids: HashSet<CommitId>,
+    pub local_branches: BTreeMap<String, RefTarget>,
+    /// Descriptions of the local branches by branch name.
+    pub branch_descriptions: BTreeMap<String, String>,
+    pub tags: BTreeMap<String, RefTarget>,
+    pub remote_views: BTreeMap<String, RemoteView>,
+    /// Names of the remote branches to be deleted by the next push, by remote
+    /// name. The local branches of the same names are kept.
+    pub remote_branch_deletions: BTreeMap<String, BTreeSet<String>>,
+    pub git_refs: BTreeMap<String, RefTarget>,
+    /// The commit the Git HEAD points to.
+    // TODO: Support multiple Git worktrees?
+    // TODO: Do we want to store the current branch name too?
+    pub git_head: RefTarget,
+    // The commit that *should be* checked out in the workspace. Note that the working copy
+    // (.jj/working_copy/) has the source of truth about which commit *is* checked out (to be
+    // precise: the commit to which we most recently completed an update to).
+    pub wc_commit_ids: HashMap<WorkspaceId, CommitId>,
+}
+
+impl ContentHash for View {
+    fn hash(&self, state: &mut impl digest::Update) {
+        let View {
+            head_ids,
+            public_head_ids,
+            local_branches,
+            branch_descriptions,
+            tags,
+            remote_views,
+            remote_branch_deletions,
+            git_refs,
+            git_head,
+            wc_commit_ids,
+        } = self;
+        ContentHash::hash(head_ids, state);
+        ContentHash::hash(public_head_ids, state);
+        ContentHash::hash(local_branches, state);
+        ContentHash::hash(tags, state);
+        ContentHash::hash(remote_views, state);
+        ContentHash::hash(git_refs, state);
+        ContentHash::hash(git_head, state);
+        ContentHash::hash(wc_commit_ids, state);
+        // Hashed last and only if present so that views without branch
+        // descriptions keep the ids they had before descriptions existed.
+        if !branch_descriptions.is_empty() {
+            ContentHash::hash(branch_descriptions, state);
+        }
+        if !remote_branch_deletions.is_empty() {
+            ContentHash::hash(remote_branch_deletions, state);
+        }
     }
 }
 
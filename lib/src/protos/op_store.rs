@@ -95,6 +95,12 @@ pub struct Tag {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RemoteBranchDeletions {
+    #[prost(string, repeated, tag = "1")]
+    pub branch_names: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct View {
     #[prost(bytes = "vec", repeated, tag = "1")]
     pub head_ids: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
@@ -110,6 +116,18 @@ pub struct View {
     >,
     #[prost(message, repeated, tag = "5")]
     pub branches: ::prost::alloc::vec::Vec<Branch>,
+    /// Descriptions of the local branches by branch name.
+    #[prost(map = "string, string", tag = "11")]
+    pub branch_descriptions: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    /// Remote branches to be deleted by the next push, by remote name.
+    #[prost(map = "string, message", tag = "12")]
+    pub remote_branch_deletions: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        RemoteBranchDeletions,
+    >,
     #[prost(message, repeated, tag = "6")]
     pub tags: ::prost::alloc::vec::Vec<Tag>,
     /// Only a subset of the refs. For example, does not include refs/notes/.
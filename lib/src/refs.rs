@@ -53,6 +53,18 @@ pub fn diff_named_remote_refs<'a, 'b, K: Ord>(
     .filter(|(_, (ref1, ref2))| ref1 != ref2)
 }
 
+/// Compares `descriptions1` and `descriptions2`, yields entry if they differ.
+/// A missing description is compared as an empty string.
+///
+/// `descriptions1` and `descriptions2` must be sorted by `K`.
+pub fn diff_named_descriptions<'a, 'b, K: Ord>(
+    descriptions1: impl IntoIterator<Item = (K, &'a str)>,
+    descriptions2: impl IntoIterator<Item = (K, &'b str)>,
+) -> impl Iterator<Item = (K, (&'a str, &'b str))> {
+    iter_named_pairs(descriptions1, descriptions2, || "", || "")
+        .filter(|(_, (description1, description2))| description1 != description2)
+}
+
 /// Iterates local `refs1` and remote `refs2` pairs by name.
 ///
 /// `refs1` and `refs2` must be sorted by `K`.
@@ -47,7 +47,8 @@ use crate::op_store::{
 };
 use crate::operation::Operation;
 use crate::refs::{
-    diff_named_ref_targets, diff_named_remote_refs, merge_ref_targets, merge_remote_refs,
+    diff_named_descriptions, diff_named_ref_targets, diff_named_remote_refs, merge_ref_targets,
+    merge_remote_refs,
 };
 use crate::revset::{self, ChangeIdIndex, Revset, RevsetExpression};
 use crate::rewrite::DescendantRebaser;
@@ -1021,6 +1022,36 @@ impl MutableRepo {
         view.set_local_branch_target(name, new_target);
     }
 
+    pub fn get_branch_description(&self, name: &str) -> String {
+        self.view
+            .with_ref(|v| v.get_branch_description(name).to_owned())
+    }
+
+    pub fn set_branch_description(&mut self, name: &str, description: &str) {
+        self.view_mut().set_branch_description(name, description);
+    }
+
+    fn merge_branch_description(&mut self, name: &str, base: &str, other: &str) {
+        let view = self.view.get_mut();
+        if view.get_local_branch(name).is_absent() {
+            return;
+        }
+        let self_description = view.get_branch_description(name);
+        if self_description == base {
+            view.set_branch_description(name, other);
+        } else if self_description != other {
+            // Descriptions can't be conflicted, so keep both if both sides
+            // changed it. They are sorted so that the result doesn't depend on
+            // the order the operations are merged in.
+            let merged = [self_description, other]
+                .into_iter()
+                .filter(|description| !description.is_empty())
+                .sorted()
+                .join("\n\n");
+            view.set_branch_description(name, &merged);
+        }
+    }
+
     pub fn get_remote_branch(&self, name: &str, remote_name: &str) -> RemoteRef {
         self.view
             .with_ref(|v| v.get_remote_branch(name, remote_name).clone())
@@ -1045,6 +1076,23 @@ impl MutableRepo {
         view.set_remote_branch(name, remote_name, new_ref);
     }
 
+    pub fn is_remote_branch_deletion_pending(&self, name: &str, remote_name: &str) -> bool {
+        self.view
+            .with_ref(|v| v.is_remote_branch_deletion_pending(name, remote_name))
+    }
+
+    /// Marks or unmarks the remote branch to be deleted by the next push
+    /// without changing the local branch.
+    pub fn set_remote_branch_deletion_pending(
+        &mut self,
+        name: &str,
+        remote_name: &str,
+        pending: bool,
+    ) {
+        self.view_mut()
+            .set_remote_branch_deletion_pending(name, remote_name, pending);
+    }
+
     /// Merges the specified remote branch in to local branch, and starts
     /// tracking it.
     pub fn track_remote_branch(&mut self, name: &str, remote_name: &str) {
@@ -1181,6 +1229,14 @@ impl MutableRepo {
             self.merge_local_branch(name, base_target, other_target);
         }
 
+        // Merged after the branches so that a branch removed on either side
+        // loses its description.
+        let changed_descriptions =
+            diff_named_descriptions(base.branch_descriptions(), other.branch_descriptions());
+        for (name, (base_description, other_description)) in changed_descriptions {
+            self.merge_branch_description(name, base_description, other_description);
+        }
+
         let changed_tags = diff_named_ref_targets(base.tags(), other.tags());
         for (name, (base_target, other_target)) in changed_tags {
             self.merge_tag(name, base_target, other_target);
@@ -1197,6 +1253,23 @@ impl MutableRepo {
             self.merge_remote_branch(name, remote_name, base_ref, other_ref);
         }
 
+        // Merged after the remote branches so that a deletion isn't kept for
+        // a remote branch that no longer exists.
+        let base_deletions: HashSet<_> = base.remote_branch_deletions().collect();
+        let other_deletions: HashSet<_> = other.remote_branch_deletions().collect();
+        for &(name, remote_name) in base_deletions.difference(&other_deletions) {
+            self.set_remote_branch_deletion_pending(name, remote_name, false);
+        }
+        for &(name, remote_name) in other_deletions.difference(&base_deletions) {
+            if self
+                .view()
+                .get_remote_branch(name, remote_name)
+                .is_present()
+            {
+                self.set_remote_branch_deletion_pending(name, remote_name, true);
+            }
+        }
+
         let new_git_head_target = merge_ref_targets(
             self.index(),
             self.view().git_head(),
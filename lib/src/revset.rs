@@ -36,6 +36,7 @@ use crate::commit::Commit;
 use crate::git;
 use crate::hex_util::to_forward_hex;
 use crate::index::{HexPrefix, PrefixResolution};
+use crate::matchers::FileGlob;
 use crate::op_store::WorkspaceId;
 use crate::repo::Repo;
 use crate::repo_path::{FsPathParseError, RepoPath};
@@ -338,6 +339,8 @@ pub enum RevsetFilterPredicate {
     Committer(StringPattern),
     /// Commits modifying the paths specified by the pattern.
     File(Option<Vec<RepoPath>>), // TODO: embed matcher expression?
+    /// Commits modifying the files matched by any of the glob patterns.
+    FileGlobs(Vec<FileGlob>),
     /// Commits with conflicts
     HasConflict,
 }
@@ -1995,7 +1998,20 @@ fn make_no_such_symbol_error(repo: &dyn Repo, name: impl Into<String>) -> Revset
     branch_names.sort_unstable();
     // Remote branch "x"@"y" may conflict with local "x@y" in unquoted form.
     branch_names.dedup();
-    let candidates = collect_similar(&name, &branch_names);
+    let mut candidates = collect_similar(&name, &branch_names);
+    if let Some((branch_name, _)) = name.rsplit_once('@') {
+        // The branch may exist on other remotes even if the remote names
+        // aren't similar.
+        let prefix = format!("{branch_name}@");
+        candidates.extend(
+            branch_names
+                .iter()
+                .filter(|symbol| symbol.starts_with(&prefix))
+                .cloned(),
+        );
+        candidates.sort_unstable();
+        candidates.dedup();
+    }
     RevsetResolutionError::NoSuchRevision { name, candidates }
 }
 
@@ -2400,6 +2416,11 @@ pub trait Revset<'index>: fmt::Debug {
     fn is_empty(&self) -> bool;
 
     fn count(&self) -> usize;
+
+    /// Returns a function that tests whether the given commit is included in
+    /// the revset. Membership is resolved lazily, so the function may be
+    /// called with commits in any order.
+    fn containing_fn(&self) -> Box<dyn Fn(&CommitId) -> bool + '_>;
 }
 
 pub trait ChangeIdIndex: Send + Sync {
@@ -264,6 +264,21 @@ fn view_to_proto(view: &View) -> crate::protos::op_store::View {
     }
 
     proto.branches = branch_views_to_proto_legacy(&view.local_branches, &view.remote_views);
+    proto.branch_descriptions = view
+        .branch_descriptions
+        .iter()
+        .map(|(name, description)| (name.clone(), description.clone()))
+        .collect();
+    proto.remote_branch_deletions = view
+        .remote_branch_deletions
+        .iter()
+        .map(|(remote_name, branch_names)| {
+            let deletions = crate::protos::op_store::RemoteBranchDeletions {
+                branch_names: branch_names.iter().cloned().collect(),
+            };
+            (remote_name.clone(), deletions)
+        })
+        .collect();
 
     for (name, target) in &view.tags {
         proto.tags.push(crate::protos::op_store::Tag {
@@ -308,6 +323,12 @@ fn view_from_proto(proto: crate::protos::op_store::View) -> View {
     let (local_branches, remote_views) = branch_views_from_proto_legacy(proto.branches);
     view.local_branches = local_branches;
     view.remote_views = remote_views;
+    view.branch_descriptions = proto.branch_descriptions.into_iter().collect();
+    view.remote_branch_deletions = proto
+        .remote_branch_deletions
+        .into_iter()
+        .map(|(remote_name, deletions)| (remote_name, deletions.branch_names.into_iter().collect()))
+        .collect();
 
     for tag_proto in proto.tags {
         view.tags
@@ -533,7 +554,7 @@ fn remote_ref_state_from_proto(proto_value: Option<i32>) -> Option<RemoteRefStat
 mod tests {
     use insta::assert_snapshot;
     use itertools::Itertools as _;
-    use maplit::{btreemap, hashmap, hashset};
+    use maplit::{btreemap, btreeset, hashmap, hashset};
 
     use super::*;
     use crate::backend::{CommitId, MillisSinceEpoch, ObjectId, Timestamp};
@@ -570,6 +591,9 @@ mod tests {
             local_branches: btreemap! {
                 "main".to_string() => branch_main_local_target,
             },
+            branch_descriptions: btreemap! {
+                "main".to_string() => "The main branch".to_string(),
+            },
             tags: btreemap! {
                 "v1.0".to_string() => tag_v1_target,
             },
@@ -581,6 +605,9 @@ mod tests {
                     },
                 },
             },
+            remote_branch_deletions: btreemap! {
+                "origin".to_string() => btreeset! {"main".to_string()},
+            },
             git_refs: btreemap! {
                 "refs/heads/main".to_string() => git_refs_main_target,
                 "refs/heads/feature".to_string() => git_refs_feature_target,
@@ -625,6 +652,17 @@ mod tests {
         // Test exact output so we detect regressions in compatibility
         assert_snapshot!(
             ViewId::new(blake2b_hash(&create_view()).to_vec()).hex(),
+            @"cea044e0af0ad09dcfabcf2d231efbf96d1bb9dff37a16c50030cf88d4937e7a1baada3783a3aee2469d2c32ca68f89416da9305c2ad9fa93c5b9144b61ab93e"
+        );
+        // Views without branch descriptions or remote branch deletions hash
+        // the same as before these fields were added
+        let view = View {
+            branch_descriptions: BTreeMap::new(),
+            remote_branch_deletions: BTreeMap::new(),
+            ..create_view()
+        };
+        assert_snapshot!(
+            ViewId::new(blake2b_hash(&view).to_vec()).hex(),
             @"7a7d8e33aff631bc3a8a281358e818f3c962d539ec2ced78a40b8221a42a707d51e546c5a6644c435b5764d8a51b29e63c3c107d5a8926d4be74288ea8ac879d"
         );
     }
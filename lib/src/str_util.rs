@@ -30,11 +30,14 @@ pub enum StringPatternParseError {
     /// Failed to parse glob pattern.
     #[error(transparent)]
     GlobPattern(glob::PatternError),
+    /// Failed to parse regular expression.
+    #[error(transparent)]
+    Regex(regex::Error),
 }
 
 /// Pattern to be tested against string property like commit description or
 /// branch name.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub enum StringPattern {
     /// Matches strings exactly equal to `string`.
     Exact(String),
@@ -42,6 +45,8 @@ pub enum StringPattern {
     Glob(glob::Pattern),
     /// Matches strings that contain `substring`.
     Substring(String),
+    /// Matches strings that contain a match of the regular expression.
+    Regex(regex::Regex),
 }
 
 impl StringPattern {
@@ -64,12 +69,19 @@ impl StringPattern {
         Ok(StringPattern::Glob(pattern))
     }
 
+    /// Parses the given string as regular expression pattern.
+    pub fn regex(src: &str) -> Result<Self, StringPatternParseError> {
+        let pattern = regex::Regex::new(src).map_err(StringPatternParseError::Regex)?;
+        Ok(StringPattern::Regex(pattern))
+    }
+
     /// Parses the given string as pattern of the specified `kind`.
     pub fn from_str_kind(src: &str, kind: &str) -> Result<Self, StringPatternParseError> {
         match kind {
             "exact" => Ok(StringPattern::exact(src)),
             "glob" => StringPattern::glob(src),
             "substring" => Ok(StringPattern::Substring(src.to_owned())),
+            "regex" => StringPattern::regex(src),
             _ => Err(StringPatternParseError::InvalidKind(kind.to_owned())),
         }
     }
@@ -85,7 +97,7 @@ impl StringPattern {
     pub fn as_exact(&self) -> Option<&str> {
         match self {
             StringPattern::Exact(literal) => Some(literal),
-            StringPattern::Glob(_) | StringPattern::Substring(_) => None,
+            StringPattern::Glob(_) | StringPattern::Substring(_) | StringPattern::Regex(_) => None,
         }
     }
 
@@ -95,13 +107,13 @@ impl StringPattern {
             StringPattern::Exact(literal) => literal,
             StringPattern::Glob(pattern) => pattern.as_str(),
             StringPattern::Substring(needle) => needle,
+            StringPattern::Regex(pattern) => pattern.as_str(),
         }
     }
 
     /// Converts this pattern to a glob string. Returns `None` if the pattern
     /// can't be represented as a glob.
     pub fn to_glob(&self) -> Option<Cow<'_, str>> {
-        // TODO: If we add Regex pattern, it will return None.
         match self {
             StringPattern::Exact(literal) => Some(glob::Pattern::escape(literal).into()),
             StringPattern::Glob(pattern) => Some(pattern.as_str().into()),
@@ -109,6 +121,7 @@ impl StringPattern {
             StringPattern::Substring(needle) => {
                 Some(format!("*{}*", glob::Pattern::escape(needle)).into())
             }
+            StringPattern::Regex(_) => None,
         }
     }
 
@@ -118,6 +131,7 @@ impl StringPattern {
             StringPattern::Exact(literal) => haystack == literal,
             StringPattern::Glob(pattern) => pattern.matches(haystack),
             StringPattern::Substring(needle) => haystack.contains(needle),
+            StringPattern::Regex(pattern) => pattern.is_match(haystack),
         }
     }
 
@@ -134,6 +148,22 @@ impl StringPattern {
     }
 }
 
+// regex::Regex doesn't implement PartialEq, so patterns are compared by kind
+// and source string.
+impl PartialEq for StringPattern {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (StringPattern::Exact(a), StringPattern::Exact(b)) => a == b,
+            (StringPattern::Glob(a), StringPattern::Glob(b)) => a == b,
+            (StringPattern::Substring(a), StringPattern::Substring(b)) => a == b,
+            (StringPattern::Regex(a), StringPattern::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for StringPattern {}
+
 impl fmt::Display for StringPattern {
     /// Shows the original string of this pattern.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -162,5 +192,16 @@ mod tests {
             StringPattern::Substring("*".into()).to_glob(),
             Some("*[*]*".into())
         );
+        assert_eq!(StringPattern::regex("^a").unwrap().to_glob(), None);
+    }
+
+    #[test]
+    fn test_string_pattern_regex() {
+        let pattern = StringPattern::from_str_kind(r"^release-\d+\.\d+$", "regex").unwrap();
+        assert!(pattern.matches("release-1.2"));
+        assert!(!pattern.matches("release-1.2-rc"));
+        assert!(!pattern.is_exact());
+        assert_eq!(pattern.as_str(), r"^release-\d+\.\d+$");
+        assert!(StringPattern::regex("(").is_err());
     }
 }
@@ -121,9 +121,14 @@ impl View {
     // TODO: maybe rename to forget_branch() because this seems unusual operation?
     pub fn remove_branch(&mut self, name: &str) {
         self.data.local_branches.remove(name);
+        self.data.branch_descriptions.remove(name);
         for remote_view in self.data.remote_views.values_mut() {
             remote_view.branches.remove(name);
         }
+        self.data.remote_branch_deletions.retain(|_, names| {
+            names.remove(name);
+            !names.is_empty()
+        });
     }
 
     /// Iterates local branch `(name, target)`s in lexicographical order.
@@ -151,11 +156,42 @@ impl View {
 
     /// Sets local branch to point to the given target. If the target is absent,
     /// and if no associated remote branches exist, the branch will be removed.
+    /// The description of a removed branch is removed too.
     pub fn set_local_branch_target(&mut self, name: &str, target: RefTarget) {
         if target.is_present() {
             self.data.local_branches.insert(name.to_owned(), target);
         } else {
             self.data.local_branches.remove(name);
+            self.data.branch_descriptions.remove(name);
+        }
+    }
+
+    /// Iterates local branch `(name, description)`s in lexicographical order.
+    pub fn branch_descriptions(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.data
+            .branch_descriptions
+            .iter()
+            .map(|(name, description)| (name.as_ref(), description.as_ref()))
+    }
+
+    /// Returns the description of the local branch, or an empty string if it
+    /// has none.
+    pub fn get_branch_description(&self, name: &str) -> &str {
+        self.data
+            .branch_descriptions
+            .get(name)
+            .map_or("", |description| description)
+    }
+
+    /// Sets the description of the local branch. If the description is empty,
+    /// it will be removed.
+    pub fn set_branch_description(&mut self, name: &str, description: &str) {
+        if description.is_empty() {
+            self.data.branch_descriptions.remove(name);
+        } else {
+            self.data
+                .branch_descriptions
+                .insert(name.to_owned(), description.to_owned());
         }
     }
 
@@ -210,7 +246,8 @@ impl View {
     }
 
     /// Sets remote-tracking branch to the given target and state. If the target
-    /// is absent, the branch will be removed.
+    /// is absent, the branch will be removed, and so will be the pending
+    /// deletion of it.
     pub fn set_remote_branch(&mut self, name: &str, remote_name: &str, remote_ref: RemoteRef) {
         if remote_ref.is_present() {
             let remote_view = self
@@ -219,8 +256,55 @@ impl View {
                 .entry(remote_name.to_owned())
                 .or_default();
             remote_view.branches.insert(name.to_owned(), remote_ref);
-        } else if let Some(remote_view) = self.data.remote_views.get_mut(remote_name) {
-            remote_view.branches.remove(name);
+        } else {
+            if let Some(remote_view) = self.data.remote_views.get_mut(remote_name) {
+                remote_view.branches.remove(name);
+            }
+            self.set_remote_branch_deletion_pending(name, remote_name, false);
+        }
+    }
+
+    /// Iterates remote branch `(name, remote_name)`s to be deleted by the next
+    /// push. Entries are sorted by `(remote_name, name)`.
+    pub fn remote_branch_deletions(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.data
+            .remote_branch_deletions
+            .iter()
+            .flat_map(|(remote_name, names)| {
+                names
+                    .iter()
+                    .map(|name| (name.as_ref(), remote_name.as_ref()))
+            })
+    }
+
+    /// Returns true if the remote branch is to be deleted by the next push
+    /// while the local branch is kept.
+    pub fn is_remote_branch_deletion_pending(&self, name: &str, remote_name: &str) -> bool {
+        self.data
+            .remote_branch_deletions
+            .get(remote_name)
+            .is_some_and(|names| names.contains(name))
+    }
+
+    /// Marks or unmarks the remote branch to be deleted by the next push. The
+    /// local branch and the remote-tracking branch are left unchanged.
+    pub fn set_remote_branch_deletion_pending(
+        &mut self,
+        name: &str,
+        remote_name: &str,
+        pending: bool,
+    ) {
+        if pending {
+            self.data
+                .remote_branch_deletions
+                .entry(remote_name.to_owned())
+                .or_default()
+                .insert(name.to_owned());
+        } else if let Some(names) = self.data.remote_branch_deletions.get_mut(remote_name) {
+            names.remove(name);
+            if names.is_empty() {
+                self.data.remote_branch_deletions.remove(remote_name);
+            }
         }
     }
 
@@ -269,12 +353,18 @@ impl View {
 
     pub fn remove_remote(&mut self, remote_name: &str) {
         self.data.remote_views.remove(remote_name);
+        self.data.remote_branch_deletions.remove(remote_name);
     }
 
     pub fn rename_remote(&mut self, old: &str, new: &str) {
         if let Some(remote_view) = self.data.remote_views.remove(old) {
             self.data.remote_views.insert(new.to_owned(), remote_view);
         }
+        if let Some(names) = self.data.remote_branch_deletions.remove(old) {
+            self.data
+                .remote_branch_deletions
+                .insert(new.to_owned(), names);
+        }
     }
 
     pub fn get_tag(&self, name: &str) -> &RefTarget {
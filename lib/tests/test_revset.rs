@@ -18,6 +18,7 @@
 #![allow(clippy::items_after_test_module)]
 
 use std::path::Path;
+use std::sync::Arc;
 
 use assert_matches::assert_matches;
 use itertools::Itertools;
@@ -27,17 +28,22 @@ use jj_lib::git;
 use jj_lib::git_backend::GitBackend;
 use jj_lib::index::{HexPrefix, PrefixResolution};
 use jj_lib::op_store::{RefTarget, RemoteRef, RemoteRefState, WorkspaceId};
-use jj_lib::repo::Repo;
+use jj_lib::repo::{ReadonlyRepo, Repo};
 use jj_lib::repo_path::RepoPath;
 use jj_lib::revset::{
     optimize, parse, DefaultSymbolResolver, ResolvedExpression, Revset, RevsetAliasesMap,
-    RevsetExpression, RevsetFilterPredicate, RevsetParseContext, RevsetResolutionError,
-    RevsetWorkspaceContext,
+    RevsetExpression, RevsetFilterPredicate, RevsetIteratorExt as _, RevsetParseContext,
+    RevsetResolutionError, RevsetWorkspaceContext,
+};
+use jj_lib::revset_graph::{
+    ReverseRevsetGraphIterator, RevsetGraphEdge, TopoGroupedRevsetGraphIterator,
 };
-use jj_lib::revset_graph::{ReverseRevsetGraphIterator, RevsetGraphEdge};
 use jj_lib::settings::GitSettings;
+use jj_lib::store::Store;
 use jj_lib::workspace::Workspace;
 use test_case::test_case;
+use testutils::counting_backend::CountingBackend;
+use testutils::test_backend::TestBackend;
 use testutils::{
     create_random_commit, create_tree, write_random_commit, CommitGraphBuilder, TestRepo,
     TestRepoBackend, TestWorkspace,
@@ -2713,6 +2719,110 @@ fn test_evaluate_expression_conflict() {
     );
 }
 
+/// Loads the repo's store with a backend that counts the commits read, so that
+/// no commits are cached.
+fn counting_store(repo: &Arc<ReadonlyRepo>) -> Arc<Store> {
+    let backend = TestBackend::load(&repo.repo_path().join("store"));
+    Store::new(Box::new(CountingBackend::new(Box::new(backend))), false)
+}
+
+fn read_commit_count(store: &Store) -> usize {
+    store
+        .backend_impl()
+        .downcast_ref::<CountingBackend>()
+        .unwrap()
+        .take_read_commit_count()
+}
+
+#[test_case(false; "forward")]
+#[test_case(true; "reversed")]
+fn test_evaluate_revset_reads_commits_lazily(reversed: bool) {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings, "test");
+    let mut graph_builder = CommitGraphBuilder::new(&settings, tx.mut_repo());
+    let mut commit = graph_builder.initial_commit();
+    for _ in 0..9 {
+        commit = graph_builder.commit_with_parents(&[&commit]);
+    }
+    let repo = tx.commit();
+
+    // Only the commits taken from the revset are read, like `jj log --limit
+    // --no-graph` does.
+    let store = counting_store(&repo);
+    let symbol_resolver = DefaultSymbolResolver::new(repo.as_ref());
+    let revset = RevsetExpression::visible_heads()
+        .ancestors()
+        .resolve_user_expression(repo.as_ref(), &symbol_resolver)
+        .unwrap()
+        .evaluate(repo.as_ref())
+        .unwrap();
+    assert_eq!(revset.iter().count(), 11);
+    assert_eq!(read_commit_count(&store), 0);
+    let iter: Box<dyn Iterator<Item = CommitId>> = if reversed {
+        Box::new(revset.iter().reversed())
+    } else {
+        Box::new(revset.iter())
+    };
+    let commits: Vec<_> = iter.commits(&store).take(3).try_collect().unwrap();
+    assert_eq!(commits.len(), 3);
+    assert_eq!(read_commit_count(&store), 3);
+}
+
+#[test_case(false; "forward")]
+#[test_case(true; "reversed")]
+fn test_evaluate_revset_graph_reads_commits_lazily(reversed: bool) {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // Two interleaved branches, so the topological grouping reorders them
+    let mut tx = repo.start_transaction(&settings, "test");
+    let mut graph_builder = CommitGraphBuilder::new(&settings, tx.mut_repo());
+    let base = graph_builder.initial_commit();
+    let mut branch1 = vec![base.clone()];
+    let mut branch2 = vec![base.clone()];
+    for _ in 0..5 {
+        branch1.push(graph_builder.commit_with_parents(&[branch1.last().unwrap()]));
+        branch2.push(graph_builder.commit_with_parents(&[branch2.last().unwrap()]));
+    }
+    let repo = tx.commit();
+
+    // Like `jj log --limit`, the graph is walked in the index, and only the
+    // commits taken from it are read.
+    let store = counting_store(&repo);
+    let symbol_resolver = DefaultSymbolResolver::new(repo.as_ref());
+    let revset = RevsetExpression::visible_heads()
+        .ancestors()
+        .resolve_user_expression(repo.as_ref(), &symbol_resolver)
+        .unwrap()
+        .evaluate(repo.as_ref())
+        .unwrap();
+    let forward_iter = TopoGroupedRevsetGraphIterator::new(revset.iter_graph());
+    let iter: Box<dyn Iterator<Item = _>> = if reversed {
+        Box::new(ReverseRevsetGraphIterator::new(forward_iter))
+    } else {
+        Box::new(forward_iter)
+    };
+    let commits: Vec<_> = iter
+        .take(3)
+        .map(|(commit_id, _edges)| store.get_commit(&commit_id))
+        .try_collect()
+        .unwrap();
+    assert_eq!(read_commit_count(&store), 3);
+    let expected_ids = if reversed {
+        vec![repo.store().root_commit_id(), base.id(), branch1[1].id()]
+    } else {
+        vec![branch2[5].id(), branch2[4].id(), branch2[3].id()]
+    };
+    assert_eq!(
+        commits.iter().map(|commit| commit.id()).collect_vec(),
+        expected_ids
+    );
+}
+
 #[test]
 fn test_reverse_graph_iterator() {
     let settings = testutils::user_settings();
@@ -14,6 +14,7 @@
 
 use std::collections::BTreeMap;
 
+use itertools::Itertools as _;
 use jj_lib::op_store::{BranchTarget, RefTarget, RemoteRef, RemoteRefState, WorkspaceId};
 use jj_lib::repo::Repo;
 use maplit::{btreemap, hashset};
@@ -360,6 +361,118 @@ fn test_merge_views_tags() {
     );
 }
 
+#[test_case(false; "tx1 first")]
+#[test_case(true; "tx2 first")]
+fn test_merge_views_branch_descriptions(reversed: bool) {
+    // Tests merging of branch descriptions (by performing concurrent
+    // operations). The result shouldn't depend on the merge order.
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings, "test");
+    let mut_repo = tx.mut_repo();
+    let commit = write_random_commit(mut_repo, &settings);
+    for name in ["both", "one", "deleted", "untouched"] {
+        mut_repo.set_local_branch_target(name, RefTarget::normal(commit.id().clone()));
+        mut_repo.set_branch_description(name, &format!("{name} tx0"));
+    }
+    let repo = tx.commit();
+
+    let mut tx1 = repo.start_transaction(&settings, "test");
+    tx1.mut_repo().set_branch_description("both", "both tx1");
+    tx1.mut_repo().set_branch_description("one", "one tx1");
+    tx1.mut_repo()
+        .set_branch_description("deleted", "deleted tx1");
+
+    let mut tx2 = repo.start_transaction(&settings, "test");
+    tx2.mut_repo().set_branch_description("both", "both tx2");
+    tx2.mut_repo()
+        .set_local_branch_target("deleted", RefTarget::absent());
+
+    let txs = if reversed {
+        vec![tx2, tx1]
+    } else {
+        vec![tx1, tx2]
+    };
+    let repo = commit_transactions(&settings, txs);
+    // The description of a deleted branch is removed even if it changed
+    // concurrently. If both sides changed a description, both are kept.
+    let descriptions = repo.view().branch_descriptions().collect_vec();
+    assert_eq!(descriptions.len(), 3, "{descriptions:?}");
+    assert_eq!(
+        repo.view().get_branch_description("both"),
+        "both tx1\n\nboth tx2"
+    );
+    assert_eq!(repo.view().get_branch_description("one"), "one tx1");
+    assert_eq!(
+        repo.view().get_branch_description("untouched"),
+        "untouched tx0"
+    );
+    assert_eq!(repo.view().get_branch_description("deleted"), "");
+}
+
+#[test_case(false; "tx1 first")]
+#[test_case(true; "tx2 first")]
+fn test_merge_views_remote_branch_deletions(reversed: bool) {
+    // Tests merging of pending remote branch deletions (by performing
+    // concurrent operations). The result shouldn't depend on the merge order.
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings, "test");
+    let mut_repo = tx.mut_repo();
+    let commit = write_random_commit(mut_repo, &settings);
+    for name in ["added", "unmarked", "fetched", "added-fetched", "untouched"] {
+        let target = RefTarget::normal(commit.id().clone());
+        mut_repo.set_local_branch_target(name, target.clone());
+        let remote_ref = RemoteRef {
+            target,
+            state: RemoteRefState::Tracking,
+        };
+        mut_repo.set_remote_branch(name, "origin", remote_ref);
+    }
+    for name in ["unmarked", "fetched", "untouched"] {
+        mut_repo.set_remote_branch_deletion_pending(name, "origin", true);
+    }
+    let repo = tx.commit();
+
+    let mut tx1 = repo.start_transaction(&settings, "test");
+    tx1.mut_repo()
+        .set_remote_branch_deletion_pending("added", "origin", true);
+    tx1.mut_repo()
+        .set_remote_branch_deletion_pending("unmarked", "origin", false);
+    tx1.mut_repo()
+        .set_remote_branch_deletion_pending("added-fetched", "origin", true);
+
+    // The remote branches were deleted by e.g. fetch.
+    let mut tx2 = repo.start_transaction(&settings, "test");
+    tx2.mut_repo()
+        .set_remote_branch("fetched", "origin", RemoteRef::absent());
+    tx2.mut_repo()
+        .set_remote_branch("added-fetched", "origin", RemoteRef::absent());
+
+    let txs = if reversed {
+        vec![tx2, tx1]
+    } else {
+        vec![tx1, tx2]
+    };
+    let repo = commit_transactions(&settings, txs);
+    // A deletion is dropped if the remote branch no longer exists. The local
+    // branches are left unchanged.
+    assert_eq!(
+        repo.view().remote_branch_deletions().collect_vec(),
+        vec![("added", "origin"), ("untouched", "origin")]
+    );
+    assert_eq!(
+        repo.view().local_branches().count(),
+        5,
+        "{:?}",
+        repo.view().local_branches().collect_vec()
+    );
+}
+
 #[test]
 fn test_merge_views_git_refs() {
     // Tests merging of git refs (by performing concurrent operations). See
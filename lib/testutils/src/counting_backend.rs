@@ -0,0 +1,120 @@
+// Copyright 2023 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+use jj_lib::backend::{
+    self, Backend, BackendResult, ChangeId, CommitId, Conflict, ConflictId, FileId, SymlinkId,
+    Tree, TreeId,
+};
+use jj_lib::repo_path::RepoPath;
+
+/// Backend that counts the commits read through it.
+#[derive(Debug)]
+pub struct CountingBackend {
+    inner: Box<dyn Backend>,
+    read_commit_count: AtomicUsize,
+}
+
+impl CountingBackend {
+    pub fn new(inner: Box<dyn Backend>) -> Self {
+        CountingBackend {
+            inner,
+            read_commit_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of commits read since the last call.
+    pub fn take_read_commit_count(&self) -> usize {
+        self.read_commit_count.swap(0, Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl Backend for CountingBackend {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn commit_id_length(&self) -> usize {
+        self.inner.commit_id_length()
+    }
+
+    fn change_id_length(&self) -> usize {
+        self.inner.change_id_length()
+    }
+
+    fn root_commit_id(&self) -> &CommitId {
+        self.inner.root_commit_id()
+    }
+
+    fn root_change_id(&self) -> &ChangeId {
+        self.inner.root_change_id()
+    }
+
+    fn empty_tree_id(&self) -> &TreeId {
+        self.inner.empty_tree_id()
+    }
+
+    async fn read_file(&self, path: &RepoPath, id: &FileId) -> BackendResult<Box<dyn Read>> {
+        self.inner.read_file(path, id).await
+    }
+
+    fn write_file(&self, path: &RepoPath, contents: &mut dyn Read) -> BackendResult<FileId> {
+        self.inner.write_file(path, contents)
+    }
+
+    async fn read_symlink(&self, path: &RepoPath, id: &SymlinkId) -> BackendResult<String> {
+        self.inner.read_symlink(path, id).await
+    }
+
+    fn write_symlink(&self, path: &RepoPath, target: &str) -> BackendResult<SymlinkId> {
+        self.inner.write_symlink(path, target)
+    }
+
+    async fn read_tree(&self, path: &RepoPath, id: &TreeId) -> BackendResult<Tree> {
+        self.inner.read_tree(path, id).await
+    }
+
+    fn write_tree(&self, path: &RepoPath, contents: &Tree) -> BackendResult<TreeId> {
+        self.inner.write_tree(path, contents)
+    }
+
+    fn read_conflict(&self, path: &RepoPath, id: &ConflictId) -> BackendResult<Conflict> {
+        self.inner.read_conflict(path, id)
+    }
+
+    fn write_conflict(&self, path: &RepoPath, contents: &Conflict) -> BackendResult<ConflictId> {
+        self.inner.write_conflict(path, contents)
+    }
+
+    async fn read_commit(&self, id: &CommitId) -> BackendResult<backend::Commit> {
+        self.read_commit_count.fetch_add(1, Ordering::Relaxed);
+        self.inner.read_commit(id).await
+    }
+
+    fn write_commit(
+        &self,
+        contents: backend::Commit,
+    ) -> BackendResult<(CommitId, backend::Commit)> {
+        self.inner.write_commit(contents)
+    }
+}
@@ -41,6 +41,7 @@ use tempfile::TempDir;
 
 use crate::test_backend::TestBackend;
 
+pub mod counting_backend;
 pub mod test_backend;
 
 pub fn hermetic_libgit2() {